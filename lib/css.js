@@ -98,7 +98,7 @@ function parseFont(str){
   return cache.font[str]
 }
 
-function parseSize(str, emSize=16){
+function parseSize(str, emSize=16, remSize=emSize){
   if (m = numSizeRE.exec(str)){
     let [size, unit] = [parseFloat(m[1]), m[2]]
     return size * (unit == 'px' ? 1
@@ -109,7 +109,8 @@ function parseSize(str, emSize=16){
                 :  unit == 'cm' ? 96.0 / 2.54
                 :  unit == 'mm' ? 96.0 / 25.4
                 :  unit == 'q' ? 96 / 25.4 / 4
-                :  unit.match('r?em') ? emSize
+                :  unit == 'rem' ? remSize
+                :  unit == 'em' ? emSize
                 :  NaN )
   }
 
@@ -159,6 +160,20 @@ function parseVariant(str){
   return cache.variant[str];
 }
 
+function parseVariantSubset(str, subset, standalone=['normal']){
+  let tokens = splitBy(String(str).trim(), /\s+/)
+  if (tokens.length==1 && standalone.includes(tokens[0])) return tokens[0]
+
+  // each keyword may appear once and at most one value may be chosen from each group
+  let groups = subset.map(group => tokens.filter(t => group.includes(t)))
+  if (groups.some(matches => matches.length > 1)) return null
+  if (groups.flat().length != tokens.length) return null
+  return groups.flat().join(' ')
+}
+
+const parseVariantNumeric = str => parseVariantSubset(str, numericVariants)
+const parseVariantLigatures = str => parseVariantSubset(str, ligatureVariants, ['normal', 'none'])
+
 // -- Window Types -----------------------------------------------------------------------
 let cursorTypes = [
   "default", "crosshair", "hand", "arrow", "move", "text", "wait", "help", "progress", "not-allowed", "context-menu",
@@ -200,12 +215,21 @@ function parseCornerRadii(r){
 
 var plainFilterRE = /(blur|hue-rotate|brightness|contrast|grayscale|invert|opacity|saturate|sepia)\((.*?)\)/,
     shadowFilterRE = /drop-shadow\((.*)\)/,
+    matrixFilterRE = /^matrix\(([\s\S]*)\)$/,
     percentValueRE = /^(\+|-)?\d+%$/,
     angleValueRE = /([\d\.]+)(deg|g?rad|turn)/;
 
-function parseFilter(str){
+// lengths may be signed, unitless (and treated as px), or relative to the current font size
+function parseLength(str, emSize=16, remSize=16){
+  let [sign, len] = [str.trim()[0]=='-' ? -1 : 1, str.trim().replace(/^[+-]/, '')]
+  return sign * (/^[\d\.]+$/.test(len) ? parseFloat(len) : parseSize(len, emSize, remSize))
+}
+
+function parseFilter(str, emSize=16, remSize=16){
   let filters = {}
   let canonical = []
+  let lengthOf = s => parseLength(s, emSize, remSize)
+  let radiusOf = s => (r => r >= 0 ? r : NaN)(lengthOf(s)) // blur radii can't be negative
 
   for (var spec of splitBy(str, /\s+/) || []){
     if (m = shadowFilterRE.exec(spec)){
@@ -213,14 +237,22 @@ function parseFilter(str){
           args = m[1].trim().split(/\s+/),
           lengths = args.slice(0,3),
           color = args.slice(3).join(' '),
-          dims = lengths.map(s => parseSize(s)).filter(isFinite);
+          dims = [lengthOf(lengths[0]), lengthOf(lengths[1]), radiusOf(lengths[2])].filter(isFinite);
       if (dims.length==3 && !!color){
         filters[kind] = [...dims, color]
         canonical.push(`${kind}(${lengths.join(' ')} ${color.replace(/ /g,'')})`)
       }
+    }else if (m = matrixFilterRE.exec(spec)){
+      let kind = 'matrix',
+          args = m[1].trim().split(/[\s,]+/),
+          values = args.map(parseFloat).filter(isFinite);
+      if (values.length==20 && args.length==20){
+        filters[kind] = values
+        canonical.push(`${kind}(${values.join(' ')})`)
+      }
     }else if (m = plainFilterRE.exec(spec)){
       let [kind, arg] = m.slice(1)
-      let val = kind=='blur' ? parseSize(arg)
+      let val = kind=='blur' ? radiusOf(arg)
               : kind=='hue-rotate' ? parseAngle(arg)
               : parsePercentage(arg);
       if (isFinite(val)){
@@ -277,6 +309,7 @@ const featureMap = {
   "normal": [],
 
   // font-variant-ligatures
+  "none": ["-liga", "-clig", "-dlig", "-hlig", "-calt"],
   "common-ligatures": ["liga", "clig"],
   "no-common-ligatures": ["-liga", "-clig"],
   "discretionary-ligatures": ["dlig"],
@@ -323,6 +356,21 @@ const featureMap = {
   "historical-forms": ["hist"],
 }
 
+const numericVariants = [
+  ["lining-nums", "oldstyle-nums"],
+  ["proportional-nums", "tabular-nums"],
+  ["diagonal-fractions", "stacked-fractions"],
+  ["ordinal"],
+  ["slashed-zero"],
+]
+
+const ligatureVariants = [
+  ["common-ligatures", "no-common-ligatures"],
+  ["discretionary-ligatures", "no-discretionary-ligatures"],
+  ["historical-ligatures", "no-historical-ligatures"],
+  ["contextual", "no-contextual"],
+]
+
 const alternatesMap = {
   "stylistic": "salt #",
   "styleset": "ss##",
@@ -336,7 +384,12 @@ const alternatesMap = {
 module.exports = {
   font:parseFont,
   variant:parseVariant,
+  variantNumeric:parseVariantNumeric,
+  numericVariants:numericVariants.flat(),
+  variantLigatures:parseVariantLigatures,
+  ligatureVariants:[...ligatureVariants.flat(), 'none'],
   size:parseSize,
+  length:parseLength,
   filter:parseFilter,
   cursor:parseCursor,
   fit:parseFit,
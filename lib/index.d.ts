@@ -4,8 +4,16 @@
 export class DOMMatrix extends globalThis.DOMMatrix {}
 export class DOMPoint extends globalThis.DOMPoint {}
 export class DOMRect extends globalThis.DOMRect {}
-export class CanvasGradient extends globalThis.CanvasGradient {}
-export class CanvasPattern extends globalThis.CanvasPattern {}
+export class CanvasGradient extends globalThis.CanvasGradient {
+  getColorStops(): {offset: number, color: string}[]
+  cloneWithGeometry(style: "linear", x0: number, y0: number, x1: number, y1: number): CanvasGradient
+  cloneWithGeometry(style: "radial", x0: number, y0: number, r0: number, x1: number, y1: number, r1: number): CanvasGradient
+  cloneWithGeometry(style: "conic", startAngle: number, x: number, y: number): CanvasGradient
+}
+export class CanvasPattern extends globalThis.CanvasPattern {
+  setTransform(transform?: DOMMatrix2DInit | ArrayLike<number>): void
+  setTransform(a: number, b: number, c: number, d: number, e: number, f: number): void
+}
 export class CanvasTexture {}
 
 //
@@ -23,7 +31,7 @@ export class Image extends globalThis.Image {
 // Canvas
 //
 
-export type ExportFormat = "png" | "jpg" | "jpeg" | "pdf" | "svg";
+export type ExportFormat = "png" | "jpg" | "jpeg" | "webp" | "jxl" | "pdf" | "svg";
 
 export interface RenderOptions {
   /** Page to export: Defaults to 1 (i.e., first page) */
@@ -40,6 +48,9 @@ export interface RenderOptions {
 
   /** Convert text to paths for SVG exports */
   outline?: boolean
+
+  /** Use lossless compression for JPEG XL exports (ignoring `quality`) */
+  lossless?: boolean
 }
 
 export interface SaveOptions extends RenderOptions {
@@ -47,10 +58,15 @@ export interface SaveOptions extends RenderOptions {
   format?: ExportFormat
 }
 
+export interface ContextAttributes extends CanvasRenderingContext2DSettings {
+  measureTextCache?: number | boolean
+}
+
 export class Canvas {
   /** @internal */
   constructor(width?: number, height?: number)
   static contexts: WeakMap<Canvas, readonly CanvasRenderingContext2D[]>
+  static fromImage(image: Image, attributes?: ContextAttributes): Canvas
 
   /**
    * @deprecated Use the saveAsSync, toBufferSync, and toDataURLSync methods
@@ -60,7 +76,7 @@ export class Canvas {
   width: number
   height: number
 
-  getContext(type?: "2d"): CanvasRenderingContext2D
+  getContext(type?: "2d", attributes?: ContextAttributes): CanvasRenderingContext2D
   newPage(width?: number, height?: number): CanvasRenderingContext2D
   readonly pages: CanvasRenderingContext2D[]
 
@@ -81,12 +97,64 @@ export class Canvas {
   get png(): Promise<Buffer>
 }
 
+export interface CanvasEncoderOptions {
+  /** Number of times to play the animation (0 = loop forever) */
+  loops?: number
+}
+
+export class CanvasEncoder {
+  constructor(format?: "apng", options?: CanvasEncoderOptions)
+  readonly frames: number
+
+  /** Snapshot the canvas's current contents as a frame lasting `duration` milliseconds */
+  addFrame(canvas: Canvas, duration?: number): this
+  finish(): Buffer
+}
+
+export interface PdfExportOptions {
+  quality?: number
+  density?: number
+  matte?: string
+}
+
+export class PdfExporter {
+  readonly pages: number
+
+  /** Snapshot a context's current contents as a new page (optionally with different dimensions) */
+  addPage(page: CanvasRenderingContext2D | Canvas, width?: number, height?: number): this
+  finish(options?: PdfExportOptions): Buffer
+}
+
 //
 // Context
 //
 
 type Offset = [x: number, y: number] | number
 
+export interface CreateNoiseOptions {
+  /** The style of Perlin noise to generate (defaults to "fractal") */
+  type?: "fractal" | "turbulence"
+
+  /** The dimensions at which the noise should repeat seamlessly */
+  tile?: Offset
+}
+
+export interface DashSegment {
+  /** The length of the dash (or, for odd-numbered segments, the gap that follows it) */
+  length: number
+
+  /** The cap style used for the ends of this dash (defaults to the context's lineCap) */
+  cap?: CanvasLineCap
+}
+
+export interface PatternFromPathOptions {
+  /** The color to use for stroking/filling the path (defaults to black) */
+  color?: string
+
+  /** The lineWidth with which to stroke the path (if omitted, the path will be filled instead) */
+  line?: number
+}
+
 export interface CreateTextureOptions {
   /** The 2D shape to be drawn in a repeating grid with the specified spacing (if omitted, parallel lines will be used) */
   path?: Path2D
@@ -107,22 +175,30 @@ export interface CreateTextureOptions {
 export type CanvasImageSource = Canvas | Image;
 
 interface CanvasDrawImage {
-  drawImage(image: CanvasImageSource, dx: number, dy: number): void;
-  drawImage(image: CanvasImageSource, dx: number, dy: number, dw: number, dh: number): void;
-  drawImage(image: CanvasImageSource, sx: number, sy: number, sw: number, sh: number, dx: number, dy: number, dw: number, dh: number): void;
+  drawImage(image: CanvasImageSource | ImageData, dx: number, dy: number): void;
+  drawImage(image: CanvasImageSource | ImageData, dx: number, dy: number, dw: number, dh: number): void;
+  drawImage(image: CanvasImageSource | ImageData, sx: number, sy: number, sw: number, sh: number, dx: number, dy: number, dw: number, dh: number): void;
   drawCanvas(image: Canvas, dx: number, dy: number): void;
   drawCanvas(image: Canvas, dx: number, dy: number, dw: number, dh: number): void;
   drawCanvas(image: Canvas, sx: number, sy: number, sw: number, sh: number, dx: number, dy: number, dw: number, dh: number): void;
+  drawYUVFrame(frame: YUVFrame, dx: number, dy: number): void;
+  drawYUVFrame(frame: YUVFrame, dx: number, dy: number, dw: number, dh: number): void;
+  drawYUVFrame(frame: YUVFrame, sx: number, sy: number, sw: number, sh: number, dx: number, dy: number, dw: number, dh: number): void;
+  drawMesh(vertices: ArrayLike<number> | readonly [x: number, y: number][], texCoords?: ArrayLike<number> | readonly [x: number, y: number][] | null, colors?: readonly string[] | null, indices?: ArrayLike<number> | null, blendMode?: GlobalCompositeOperation | "modulate"): void;
 }
 
 interface CanvasFillStrokeStyles {
   fillStyle: string | CanvasGradient | CanvasPattern | CanvasTexture;
   strokeStyle: string | CanvasGradient | CanvasPattern | CanvasTexture;
-  createConicGradient(startAngle: number, x: number, y: number): CanvasGradient;
-  createLinearGradient(x0: number, y0: number, x1: number, y1: number): CanvasGradient;
-  createRadialGradient(x0: number, y0: number, r0: number, x1: number, y1: number, r1: number): CanvasGradient;
+  currentColor: string;
+  createConicGradient(startAngle: number, x: number, y: number, interpolation?: GradientInterpolation): CanvasGradient;
+  createConicalGradient(x0: number, y0: number, r0: number, x1: number, y1: number, r1: number, interpolation?: GradientInterpolation): CanvasGradient;
+  createLinearGradient(x0: number, y0: number, x1: number, y1: number, interpolation?: GradientInterpolation): CanvasGradient;
+  createRadialGradient(x0: number, y0: number, r0: number, x1: number, y1: number, r1: number, interpolation?: GradientInterpolation): CanvasGradient;
   createPattern(image: CanvasImageSource, repetition: string | null): CanvasPattern | null;
   createTexture(spacing: Offset, options?: CreateTextureOptions): CanvasTexture
+  createNoise(frequency: Offset, octaves?: number, seed?: number, options?: CreateNoiseOptions): CanvasPattern
+  patternFromPath(path: Path2D, cellSize: Offset, options?: PatternFromPathOptions): CanvasPattern
 }
 
 type QuadOrRect = [x1:number, y1:number, x2:number, y2:number, x3:number, y3:number, x4:number, y4:number] |
@@ -130,21 +206,109 @@ type QuadOrRect = [x1:number, y1:number, x2:number, y2:number, x3:number, y3:num
 
 type CornerRadius = number | DOMPoint
 
-export interface CanvasRenderingContext2D extends CanvasCompositing, CanvasDrawImage, CanvasDrawPath, CanvasFillStrokeStyles, CanvasFilters, CanvasImageData, CanvasImageSmoothing, CanvasPath, CanvasPathDrawingStyles, CanvasRect, CanvasShadowStyles, CanvasState, CanvasText, CanvasTextDrawingStyles, CanvasTransform, CanvasUserInterface {
+export type GradientInterpolation = "srgb" | "srgb-linear" | "oklab"
+
+export interface YUVFrame {
+  width: number
+  height: number
+  y: Uint8Array | Uint8ClampedArray | Uint16Array
+  u: Uint8Array | Uint8ClampedArray | Uint16Array
+  v: Uint8Array | Uint8ClampedArray | Uint16Array
+  colorSpace?: "bt709" | "bt601" | "jpeg"
+}
+
+export interface DrawPictureOptions {
+  matrix?: DOMMatrix | number[]
+  translate?: [x: number, y: number]
+  rotate?: number
+  scale?: number | [x: number, y: number]
+  alpha?: number
+}
+
+export interface DrawPathOptions {
+  style?: "fill" | "stroke"
+  fillRule?: CanvasFillRule
+  lineDash?: number[]
+  fillStyle?: string | CanvasGradient | CanvasPattern | CanvasTexture
+  strokeStyle?: string | CanvasGradient | CanvasPattern | CanvasTexture
+  globalAlpha?: number
+  globalCompositeOperation?: GlobalCompositeOperation
+  filter?: string
+  imageSmoothingEnabled?: boolean
+  imageSmoothingQuality?: ImageSmoothingQuality
+  lineCap?: CanvasLineCap
+  lineDashFit?: "move" | "turn" | "follow"
+  lineDashMarker?: Path2D | null
+  lineDashOffset?: number
+  lineJoin?: CanvasLineJoin
+  lineWidth?: number
+  /** Alias for `lineWidth` */
+  strokeWidth?: number
+  miterLimit?: number
+  shadowBlur?: number
+  shadowColor?: string
+  shadowOffsetX?: number | string
+  shadowOffsetY?: number | string
+}
+
+export interface CanvasRenderingContext2D extends CanvasCompositing, CanvasDrawImage, CanvasDrawPath, CanvasFillStrokeStyles, CanvasFilters, CanvasImageData, CanvasImageSmoothing, CanvasPath, CanvasPathDrawingStyles, CanvasRect, Omit<CanvasShadowStyles, "shadowOffsetX" | "shadowOffsetY">, CanvasState, CanvasText, Omit<CanvasTextDrawingStyles, "direction" | "textAlign">, CanvasTransform, CanvasUserInterface {
   readonly canvas: Canvas;
+  direction: CanvasDirection | "auto";
   fontVariant: string;
+  fontVariantNumeric: string;
+  fontVariantLigatures: string;
   textTracking: number;
   textWrap: boolean;
+  writingMode: "horizontal-tb" | "vertical-rl" | "vertical-lr";
+  get textAlign(): CanvasTextAlign;
+  set textAlign(mode: CanvasTextAlign | "matchParent");
+  get shadowOffsetX(): number;
+  set shadowOffsetX(offset: number | string);
+  get shadowOffsetY(): number;
+  set shadowOffsetY(offset: number | string);
+  rootFontSize: number;
+  readonly fontBoundingBoxAscent: number;
+  readonly fontBoundingBoxDescent: number;
   lineDashMarker: Path2D | null;
   lineDashFit: "move" | "turn" | "follow";
 
+  readonly currentPath: Path2D
+  path(): PathBuilder
+  pathBounds(path?: Path2D): DOMRect
+  readPixels(rect: DOMRectInit | [number, number, number, number], options?: {colorType?: "u8", alphaType?: "premul" | "unpremul"}): Uint8ClampedArray
+  readPixels(rect: DOMRectInit | [number, number, number, number], options: {colorType: "f16", alphaType?: "premul" | "unpremul"}): Uint16Array
+  readPixels(rect: DOMRectInit | [number, number, number, number], options: {colorType: "f32", alphaType?: "premul" | "unpremul"}): Float32Array
+  hitTest(x: number, y: number): boolean
+  colorAtPoint(x: number, y: number): {r: number, g: number, b: number, a: number}
+  setShadowColor(r: number, g: number, b: number, a?: number): void
+  setLineDashAdvanced(segments: (number | DashSegment)[]): void
   get currentTransform(): DOMMatrix
   set currentTransform(matrix: DOMMatrix)
   createProjection(quad: QuadOrRect, basis?: QuadOrRect): DOMMatrix
-
+  rotateDeg(degrees: number): void
+  transformPoint(x: number, y: number): DOMPoint
+  inverseTransformPoint(x: number, y: number): DOMPoint | null
+
+  drawCircle(x: number, y: number, radius: number): void
+  drawOval(x: number, y: number, width: number, height: number, style?: "fill" | "stroke"): void
+  drawLine(x0: number, y0: number, x1: number, y1: number): void
+  drawPoints(mode: "points" | "lines" | "polygon", points: Float32Array | number[] | [x: number, y: number][]): void
+  drawPath(path: Path2D, options?: DrawPathOptions): void
+  drawPicture(canvas: Canvas, options?: DrawPictureOptions): void
+  compositeWith(source: Canvas | CanvasRenderingContext2D, blendMode?: GlobalCompositeOperation, alpha?: number, x?: number, y?: number): void
+  drawNinePatch(image: Image | Canvas, center: DOMRectInit | [number, number, number, number], dest: DOMRectInit | [number, number, number, number]): void
+  tileImage(image: Image | Canvas, src: DOMRectInit | [number, number, number, number], dest: DOMRectInit | [number, number, number, number], spacing?: {xSpacing?: number, ySpacing?: number}): void
+  drawSprite(image: Image | Canvas, src: DOMRectInit | [number, number, number, number], x: number, y: number): void
+  applyBackdropFilter(filter: string): void
+  applyBackdropFilter(filter: string, x: number, y: number, width: number, height: number): void
+  stencil<T>(path: Path2D, callback: (ctx: CanvasRenderingContext2D) => T): T
+  stencil<T>(path: Path2D, fillRule: CanvasFillRule, callback: (ctx: CanvasRenderingContext2D) => T): T
+  clipOut(fillRule?: CanvasFillRule): void
+  clipOut(path: Path2D, fillRule?: CanvasFillRule): void
+  clipStroke(path?: Path2D): void
   conicCurveTo(cpx: number, cpy: number, x: number, y: number, weight: number): void
   roundRect(x: number, y: number, width: number, height: number, radii: number | CornerRadius[]): void
-  // getContextAttributes(): CanvasRenderingContext2DSettings;
+  getContextAttributes(): ContextAttributes
 
   fillText(text: string, x: number, y:number, maxWidth?: number): void
   strokeText(text: string, x: number, y:number, maxWidth?: number): void
@@ -152,12 +316,33 @@ export interface CanvasRenderingContext2D extends CanvasCompositing, CanvasDrawI
   outlineText(text: string): Path2D
 
   reset(): void
+  dispose(): void
+  toDataURL(type?: string, quality?: number): string
+  toBlob(callback: (blob: Blob | null) => void, type?: string, quality?: number): void
 }
 
 //
 // Bézier Paths
 //
 
+export interface PathBuilder {
+  moveTo(x: number, y: number): PathBuilder
+  lineTo(x: number, y: number): PathBuilder
+  closePath(): PathBuilder
+  arcTo(x1: number, y1: number, x2: number, y2: number, radius: number): PathBuilder
+  bezierCurveTo(cp1x: number, cp1y: number, cp2x: number, cp2y: number, x: number, y: number): PathBuilder
+  quadraticCurveTo(cpx: number, cpy: number, x: number, y: number): PathBuilder
+  conicCurveTo(cpx: number, cpy: number, x: number, y: number, weight: number): PathBuilder
+  ellipse(x: number, y: number, radiusX: number, radiusY: number, rotation: number, startAngle: number, endAngle: number, counterclockwise?: boolean): PathBuilder
+  rect(x: number, y: number, width: number, height: number): PathBuilder
+  arc(x: number, y: number, radius: number, startAngle: number, endAngle: number, counterclockwise?: boolean): PathBuilder
+  roundRect(x: number, y: number, width: number, height: number, radii: number | CornerRadius[]): PathBuilder
+
+  fill(fillRule?: CanvasFillRule): Path2D
+  stroke(): Path2D
+  clip(fillRule?: CanvasFillRule): Path2D
+}
+
 export interface Path2DBounds {
   readonly top: number
   readonly left: number
@@ -170,6 +355,8 @@ export interface Path2DBounds {
 export type Path2DEdge = [verb: string, ...args: number[]]
 
 export class Path2D extends globalThis.Path2D {
+  static transformed(ctx: CanvasRenderingContext2D): Path2D
+
   d: string
   readonly bounds: Path2DBounds
   readonly edges: readonly Path2DEdge[]
@@ -191,17 +378,20 @@ export class Path2D extends globalThis.Path2D {
   union(otherPath: Path2D): Path2D
   xor(otherPath: Path2D): Path2D
   interpolate(otherPath: Path2D, weight: number): Path2D
+  equals(otherPath: Path2D, tolerance?: number): boolean
 
   jitter(segmentLength: number, amount: number, seed?: number): Path2D
   offset(dx: number, dy: number): Path2D
   points(step?: number): readonly [x: number, y: number][]
   round(radius: number): Path2D
   simplify(rule?: "nonzero" | "evenodd"): Path2D
+  toStrokePath(ctx: CanvasRenderingContext2D): Path2D
   transform(...args: [matrix: DOMMatrix] | [a: number, b: number, c: number, d: number, e: number, f: number]): Path2D;
   trim(start: number, end: number, inverted?: boolean): Path2D;
   trim(start: number, inverted?: boolean): Path2D;
 
   unwind(): Path2D
+  reverse(): Path2D
 }
 
 //
@@ -2,6 +2,7 @@
 
 const fs = require('fs'),
       {EventEmitter} = require('events'),
+      {Blob} = require('buffer'),
       {inspect} = require('util'),
       {sync:glob, hasMagic} = require('glob'),
       get = require('simple-get'),
@@ -28,6 +29,12 @@ const ø = Symbol.for('📦'), // the attr containing the boxed struct
         return api
       }, {})
 
+// keys for JS objects retained alongside a struct (namespaced to avoid colliding with user-set symbols)
+const StashKey = Object.freeze({
+  FillStyle: Symbol.for('skia-canvas::fill_style'),
+  StrokeStyle: Symbol.for('skia-canvas::stroke_style'),
+})
+
 class RustClass{
   constructor(type){
     internal(this, 'native', neon[type.name])
@@ -42,7 +49,8 @@ class RustClass{
   }
 
   ref(key, val){
-    return arguments.length > 1 ? this[Symbol.for(key)] = val : this[Symbol.for(key)]
+    if (!Object.values(StashKey).includes(key)) throw new TypeError(`Unknown stash key ${String(key)}`)
+    return arguments.length > 1 ? this[key] = val : this[key]
   }
 
   prop(attr, ...vals){
@@ -76,20 +84,70 @@ function signature(args){
 
 const toString = val => typeof val=='string' ? val : new String(val).toString()
 
+// map browser-style toDataURL/toBlob arguments to a page's format & options, following the
+// browser's conventions: unsupported types fall back to PNG and out-of-range qualities are ignored
+const BLOB_TYPES = {"image/png":"png", "image/jpeg":"jpg", "image/webp":"webp"}
+
+function encoding(ctx, type, quality){
+  let page = ctx.canvas.pages.indexOf(ctx) + 1,
+      format = BLOB_TYPES.hasOwnProperty(type) ? BLOB_TYPES[type] : "png",
+      opts = (typeof quality=='number' && quality>=0 && quality<=1) ? {page, quality} : {page}
+  return [format, opts]
+}
+
+// coerce the arguments shared by the sprite-sheet style drawing methods: rectangles can be
+// DOMRect-like objects or [x, y, width, height] arrays & sources must be an Image or Canvas
+const toRect = r => Array.isArray(r) ? r : [r.x, r.y, r.width, r.height]
+
+function imageSource(image){
+  let src = image instanceof Canvas ? core(image.getContext('2d'))
+          : image instanceof Image ? core(image)
+          : null
+  if (!src) throw new TypeError("Expected an Image or Canvas argument")
+  return src
+}
+
 //
 // Helpers to reconcile Skia and DOMMatrix’s disagreement about row/col orientation
 //
 
 function toSkMatrix(jsMatrix){
-  if (Array.isArray(jsMatrix) && jsMatrix.length==6){
-    var [a, b, c, d, e, f, m14, m24, m44] = jsMatrix.concat(0, 0, 1)
+  let isList = Array.isArray(jsMatrix) || ArrayBuffer.isView(jsMatrix)
+  if (isList && jsMatrix.length==6){
+    var [a, b, c, d, e, f, m14, m24, m44] = [...jsMatrix, 0, 0, 1]
   }else if (jsMatrix instanceof geometry.DOMMatrix){
     var {a, b, c, d, e, f, m14, m24, m44} = jsMatrix
+  }else if (jsMatrix && typeof jsMatrix == 'object' && !isList){
+    // duck-type DOMMatrixInit-style objects (e.g., the output of DOMMatrix.toJSON()), defaulting to the identity
+    var {a=1, b=0, c=0, d=1, e=0, f=0, m14=0, m24=0, m44=1} = jsMatrix
   }
   return [a, c, e, b, d, f, m14, m24, m44]
 }
 
+function toSkMatrix44(jsMatrix){
+  // returns the row-major terms of a DOMMatrix that uses the z-axis (and undefined otherwise)
+  let m = jsMatrix
+  if (m instanceof geometry.DOMMatrix && ([m.m13, m.m23, m.m31, m.m32, m.m34, m.m43].some(v => v != 0) || m.m33 != 1)){
+    return [
+      m.m11, m.m21, m.m31, m.m41,
+      m.m12, m.m22, m.m32, m.m42,
+      m.m13, m.m23, m.m33, m.m43,
+      m.m14, m.m24, m.m34, m.m44
+    ]
+  }
+}
+
 function fromSkMatrix(skMatrix){
+  if (skMatrix.length==16){
+    let [m11, m21, m31, m41, m12, m22, m32, m42, m13, m23, m33, m43, m14, m24, m34, m44] = skMatrix
+    return new geometry.DOMMatrix([
+      m11, m12, m13, m14,
+      m21, m22, m23, m24,
+      m31, m32, m33, m34,
+      m41, m42, m43, m44
+    ])
+  }
+
   let [a, b, c, d, e, f, p0, p1, p2] = skMatrix
   return new geometry.DOMMatrix([
     a, d, 0, p0,
@@ -394,15 +452,33 @@ const GUI = {
 
 class Canvas extends RustClass{
   #contexts
+  #attributes
 
   constructor(width, height){
     super(Canvas).alloc()
     this.#contexts = []
+    this.#attributes = {}
     Object.assign(this, {width, height})
   }
 
-  getContext(kind){
-    return (kind=="2d") ? this.#contexts[0] || this.newPage() : null
+  static fromImage(image, attributes){
+    if (!(image instanceof Image)) throw new TypeError("Expected an Image")
+    if (!image.complete) throw new Error("Cannot use incomplete image (has it finished loading?)")
+
+    // size the canvas to the image and copy its pixels in as the starting point for further drawing
+    let canvas = new Canvas(image.width, image.height)
+    canvas.getContext("2d", attributes).ƒ('blitImage', core(image))
+    return canvas
+  }
+
+  getContext(kind, attributes){
+    if (kind!="2d") return null
+    if (!this.#contexts[0]){
+      // the first context's attributes are used for every subsequent page
+      this.#attributes = attributes || {}
+      this.newPage()
+    }
+    return this.#contexts[0]
   }
 
   get gpu(){ return this.prop('engine')=='gpu' }
@@ -410,18 +486,18 @@ class Canvas extends RustClass{
 
   get width(){ return this.prop('width') }
   set width(w){
-    this.prop('width', (typeof w=='number' && !Number.isNaN(w) && w>=0) ? w : 300)
+    this.prop('width', (typeof w=='number' && !Number.isNaN(w) && w>=0) ? Math.floor(w) : 300)
     if (this.#contexts[0]) this.getContext("2d").ƒ('resetSize', core(this))
   }
 
   get height(){ return this.prop('height') }
   set height(h){
-    this.prop('height', h = (typeof h=='number' && !Number.isNaN(h) && h>=0) ? h : 150)
+    this.prop('height', h = (typeof h=='number' && !Number.isNaN(h) && h>=0) ? Math.floor(h) : 150)
     if (this.#contexts[0]) this.getContext("2d").ƒ('resetSize', core(this))
   }
 
   newPage(width, height){
-    let ctx = new CanvasRenderingContext2D(this)
+    let ctx = new CanvasRenderingContext2D(this, this.#attributes)
     this.#contexts.unshift(ctx)
     if (arguments.length==2){
       Object.assign(this, {width, height})
@@ -450,30 +526,30 @@ class Canvas extends RustClass{
     if (!this.async) return this.saveAsSync(...arguments) // support while deprecated
 
     opts = typeof opts=='number' ? {quality:opts} : opts
-    let {format, quality, pages, padding, pattern, density, outline, matte} = io.options(this.pages, {filename, ...opts}),
-        args = [pages.map(core), pattern, padding, format, quality, density, outline, matte]
+    let {format, quality, pages, padding, pattern, density, outline, matte, lossless} = io.options(this.pages, {filename, ...opts}),
+        args = [pages.map(core), pattern, padding, format, quality, density, outline, matte, lossless]
     return this.ƒ("save", ...args)
   }
 
   saveAsSync(filename, opts={}){
     opts = typeof opts=='number' ? {quality:opts} : opts
-    let {format, quality, pages, padding, pattern, density, outline, matte} = io.options(this.pages, {filename, ...opts})
-    this.ƒ("saveSync", pages.map(core), pattern, padding, format, quality, density, outline, matte)
+    let {format, quality, pages, padding, pattern, density, outline, matte, lossless} = io.options(this.pages, {filename, ...opts})
+    this.ƒ("saveSync", pages.map(core), pattern, padding, format, quality, density, outline, matte, lossless)
   }
 
   toBuffer(extension="png", opts={}){
     if (!this.async) return this.toBufferSync(...arguments) // support while deprecated
 
     opts = typeof opts=='number' ? {quality:opts} : opts
-    let {format, quality, pages, density, outline, matte} = io.options(this.pages, {extension, ...opts}),
-        args = [pages.map(core), format, quality, density, outline, matte];
+    let {format, quality, pages, density, outline, matte, lossless} = io.options(this.pages, {extension, ...opts}),
+        args = [pages.map(core), format, quality, density, outline, matte, lossless];
     return this.ƒ("toBuffer", ...args)
   }
 
   toBufferSync(extension="png", opts={}){
     opts = typeof opts=='number' ? {quality:opts} : opts
-    let {format, quality, pages, density, outline, matte} = io.options(this.pages, {extension, ...opts})
-    return this.ƒ("toBufferSync", pages.map(core), format, quality, density, outline, matte)
+    let {format, quality, pages, density, outline, matte, lossless} = io.options(this.pages, {extension, ...opts})
+    return this.ƒ("toBufferSync", pages.map(core), format, quality, density, outline, matte, lossless)
   }
 
   toDataURL(extension="png", opts={}){
@@ -499,12 +575,64 @@ class Canvas extends RustClass{
   }
 }
 
+class CanvasEncoder{
+  #animation
+
+  constructor(format="apng", {loops=0}={}){
+    if (String(format).toLowerCase() != "apng"){
+      throw new Error(`Unsupported animation format: "${format}" (expected "apng")`)
+    }
+    this.#animation = new io.APNG(loops)
+  }
+
+  get frames(){ return this.#animation.frames.length }
+
+  addFrame(canvas, duration=100){
+    if (!(canvas instanceof Canvas)) throw new TypeError("Expected a Canvas")
+    this.#animation.add(canvas.toBufferSync("png"), duration)
+    return this
+  }
+
+  finish(){
+    return Buffer.from(this.#animation.toBuffer())
+  }
+
+  [REPR](depth, options) {
+    let {frames} = this
+    return `CanvasEncoder ${inspect({frames}, options)}`
+  }
+}
+
+class PdfExporter extends RustClass{
+  constructor(){
+    super(PdfExporter).alloc()
+  }
+
+  get pages(){ return this.prop("pages") }
+
+  addPage(page, width, height){
+    let ctx = page instanceof Canvas ? page.getContext('2d') : page
+    if (!(ctx instanceof CanvasRenderingContext2D)) throw new TypeError("Expected a CanvasRenderingContext2D or Canvas")
+    this.ƒ('addPage', core(ctx), ...[width, height].filter(n => n !== undefined))
+    return this
+  }
+
+  finish({quality=0.92, density=1, matte}={}){
+    return this.ƒ('finish', quality, density, matte)
+  }
+
+  [REPR](depth, options) {
+    let {pages} = this
+    return `PdfExporter ${inspect({pages}, options)}`
+  }
+}
+
 class CanvasGradient extends RustClass{
   constructor(style, ...coords){
     super(CanvasGradient)
     style = (style || "").toLowerCase()
     if (['linear', 'radial', 'conic'].includes(style)) this.init(style, ...coords)
-    else throw new Error(`Function is not a constructor (use CanvasRenderingContext2D's "createConicGradient", "createLinearGradient", and "createRadialGradient" methods instead)`)
+    else throw new Error(`Function is not a constructor (use CanvasRenderingContext2D's "createConicGradient", "createConicalGradient", "createLinearGradient", and "createRadialGradient" methods instead)`)
   }
 
   addColorStop(offset, color){
@@ -512,6 +640,12 @@ class CanvasGradient extends RustClass{
     else throw new Error("Color stop offsets must be between 0.0 and 1.0")
   }
 
+  getColorStops(){ return this.ƒ('getColorStops') }
+
+  cloneWithGeometry(style, ...coords){
+    return wrap(CanvasGradient, this.ƒ('cloneWithGeometry', style, ...coords))
+  }
+
   [REPR](depth, options) {
     return `CanvasGradient (${this.ƒ("repr")})`
   }
@@ -530,9 +664,13 @@ class CanvasPattern extends RustClass{
     }
   }
 
-  setTransform(matrix){
+  setTransform(matrix={}){
     if (arguments.length>1) matrix = [...arguments]
-    this.ƒ('setTransform', toSkMatrix(matrix))
+    let terms = toSkMatrix(matrix)
+    if (!terms.every(Number.isFinite)){
+      throw new TypeError("Expected a DOMMatrix, an object with numeric a–f attributes, or an array of 6 numbers")
+    }
+    this.ƒ('setTransform', terms)
   }
 
   [REPR](depth, options) {
@@ -557,12 +695,22 @@ class CanvasTexture extends RustClass{
 }
 
 
+// context properties that can be overridden on a per-call basis by drawPath()
+const PAINT_OPTIONS = [
+  "fillStyle", "strokeStyle", "globalAlpha", "globalCompositeOperation", "filter", "imageSmoothingEnabled",
+  "imageSmoothingQuality", "lineCap", "lineDashFit", "lineDashMarker", "lineDashOffset", "lineJoin", "lineWidth",
+  "miterLimit", "shadowBlur", "shadowColor", "shadowOffsetX", "shadowOffsetY"
+]
+
 class CanvasRenderingContext2D extends RustClass{
   #canvas
+  #disposed = false
+  #rootFontSize = 16
 
-  constructor(canvas){
+  constructor(canvas, {alpha=true, willReadFrequently=false, measureTextCache=0}={}){
     try{
-      super(CanvasRenderingContext2D).alloc(core(canvas))
+      let cacheSize = measureTextCache===true ? 256 : Math.max(0, Math.floor(measureTextCache)) || 0
+      super(CanvasRenderingContext2D).alloc(core(canvas), !!alpha, !!willReadFrequently, cacheSize)
       this.#canvas = new WeakRef(canvas)
     }catch(e){
       throw new TypeError(`Function is not a constructor (use Canvas's "getContext" method instead)`)
@@ -570,16 +718,35 @@ class CanvasRenderingContext2D extends RustClass{
   }
 
   get canvas(){ return this.#canvas.deref() }
+  getContextAttributes(){ return this.ƒ('getContextAttributes') }
+
+  ƒ(fn, ...args){
+    if (this.#disposed) throw new Error(`Cannot call ${fn}() on a disposed CanvasRenderingContext2D`)
+    return super.ƒ(fn, ...args)
+  }
+
+  prop(attr, ...vals){
+    if (this.#disposed) throw new Error(`Cannot access ${attr} on a disposed CanvasRenderingContext2D`)
+    return super.prop(attr, ...vals)
+  }
 
   // -- global state & content reset ------------------------------------------
   reset(){ this.ƒ('reset') }
+  dispose(){
+    // release the recorded drawing and any images held by the context's state
+    if (!this.#disposed) this.ƒ('dispose')
+    this.#disposed = true
+  }
 
   // -- grid state ------------------------------------------------------------
   save(){ this.ƒ('save') }
   restore(){ this.ƒ('restore') }
 
   get currentTransform(){ return fromSkMatrix( this.prop('currentTransform') ) }
-  set currentTransform(matrix){ this.prop('currentTransform', toSkMatrix(matrix) ) }
+  set currentTransform(matrix){
+    if ((Array.isArray(matrix) || ArrayBuffer.isView(matrix)) && matrix.length==16) matrix = new geometry.DOMMatrix(matrix)
+    this.prop('currentTransform', toSkMatrix44(matrix) || toSkMatrix(matrix) )
+  }
 
   resetTransform(){ this.ƒ('resetTransform')}
   getTransform(){ return this.currentTransform }
@@ -591,6 +758,17 @@ class CanvasRenderingContext2D extends RustClass{
   translate(x, y){ this.ƒ('translate', ...arguments)}
   scale(x, y){ this.ƒ('scale', ...arguments)}
   rotate(angle){ this.ƒ('rotate', ...arguments)}
+  rotateDeg(degrees){ this.ƒ('rotateDeg', ...arguments)}
+
+  transformPoint(x, y){
+    let [px, py] = this.ƒ('transformPoint', ...arguments)
+    return new geometry.DOMPoint(px, py)
+  }
+
+  inverseTransformPoint(x, y){
+    let pt = this.ƒ('inverseTransformPoint', ...arguments)
+    return pt ? new geometry.DOMPoint(...pt) : null
+  }
 
   createProjection(quad, basis){
     return fromSkMatrix(this.ƒ("createProjection", [quad].flat(), [basis].flat()))
@@ -598,6 +776,13 @@ class CanvasRenderingContext2D extends RustClass{
 
   // -- bézier paths ----------------------------------------------------------
   beginPath(){ this.ƒ('beginPath') }
+  path(){ return new PathBuilder(this) }
+  get currentPath(){ return wrap(Path2D, this.prop('currentPath')) }
+  pathBounds(path){
+    if (path !== undefined && !(path instanceof Path2D)) throw new TypeError("Expected a Path2D")
+    let [x, y, width, height] = path ? this.ƒ('pathBounds', core(path)) : this.ƒ('pathBounds')
+    return new geometry.DOMRect(x, y, width, height)
+  }
   rect(x, y, width, height){ this.ƒ('rect', ...arguments) }
   arc(x, y, radius, startAngle, endAngle, isCCW){ this.ƒ('arc', ...arguments) }
   ellipse(x, y, xRadius, yRadius, rotation, startAngle, endAngle, isCCW){ this.ƒ('ellipse', ...arguments) }
@@ -637,15 +822,62 @@ class CanvasRenderingContext2D extends RustClass{
     else this.ƒ('clip', path) // 'path' is the optional winding-rule
   }
 
+  stencil(path, rule, callback){
+    if (typeof rule == 'function') [rule, callback] = [undefined, rule]
+    if (!(path instanceof Path2D)) throw new TypeError("Expected a Path2D")
+    if (typeof callback != 'function') throw new TypeError("Expected a callback function")
+
+    // confine the callback's drawing to the path and discard any state changes it makes
+    this.save()
+    try{
+      this.clip(path, rule)
+      return callback(this)
+    }finally{
+      this.restore()
+    }
+  }
+
+  clipOut(path, rule){
+    if (path instanceof Path2D) this.ƒ('clipOut', core(path), rule)
+    else this.ƒ('clipOut', path) // 'path' is the optional winding-rule
+  }
+
+  clipStroke(path){
+    if (path instanceof Path2D) this.ƒ('clipStroke', core(path))
+    else this.ƒ('clipStroke')
+  }
+
+  drawPath(path, {style="stroke", fillRule="nonzero", strokeWidth, ...props}={}){
+    if (!(path instanceof Path2D)) throw new TypeError("Expected a Path2D")
+    if (!['fill', 'stroke'].includes(style)) throw new TypeError(`Unknown drawPath style "${style}" (expected "fill" or "stroke")`)
+    let unknown = Object.keys(props).find(key => key!='lineDash' && !PAINT_OPTIONS.includes(key))
+    if (unknown) throw new TypeError(`Unknown drawPath option "${unknown}"`)
+
+    // convert the options the same way their property setters would (with strokeWidth as an alias
+    // for lineWidth) then let the native side apply them on top of a copy of the current state
+    let {lineWidth=strokeWidth, fillStyle, strokeStyle, lineDashMarker, shadowOffsetX, shadowOffsetY, filter} = props,
+        shader = dye => (dye instanceof CanvasPattern || dye instanceof CanvasGradient || dye instanceof CanvasTexture) ? core(dye) : dye
+    this.ƒ('drawPath', core(path), style, fillRule, {
+      ...props, lineWidth, fillStyle:shader(fillStyle), strokeStyle:shader(strokeStyle),
+      lineDashMarker: lineDashMarker instanceof Path2D ? core(lineDashMarker) : lineDashMarker,
+      shadowOffsetX: shadowOffsetX===undefined ? undefined : this.#length(shadowOffsetX),
+      shadowOffsetY: shadowOffsetY===undefined ? undefined : this.#length(shadowOffsetY),
+      filter: filter===undefined ? undefined : css.filter(filter, css.font(this.font).size, this.#rootFontSize),
+    })
+  }
+
   // -- shaders ---------------------------------------------------------------
   createPattern(image, repetition){ return new CanvasPattern(...arguments) }
-  createLinearGradient(x0, y0, x1, y1){
+  createLinearGradient(x0, y0, x1, y1, interpolation){
     return new CanvasGradient("Linear", ...arguments)
   }
-  createRadialGradient(x0, y0, r0, x1, y1, r1){
+  createRadialGradient(x0, y0, r0, x1, y1, r1, interpolation){
     return new CanvasGradient("Radial", ...arguments)
   }
-  createConicGradient(startAngle, x, y){
+  createConicalGradient(x0, y0, r0, x1, y1, r1, interpolation){
+    return new CanvasGradient("Radial", ...arguments)
+  }
+  createConicGradient(startAngle, x, y, interpolation){
     return new CanvasGradient("Conic", ...arguments)
   }
 
@@ -653,38 +885,74 @@ class CanvasRenderingContext2D extends RustClass{
     return new CanvasTexture(spacing, options)
   }
 
+  patternFromPath(path, cellSize, {color, line=0}={}){
+    if (!(path instanceof Path2D)) throw new TypeError("Expected a Path2D object as the pattern's source")
+    let [w, h] = typeof cellSize=='number' ? [cellSize, cellSize] : [...cellSize].slice(0, 2)
+    return wrap(CanvasPattern, neon.CanvasPattern.from_path(null, core(path), w, h, color, line))
+  }
+
+  createNoise(frequency, octaves=1, seed=0, {type="fractal", tile}={}){
+    let [x, y] = typeof frequency=='number' ? [frequency, frequency] : [...frequency].slice(0, 2),
+        [w, h] = typeof tile=='number' ? [tile, tile] : tile ? [...tile].slice(0, 2) : []
+    return wrap(CanvasPattern, neon.CanvasPattern.from_noise(null, x, y, octaves, seed, type, w, h))
+  }
+
   // -- fill & stroke ---------------------------------------------------------
   fillRect(x, y, width, height){ this.ƒ('fillRect', ...arguments) }
   strokeRect(x, y, width, height){ this.ƒ('strokeRect', ...arguments) }
   clearRect(x, y, width, height){ this.ƒ('clearRect', ...arguments) }
+  drawCircle(x, y, radius){ this.ƒ('drawCircle', ...arguments) }
+  drawLine(x0, y0, x1, y1){ this.ƒ('drawLine', ...arguments) }
+  drawPoints(mode, points){
+    if (!['points', 'lines', 'polygon'].includes(mode)) throw new TypeError(`Unknown drawPoints mode "${mode}" (expected "points", "lines", or "polygon")`)
+    if (points==null) throw new TypeError("Expected an array of point coordinates")
+    this.ƒ('drawPoints', mode, points instanceof Float32Array ? points : Float32Array.from([...points].flat()))
+  }
+  drawOval(x, y, width, height, style="fill"){
+    if (!['fill', 'stroke'].includes(style)) throw new TypeError(`Unknown drawOval style "${style}" (expected "fill" or "stroke")`)
+    this.ƒ('drawOval', ...[...arguments].slice(0, 4), style)
+  }
 
   set fillStyle(style){
     let isShader = style instanceof CanvasPattern || style instanceof CanvasGradient || style instanceof CanvasTexture,
         [ref, val] = isShader ? [style, core(style)] : [null, style]
-    this.ref('fill', ref)
+    this.ref(StashKey.FillStyle, ref)
     this.prop('fillStyle', val)
   }
 
   get fillStyle(){
     let style = this.prop('fillStyle')
-    return style===null ? this.ref('fill') : style
+    return style===null ? this.ref(StashKey.FillStyle) : style
   }
 
   set strokeStyle(style){
     let isShader = style instanceof CanvasPattern || style instanceof CanvasGradient || style instanceof CanvasTexture,
         [ref, val] = isShader ? [style, core(style)] : [null, style]
-    this.ref('stroke', ref)
+    this.ref(StashKey.StrokeStyle, ref)
     this.prop('strokeStyle', val)
   }
 
   get strokeStyle(){
     let style = this.prop('strokeStyle')
-    return style===null ? this.ref('stroke') : style
+    return style===null ? this.ref(StashKey.StrokeStyle) : style
   }
 
+  get currentColor(){ return this.prop('currentColor') }
+  set currentColor(color){   this.prop('currentColor', color) }
+
   // -- line style ------------------------------------------------------------
   getLineDash(){        return this.ƒ("getLineDash") }
   setLineDash(segments){       this.ƒ("setLineDash", segments) }
+  setLineDashAdvanced(segments){
+    if (!Array.isArray(segments)) throw new TypeError("Expected an array of dash lengths or {length, cap} objects")
+    // segments can be plain numbers (using the current lineCap) or objects with their own cap style
+    let lengths = segments.map(seg => typeof seg=='number' ? seg : (seg||{}).length),
+        caps = segments.map(seg => (seg||{}).cap || this.lineCap)
+    if (!caps.every(cap => ['butt', 'round', 'square'].includes(cap))){
+      throw new TypeError(`Unknown cap style (expected "butt", "round", or "square")`)
+    }
+    this.ƒ("setLineDashAdvanced", lengths, caps)
+  }
   get lineCap(){        return this.prop("lineCap") }
   set lineCap(style){          this.prop("lineCap", style) }
   get lineDashFit(){    return this.prop("lineDashFit") }
@@ -715,13 +983,31 @@ class CanvasRenderingContext2D extends RustClass{
     return new ImageData(buffer, w, h)
   }
 
+  readPixels(rect, {colorType="u8", alphaType="unpremul"}={}){
+    let {x, y, width, height} = rect || {}
+    if (Array.isArray(rect)) [x, y, width, height] = rect
+
+    let buffer = this.ƒ('readPixels', x, y, Math.floor(width), Math.floor(height), colorType, alphaType),
+        data = buffer.buffer.slice(buffer.byteOffset, buffer.byteOffset + buffer.length)
+    // half-floats are returned as their raw 16-bit encodings since js has no native Float16Array
+    return colorType == "f32" ? new Float32Array(data)
+         : colorType == "f16" ? new Uint16Array(data)
+         : new Uint8ClampedArray(data)
+  }
+
+  hitTest(x, y){ return this.ƒ('hitTest', ...arguments) }
+  colorAtPoint(x, y){ return this.ƒ('colorAtPoint', ...arguments) }
+
   drawImage(image, ...coords){
     if (image instanceof Canvas){
       this.ƒ('drawImage', core(image.getContext('2d')), ...coords)
     }else if (image instanceof Image){
       this.ƒ('drawImage', core(image), ...coords)
+    }else if (image instanceof ImageData || ArrayBuffer.isView(image && image.data)){
+      let {width, height, data} = image
+      this.ƒ('drawImage', {width, height, data}, ...coords)
     }else{
-      throw new Error("Expected an Image or a Canvas argument")
+      throw new Error("Expected an Image, Canvas, or ImageData argument")
     }
   }
 
@@ -733,6 +1019,61 @@ class CanvasRenderingContext2D extends RustClass{
     }
   }
 
+  drawPicture(canvas, {matrix, translate=0, rotate=0, scale=1, alpha=1}={}){
+    if (!(canvas instanceof Canvas)) throw new TypeError("Expected a Canvas")
+    let [tx, ty=0] = [translate].flat(),
+        [sx, sy=sx] = [scale].flat(),
+        xform = new geometry.DOMMatrix(matrix instanceof geometry.DOMMatrix ? matrix.toFloat64Array() : matrix)
+    xform = xform.translate(tx, ty).rotate(rotate * 180 / Math.PI).scale(sx, sy)
+    this.ƒ('drawPicture', core(canvas.getContext('2d')), toSkMatrix(xform), alpha)
+  }
+
+  compositeWith(source, blendMode="source-over", alpha=1, x=0, y=0){
+    let ctx = source instanceof Canvas ? source.getContext('2d') : source
+    if (!(ctx instanceof CanvasRenderingContext2D)) throw new TypeError("Expected a CanvasRenderingContext2D or Canvas")
+    this.ƒ('compositeWith', core(ctx), blendMode, alpha, x, y)
+  }
+
+  applyBackdropFilter(filter, ...rect){
+    let spec = css.filter(filter, css.font(this.font).size, this.#rootFontSize)
+    if (!spec) throw new TypeError(`Invalid filter: "${filter}"`)
+    this.ƒ('applyBackdropFilter', spec, ...rect)
+  }
+
+  drawNinePatch(image, center, dest){
+    if (!center || !dest) throw new TypeError("Expected center and destination rectangles")
+    this.ƒ('drawNinePatch', imageSource(image), ...toRect(center), ...toRect(dest))
+  }
+
+  tileImage(image, src, dest, {xSpacing=0, ySpacing=0}={}){
+    if (!src || !dest) throw new TypeError("Expected source and destination rectangles")
+    this.ƒ('tileImage', imageSource(image), ...toRect(src), ...toRect(dest), xSpacing, ySpacing)
+  }
+
+  drawSprite(image, src, x, y){
+    if (!src) throw new TypeError("Expected a source rectangle")
+    this.ƒ('drawSprite', imageSource(image), ...toRect(src), x, y)
+  }
+
+  drawYUVFrame(frame, ...coords){
+    let {width, height, y, u, v, colorSpace='bt709'} = frame || {},
+        plane = arr => arr instanceof Uint8Array || arr instanceof Uint16Array ? arr
+                     : ArrayBuffer.isView(arr) && arr.BYTES_PER_ELEMENT==1 ? new Uint8Array(arr.buffer, arr.byteOffset, arr.byteLength)
+                     : arr;
+    this.ƒ('drawYUVFrame', {width, height, y:plane(y), u:plane(u), v:plane(v), colorSpace}, ...coords)
+  }
+
+  drawMesh(vertices, texCoords, colors, indices, blendMode){
+    let floats = arr => arr==null ? null : arr instanceof Float32Array ? arr : Float32Array.from([...arr].flat()),
+        shorts = arr => arr==null ? null : arr instanceof Uint16Array ? arr : Uint16Array.from(arr, i => {
+          // rather than letting out-of-range indices silently wrap around
+          if (!Number.isInteger(i) || i < 0 || i > 65535) throw new RangeError(`Mesh indices must be integers between 0 and 65535 (got ${i})`)
+          return i
+        });
+    if (vertices==null) throw new TypeError("Expected an array of vertex coordinates")
+    this.ƒ('drawMesh', floats(vertices), floats(texCoords), colors==null ? null : [...colors], shorts(indices), blendMode)
+  }
+
   // -- typography ------------------------------------------------------------
   get font(){         return this.prop('font') }
   set font(str){             this.prop('font', css.font(str)) }
@@ -742,6 +1083,8 @@ class CanvasRenderingContext2D extends RustClass{
   set textBaseline(mode){    this.prop("textBaseline", mode) }
   get direction(){    return this.prop("direction") }
   set direction(mode){       this.prop("direction", mode) }
+  get fontBoundingBoxAscent(){ return this.prop("fontBoundingBoxAscent") }
+  get fontBoundingBoxDescent(){ return this.prop("fontBoundingBoxDescent") }
 
   measureText(text, maxWidth){
     text = this.textWrap ? text : text + '\u200b' // include trailing whitespace by default
@@ -765,10 +1108,40 @@ class CanvasRenderingContext2D extends RustClass{
   // -- non-standard typography extensions --------------------------------------------
   get fontVariant(){  return this.prop('fontVariant') }
   set fontVariant(str){      this.prop('fontVariant', css.variant(str)) }
+  get fontVariantNumeric(){
+    let nums = this.fontVariant.split(' ').filter(v => css.numericVariants.includes(v))
+    return nums.join(' ') || 'normal'
+  }
+  set fontVariantNumeric(str){
+    let numeric = css.variantNumeric(str)
+    if (numeric){
+      // replace any numeric settings while leaving the other variants in place
+      let others = this.fontVariant.split(' ').filter(v => v!='normal' && !css.numericVariants.includes(v)),
+          nums = numeric=='normal' ? [] : numeric.split(' ');
+      this.fontVariant = [...others, ...nums].join(' ') || 'normal'
+    }
+  }
+  get fontVariantLigatures(){
+    let ligs = this.fontVariant.split(' ').filter(v => css.ligatureVariants.includes(v))
+    return ligs.join(' ') || 'normal'
+  }
+  set fontVariantLigatures(str){
+    let ligatures = css.variantLigatures(str)
+    if (ligatures){
+      // replace any ligature settings while leaving the other variants in place
+      let others = this.fontVariant.split(' ').filter(v => v!='normal' && !css.ligatureVariants.includes(v)),
+          ligs = ligatures=='normal' ? [] : ligatures.split(' ');
+      this.fontVariant = [...others, ...ligs].join(' ') || 'normal'
+    }
+  }
+  get fontKerning(){  return this.prop("fontKerning") }
+  set fontKerning(mode){     this.prop("fontKerning", mode) }
   get textTracking(){ return this.prop("textTracking") }
   set textTracking(ems){     this.prop("textTracking", ems) }
   get textWrap(){     return this.prop("textWrap") }
   set textWrap(flag){        this.prop("textWrap", !!flag) }
+  get writingMode(){  return this.prop("writingMode") }
+  set writingMode(mode){     this.prop("writingMode", mode) }
 
   // -- effects ---------------------------------------------------------------
   get globalCompositeOperation(){ return this.prop("globalCompositeOperation") }
@@ -779,16 +1152,41 @@ class CanvasRenderingContext2D extends RustClass{
   set shadowBlur(level){      this.prop("shadowBlur", level) }
   get shadowColor(){   return this.prop("shadowColor") }
   set shadowColor(color){     this.prop("shadowColor", color) }
+  setShadowColor(r, g, b, a=1){ this.ƒ('setShadowColor', r, g, b, a) }
   get shadowOffsetX(){ return this.prop("shadowOffsetX") }
-  set shadowOffsetX(x){       this.prop("shadowOffsetX", x) }
+  set shadowOffsetX(x){       this.prop("shadowOffsetX", this.#length(x)) }
   get shadowOffsetY(){ return this.prop("shadowOffsetY") }
-  set shadowOffsetY(y){       this.prop("shadowOffsetY", y) }
+  set shadowOffsetY(y){       this.prop("shadowOffsetY", this.#length(y)) }
+  get rootFontSize(){  return this.#rootFontSize }
+  set rootFontSize(size){     if (size > 0 && isFinite(size)) this.#rootFontSize = +size }
+
+  #length(val){
+    // CSS length strings are converted to pixels relative to the current font size & root size
+    return typeof val == 'string' ? css.length(val, css.font(this.font).size, this.#rootFontSize) : val
+  }
   get filter(){        return this.prop('filter') }
-  set filter(str){            this.prop('filter', css.filter(str)) }
+  set filter(str){            this.prop('filter', css.filter(str, css.font(this.font).size, this.#rootFontSize)) }
+
+  // -- encoding --------------------------------------------------------------
+  toDataURL(type, quality){
+    return this.canvas.toDataURLSync(...encoding(this, type, quality))
+  }
+
+  toBlob(callback, type, quality){
+    if (typeof callback!='function') throw new TypeError("Expected a callback function")
+
+    // the page is captured immediately and then encoded on a background thread
+    let [format, opts] = encoding(this, type, quality),
+        mime = Object.keys(BLOB_TYPES).find(mime => BLOB_TYPES[mime]==format)
+    new Promise(res => res(this.canvas.toBuffer(format, opts))).then(
+      data => callback(new Blob([data], {type:mime})),
+      () => callback(null)
+    )
+  }
 
   [REPR](depth, options) {
-    let props = [ "canvas", "currentTransform", "fillStyle", "strokeStyle", "font", "fontVariant",
-                  "direction", "textAlign", "textBaseline", "textTracking", "textWrap", "globalAlpha",
+    let props = [ "canvas", "currentTransform", "fillStyle", "strokeStyle", "currentColor", "font", "fontVariant",
+                  "fontVariantNumeric", "fontVariantLigatures", "fontKerning", "direction", "textAlign", "textBaseline", "textTracking", "textWrap", "writingMode", "globalAlpha",
                   "globalCompositeOperation", "imageSmoothingEnabled", "imageSmoothingQuality", "filter",
                   "shadowBlur", "shadowColor", "shadowOffsetX", "shadowOffsetY", "lineCap", "lineDashOffset",
                   "lineJoin", "lineWidth", "miterLimit" ]
@@ -949,6 +1347,11 @@ class Path2D extends RustClass{
     return wrap(Path2D, path.ƒ(effect, ...args))
   }
 
+  static transformed(ctx){
+    if (!(ctx instanceof CanvasRenderingContext2D)) throw new TypeError("Expected a CanvasRenderingContext2D")
+    return wrap(Path2D, neon.Path2D.from_context(null, core(ctx)))
+  }
+
   constructor(source){
     super(Path2D)
     if (source instanceof Path2D) this.init('from_path', core(source))
@@ -962,6 +1365,9 @@ class Path2D extends RustClass{
   get d(){ return this.prop("d") }
   set d(svg){ return this.prop("d", svg) }
   contains(x, y){ return this.ƒ("contains", x, y)}
+  equals(path, tolerance){
+    return path instanceof Path2D && this.ƒ("equals", core(path), tolerance)
+  }
 
   points(step=1){
     return this.jitter(step, 0).edges
@@ -1012,9 +1418,15 @@ class Path2D extends RustClass{
   jitter(len, amt, seed){ return Path2D.effect("jitter", this, ...arguments) }
   simplify(rule){         return Path2D.effect("simplify", this, rule) }
   unwind(){               return Path2D.effect("unwind", this) }
+  reverse(){              return Path2D.effect("reverse", this) }
   round(radius){          return Path2D.effect("round", this, radius) }
   offset(dx, dy){         return Path2D.effect("offset", this, dx, dy) }
 
+  toStrokePath(ctx){
+    if (!(ctx instanceof CanvasRenderingContext2D)) throw new TypeError("Expected a CanvasRenderingContext2D")
+    return Path2D.effect("toStrokePath", this, core(ctx))
+  }
+
   transform(matrix){
     let terms = arguments.length > 1 ? [...arguments] : matrix
     return Path2D.effect("transform", this, toSkMatrix(terms))
@@ -1036,6 +1448,31 @@ class Path2D extends RustClass{
   }
 }
 
+class PathBuilder{
+  #ctx
+  #path = new Path2D()
+
+  constructor(ctx){ this.#ctx = ctx }
+
+  // segments are collected in a private Path2D (leaving the context's current path untouched)
+  moveTo(x, y){ this.#path.moveTo(...arguments); return this }
+  lineTo(x, y){ this.#path.lineTo(...arguments); return this }
+  closePath(){ this.#path.closePath(); return this }
+  arcTo(x1, y1, x2, y2, radius){ this.#path.arcTo(...arguments); return this }
+  bezierCurveTo(cp1x, cp1y, cp2x, cp2y, x, y){ this.#path.bezierCurveTo(...arguments); return this }
+  quadraticCurveTo(cpx, cpy, x, y){ this.#path.quadraticCurveTo(...arguments); return this }
+  conicCurveTo(cpx, cpy, x, y, weight){ this.#path.conicCurveTo(...arguments); return this }
+  ellipse(x, y, radiusX, radiusY, rotation, startAngle, endAngle, isCCW){ this.#path.ellipse(...arguments); return this }
+  rect(x, y, width, height){ this.#path.rect(...arguments); return this }
+  arc(x, y, radius, startAngle, endAngle, isCCW){ this.#path.arc(...arguments); return this }
+  roundRect(x, y, w, h, r){ this.#path.roundRect(...arguments); return this }
+
+  // terminal methods draw with the context's current state and return the finished path
+  fill(rule){ this.#ctx.fill(this.#path, rule); return new Path2D(this.#path) }
+  stroke(){ this.#ctx.stroke(this.#path); return new Path2D(this.#path) }
+  clip(rule){ this.#ctx.clip(this.#path, rule); return new Path2D(this.#path) }
+}
+
 class TextMetrics{
   constructor([
     width, left, right, ascent, descent,
@@ -1063,7 +1500,7 @@ class TextMetrics{
 const loadImage = src => Object.assign(new Image(), {src}).decode()
 
 module.exports = {
-  Canvas, CanvasGradient, CanvasPattern, CanvasRenderingContext2D, CanvasTexture,
-  TextMetrics, Image, ImageData, Path2D, Window, loadImage, ...geometry,
+  Canvas, CanvasEncoder, CanvasGradient, CanvasPattern, CanvasRenderingContext2D, CanvasTexture,
+  PdfExporter, TextMetrics, Image, ImageData, Path2D, Window, loadImage, ...geometry,
   FontLibrary: new FontLibrary(), App: GUI.App
 }
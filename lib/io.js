@@ -13,6 +13,7 @@ class Format{
         jpg = "image/jpeg",
         jpeg = "image/jpeg",
         webp = "image/webp",
+        jxl = "image/jxl",
         pdf = "application/pdf",
         svg = "image/svg+xml"
 
@@ -20,11 +21,11 @@ class Format{
       toMime: this.toMime.bind(this),
       fromMime: this.fromMime.bind(this),
       expected: isWeb ? `"png", "jpg", or "webp"`
-                      : `"png", "jpg", "pdf", or "svg"`,
+                      : `"png", "jpg", "webp", "jxl", "pdf", or "svg"`,
       formats: isWeb ? {png, jpg, jpeg, webp}
-                     : {png, jpg, jpeg, pdf, svg},
+                     : {png, jpg, jpeg, webp, jxl, pdf, svg},
       mimes: isWeb ? {[png]: "png", [jpg]: "jpg", [webp]: "webp"}
-                   : {[png]: "png", [jpg]: "jpg", [pdf]: "pdf", [svg]: "svg"},
+                   : {[png]: "png", [jpg]: "jpg", [webp]: "webp", [jxl]: "jxl", [pdf]: "pdf", [svg]: "svg"},
     })
   }
 
@@ -41,7 +42,7 @@ class Format{
 // Validation of the options dict shared by the Canvas saveAs, toBuffer, and toDataURL methods
 //
 
-function options(pages, {filename='', extension='', format, page, quality, matte, density, outline, archive}={}){
+function options(pages, {filename='', extension='', format, page, quality, matte, density, outline, lossless, archive}={}){
   var {fromMime, toMime, expected} = new Format(),
       archive = archive || 'canvas',
       ext = format || extension.replace(/@\d+x$/i,'') || extname(filename),
@@ -95,7 +96,9 @@ function options(pages, {filename='', extension='', format, page, quality, matte
     outline = !!outline
   }
 
-  return {filename, pattern, format, mime, pages, padding, quality, matte, density, outline, archive}
+  lossless = !!lossless
+
+  return {filename, pattern, format, mime, pages, padding, quality, matte, density, outline, lossless, archive}
 }
 
 //
@@ -137,14 +140,14 @@ Crc32.prototype.table = (() => {
   return table
 })()
 
-function calloc(size){
+function calloc(size, littleEndian=true){
   let array = new Uint8Array(size),
       view = new DataView(array.buffer),
       buf = {
         array, view, size,
         set8(at, to){ view.setUint8(at, to); return buf },
-        set16(at, to){ view.setUint16(at, to, true); return buf },
-        set32(at, to){ view.setUint32(at, to, true); return buf },
+        set16(at, to){ view.setUint16(at, to, littleEndian); return buf },
+        set32(at, to){ view.setUint32(at, to, littleEndian); return buf },
         bytes(at, to){ array.set(to, at); return buf },
       }
   return buf
@@ -239,6 +242,103 @@ class Zip{
   }
 }
 
+//
+// Animated PNG (q.v. https://wiki.mozilla.org/APNG_Specification)
+//
+
+class APNG{
+  static signature = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]
+  static encoder = new TextEncoder()
+
+  constructor(loops=0){
+    Object.assign(this, {loops, frames:[]})
+  }
+
+  add(png, duration){
+    // split the encoded frame into chunks, keeping only its header and image data
+    let data = new Uint8Array(png),
+        view = new DataView(data.buffer, data.byteOffset, data.byteLength),
+        chunks = []
+
+    for (let at = APNG.signature.length; at + 8 <= data.length; ){
+      let length = view.getUint32(at),
+          type = String.fromCharCode(...data.subarray(at + 4, at + 8))
+      chunks.push({type, data:data.subarray(at + 8, at + 8 + length)})
+      at += 12 + length
+    }
+
+    let header = chunks.find(({type}) => type == 'IHDR'),
+        image = chunks.filter(({type}) => type == 'IDAT').map(({data}) => data)
+    if (!header || !image.length) throw new TypeError("Frame data is not a valid PNG image")
+
+    let [first] = this.frames
+    if (first && first.header.slice(0, 8).some((b, i) => b != header.data[i])){
+      throw new RangeError("All frames of an animation must have the same dimensions")
+    }
+
+    let delay = Math.round(Math.min(Math.max(0, duration), 0xFFFF)) || 0
+    this.frames.push({header:header.data, image, delay})
+  }
+
+  chunk(type, data){
+    let body = calloc(4 + data.length, false)
+          .bytes(0, APNG.encoder.encode(type))
+          .bytes(4, data)
+    return calloc(8 + data.length + 4, false)
+      .set32(0, data.length)                       // payload length
+      .bytes(4, body.array)                        // chunk type & payload
+      .set32(4 + body.size, Crc32.for(body.array)) // checksum
+      .array
+  }
+
+  toBuffer(){
+    if (!this.frames.length) throw new Error("Cannot encode an animation with no frames")
+
+    let [{header}] = this.frames,
+        sequence = 0,
+        chunks = [
+          new Uint8Array(APNG.signature),
+          this.chunk('IHDR', header),
+          this.chunk('acTL', calloc(8, false)
+            .set32(0, this.frames.length) // № frames
+            .set32(4, this.loops)         // № plays (0 = infinite)
+            .array
+          ),
+        ]
+
+    this.frames.forEach(({header, image, delay}, i) => {
+      chunks.push(this.chunk('fcTL', calloc(26, false)
+        .set32(0, sequence++)         // sequence number
+        .bytes(4, header.slice(0, 8)) // width & height
+        .set32(12, 0)                 // x offset
+        .set32(16, 0)                 // y offset
+        .set16(20, delay)             // delay numerator
+        .set16(22, 1000)              // delay denominator (i.e., milliseconds)
+        .set8(24, 0)                  // dispose op: none
+        .set8(25, 0)                  // blend op: source
+        .array
+      ))
+
+      // the first frame doubles as the default image, the rest are stored as frame data
+      for (const data of image){
+        chunks.push(i == 0 ? this.chunk('IDAT', data) : this.chunk('fdAT', calloc(4 + data.length, false)
+          .set32(0, sequence++)
+          .bytes(4, data)
+          .array
+        ))
+      }
+    })
+    chunks.push(this.chunk('IEND', new Uint8Array(0)))
+
+    let output = new Uint8Array(chunks.reduce((len, {length}) => len + length, 0)),
+        cursor = 0
+    for (const chunk of chunks){
+      output.set(chunk, cursor)
+      cursor += chunk.length
+    }
+    return output
+  }
+}
 
 //
 // Browser helpers for converting canvas elements to blobs/buffers/files/zips
@@ -309,4 +409,4 @@ const atScale = (pages, density, matte) => pages.map(page => {
   return scaled
 })
 
-module.exports = {asBuffer, asDownload, asZipDownload, atScale, options}
+module.exports = {asBuffer, asDownload, asZipDownload, atScale, options, APNG}
@@ -90,6 +90,15 @@ pub fn set_engine(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   Ok(cx.undefined())
 }
 
+pub fn features(mut cx: FunctionContext) -> JsResult<JsArray> {
+  // the optional cargo features the library was compiled with
+  let enabled:Vec<String> = [
+    ("metal", cfg!(feature = "metal")), ("vulkan", cfg!(feature = "vulkan")), ("window", cfg!(feature = "window")),
+    ("jxl", cfg!(feature = "jxl")), ("woff2", cfg!(feature = "woff2")),
+  ].iter().filter(|(_, on)| *on).map(|(name, _)| name.to_string()).collect();
+  strings_to_array(&mut cx, &enabled)
+}
+
 pub fn toBuffer(mut cx: FunctionContext) -> JsResult<JsPromise> {
   let this = cx.argument::<BoxedCanvas>(0)?;
   let pages = pages_arg(&mut cx, 1, &this)?;
@@ -98,13 +107,14 @@ pub fn toBuffer(mut cx: FunctionContext) -> JsResult<JsPromise> {
   let density = float_arg(&mut cx, 4, "density")?;
   let outline = bool_arg(&mut cx, 5, "outline")?;
   let matte = color_arg(&mut cx, 6);
+  let lossless = bool_arg(&mut cx, 7, "lossless")?;
 
   let promise = cx
     .task(move || {
       if file_format=="pdf" && pages.len() > 1 {
         pages.as_pdf(quality, density, matte)
       }else{
-        pages.first().encoded_as(&file_format, quality, density, outline, matte, pages.engine)
+        pages.first().encoded_as(&file_format, quality, density, outline, lossless, matte, pages.engine)
       }
     })
     .promise(move |mut cx, result| {
@@ -125,12 +135,13 @@ pub fn toBufferSync(mut cx: FunctionContext) -> JsResult<JsValue> {
   let density = float_arg(&mut cx, 4, "density")?;
   let outline = bool_arg(&mut cx, 5, "outline")?;
   let matte = color_arg(&mut cx, 6);
+  let lossless = bool_arg(&mut cx, 7, "lossless")?;
 
     let encoded = {
       if file_format=="pdf" && pages.len() > 1 {
         pages.as_pdf(quality, density, matte)
       }else{
-        pages.first().encoded_as(&file_format, quality, density, outline, matte, pages.engine)
+        pages.first().encoded_as(&file_format, quality, density, outline, lossless, matte, pages.engine)
       }
     };
 
@@ -155,15 +166,16 @@ pub fn save(mut cx: FunctionContext) -> JsResult<JsPromise> {
   let density = float_arg(&mut cx, 6, "density")?;
   let outline = bool_arg(&mut cx, 7, "outline")?;
   let matte = color_arg(&mut cx, 8);
+  let lossless = bool_arg(&mut cx, 9, "lossless")?;
 
   let promise = cx
     .task(move || {
       if sequence {
-        pages.write_sequence(&name_pattern, &file_format, padding, quality, density, outline, matte)
+        pages.write_sequence(&name_pattern, &file_format, padding, quality, density, outline, lossless, matte)
       } else if file_format == "pdf" {
         pages.write_pdf(&name_pattern, quality, density, matte)
       } else {
-        pages.write_image(&name_pattern, &file_format, quality, density, outline, matte)
+        pages.write_image(&name_pattern, &file_format, quality, density, outline, lossless, matte)
       }
     })
     .promise(move |mut cx, result| {
@@ -185,14 +197,15 @@ pub fn saveSync(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let density = float_arg(&mut cx, 6, "density")?;
   let outline = bool_arg(&mut cx, 7, "outline")?;
   let matte = color_arg(&mut cx, 8);
+  let lossless = bool_arg(&mut cx, 9, "lossless")?;
 
   let result = {
     if sequence {
-      pages.write_sequence(&name_pattern, &file_format, padding, quality, density, outline, matte)
+      pages.write_sequence(&name_pattern, &file_format, padding, quality, density, outline, lossless, matte)
     } else if file_format == "pdf" {
       pages.write_pdf(&name_pattern, quality, density, matte)
     } else {
-      pages.write_image(&name_pattern, &file_format, quality, density, outline, matte)
+      pages.write_image(&name_pattern, &file_format, quality, density, outline, lossless, matte)
     }
   };
 
@@ -3,16 +3,19 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 #![allow(non_snake_case)]
-use std::f32::consts::PI;
+use std::f64::consts::TAU;
 use std::cell::RefCell;
 use neon::{prelude::*, types::buffer::TypedArray};
-use skia_safe::{Point, Rect, RRect, Matrix, Path, PathDirection::{CW, CCW}, PaintStyle};
+use skia_safe::{Point, Rect, IRect, RRect, Matrix, Path, PathDirection::{CW, CCW}, PaintStyle, Color, Color4f, BlendMode, ClipOp,
+                PaintCap, ColorType, AlphaType, Image as SkImage, ISize, YUVColorSpace};
+use skia_safe::vertices::{self, VertexMode};
+use skia_safe::canvas::PointMode;
 use skia_safe::path::AddPathMode::Append;
 use skia_safe::path::AddPathMode::Extend;
-use skia_safe::textlayout::{TextDirection};
+use skia_safe::textlayout::{TextDirection, TextAlign};
 use skia_safe::PaintStyle::{Fill, Stroke};
 
-use super::{Context2D, BoxedContext2D, Dye};
+use super::{Context2D, ContextAttributes, BoxedContext2D, Dye, State};
 use crate::canvas::{Canvas, BoxedCanvas};
 use crate::path::{Path2D, BoxedPath2D};
 use crate::image::{Image, BoxedImage};
@@ -25,14 +28,35 @@ use crate::utils::*;
 //
 
 pub fn new(mut cx: FunctionContext) -> JsResult<BoxedContext2D> {
-  let this = RefCell::new(Context2D::new());
   let parent = cx.argument::<BoxedCanvas>(1)?;
+  let attributes = ContextAttributes{
+    alpha: bool_arg_or(&mut cx, 2, true),
+    will_read_frequently: bool_arg_or(&mut cx, 3, false),
+    measure_text_cache: float_arg_or(&mut cx, 4, 0.0).max(0.0) as usize,
+  };
   let parent = parent.borrow();
-
-  this.borrow_mut().reset_size((parent.width, parent.height));
+  let this = RefCell::new(Context2D::with_size((parent.width, parent.height), attributes));
   Ok(cx.boxed(this))
 }
 
+pub fn getContextAttributes(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let attributes = this.borrow().attributes;
+
+  let js_object: Handle<JsObject> = cx.empty_object();
+  let alpha = cx.boolean(attributes.alpha);
+  let color_space = cx.string("srgb");
+  let desynchronized = cx.boolean(false);
+  let will_read_frequently = cx.boolean(attributes.will_read_frequently);
+  js_object.set(&mut cx, "alpha", alpha)?;
+  js_object.set(&mut cx, "colorSpace", color_space)?;
+  js_object.set(&mut cx, "desynchronized", desynchronized)?;
+  js_object.set(&mut cx, "willReadFrequently", will_read_frequently)?;
+  let measure_text_cache = cx.number(attributes.measure_text_cache as f64);
+  js_object.set(&mut cx, "measureTextCache", measure_text_cache)?;
+  Ok(js_object)
+}
+
 pub fn resetSize(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let parent = cx.argument::<BoxedCanvas>(1)?;
@@ -64,6 +88,12 @@ pub fn set_size(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   Ok(cx.undefined())
 }
 
+pub fn dispose(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  this.borrow_mut().dispose();
+  Ok(cx.undefined())
+}
+
 pub fn reset(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
@@ -99,7 +129,7 @@ pub fn transform(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let nums = opt_float_args(&mut cx, 1..7);
   if let [m11, m12, m21, m22, dx, dy] = nums.as_slice(){
     let matrix = Matrix::new_all(*m11, *m21, *dx, *m12, *m22, *dy, 0.0, 0.0, 1.0);
-    this.with_matrix(|ctm| ctm.pre_concat(&matrix) );
+    this.concat_matrix(&matrix);
   }
   Ok(cx.undefined())
 }
@@ -111,7 +141,7 @@ pub fn translate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
   let xy = opt_float_args(&mut cx, 1..3);
   if let [dx, dy] = xy.as_slice(){
-    this.with_matrix(|ctm| ctm.pre_translate((*dx, *dy)) );
+    this.concat_matrix(&Matrix::translate((*dx, *dy)));
   }
   Ok(cx.undefined())
 }
@@ -123,7 +153,7 @@ pub fn scale(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
   let xy = opt_float_args(&mut cx, 1..3);
   if let [m11, m22] = xy.as_slice(){
-    this.with_matrix(|ctm| ctm.pre_scale((*m11, *m22), None) );
+    this.concat_matrix(&Matrix::scale((*m11, *m22)));
   }
   Ok(cx.undefined())
 }
@@ -133,9 +163,21 @@ pub fn rotate(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let mut this = this.borrow_mut();
   check_argc(&mut cx, 2)?;
 
-  if let Some(radians) = opt_float_arg(&mut cx, 1){
-    let degrees = radians / PI * 180.0;
-    this.with_matrix(|ctm| ctm.pre_rotate(degrees, None) );
+  if let Some(radians) = opt_double_arg(&mut cx, 1){
+    // reduce the angle while still in double-precision so large values don't lose accuracy as f32s
+    let degrees = (radians % TAU).to_degrees();
+    this.concat_matrix(&Matrix::rotate_deg(degrees as f32));
+  }
+  Ok(cx.undefined())
+}
+
+pub fn rotateDeg(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  check_argc(&mut cx, 2)?;
+
+  if let Some(degrees) = opt_double_arg(&mut cx, 1){
+    this.concat_matrix(&Matrix::rotate_deg((degrees % 360.0) as f32));
   }
   Ok(cx.undefined())
 }
@@ -144,7 +186,7 @@ pub fn resetTransform(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
 
-  this.with_matrix(|ctm| ctm.reset() );
+  this.reset_transform();
   Ok(cx.undefined())
 }
 
@@ -189,9 +231,19 @@ pub fn get_currentTransform(mut cx: FunctionContext) -> JsResult<JsArray> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let this = this.borrow();
 
-  let array = JsArray::new(&mut cx, 9);
-  for i in 0..9 {
-    let num = cx.number(this.state.matrix[i as usize]);
+  // 3×3 matrices are returned in skia's order, 4×4 ones in row-major order
+  let terms:Vec<f32> = match this.matrix_3d(){
+    Some(m44) => {
+      let mut terms = [0.0; 16];
+      m44.get_row_major(&mut terms);
+      terms.to_vec()
+    },
+    None => (0..9).map(|i| this.state.matrix[i]).collect()
+  };
+
+  let array = JsArray::new(&mut cx, terms.len() as u32);
+  for (i, term) in terms.iter().enumerate() {
+    let num = cx.number(*term);
     array.set(&mut cx, i as u32, num)?;
   }
   Ok(array)
@@ -201,12 +253,36 @@ pub fn set_currentTransform(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
 
-  if let Some(matrix) = opt_matrix_arg(&mut cx, 1){
-    this.with_matrix(|ctm| ctm.reset().pre_concat(&matrix) );
+  if let Some(m44) = opt_matrix_3d_arg(&mut cx, 1){
+    this.set_matrix_3d(m44);
+  }else if let Some(matrix) = opt_matrix_arg(&mut cx, 1){
+    this.set_matrix(&matrix);
   }
   Ok(cx.undefined())
 }
 
+pub fn transformPoint(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let this = this.borrow();
+  let x = float_arg(&mut cx, 1, "x")?;
+  let y = float_arg(&mut cx, 2, "y")?;
+
+  let pt = this.transform_point(x, y);
+  floats_to_array(&mut cx, &[pt.x, pt.y])
+}
+
+pub fn inverseTransformPoint(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let this = this.borrow();
+  let x = float_arg(&mut cx, 1, "x")?;
+  let y = float_arg(&mut cx, 2, "y")?;
+
+  match this.inverse_transform_point(x, y){
+    Some(pt) => floats_to_array(&mut cx, &[pt.x, pt.y]),
+    None => Ok(cx.null().upcast())
+  }
+}
+
 
 //
 // Bézier Paths
@@ -220,6 +296,19 @@ pub fn beginPath(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   Ok(cx.undefined())
 }
 
+pub fn get_currentPath(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let path = this.borrow().current_path();
+  Ok(cx.boxed(RefCell::new(Path2D{path})))
+}
+
+pub fn pathBounds(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let path = opt_path2d_arg(&mut cx, 1);
+  let bounds = this.borrow().path_bounds(path.as_ref());
+  floats_to_array(&mut cx, &[bounds.left, bounds.top, bounds.width(), bounds.height()])
+}
+
 // -- primitives ------------------------------------------------------------------------
 
 pub fn rect(mut cx: FunctionContext) -> JsResult<JsUndefined> {
@@ -265,6 +354,9 @@ pub fn arc(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let nums = opt_float_args(&mut cx, 1..6);
   let ccw = bool_arg_or(&mut cx, 6, false);
   if let [x, y, radius, start_angle, end_angle] = nums.as_slice(){
+    if *radius < 0.0 {
+      return cx.throw_range_error("radius cannot be negative")
+    }
     let matrix = this.state.matrix;
     let mut arc = Path2D::new();
     arc.add_ellipse((*x, *y), (*radius, *radius), 0.0, *start_angle, *end_angle, ccw);
@@ -282,7 +374,7 @@ pub fn ellipse(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let ccw = bool_arg_or(&mut cx, 8, false);
   if let [x, y, x_radius, y_radius, rotation, start_angle, end_angle] = nums.as_slice(){
     if *x_radius < 0.0 || *y_radius < 0.0 {
-      return cx.throw_error("radii cannot be negative")
+      return cx.throw_range_error("radii cannot be negative")
     }
     let matrix = this.state.matrix;
     let mut arc = Path2D::new();
@@ -328,6 +420,9 @@ pub fn arcTo(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let radius = opt_float_arg(&mut cx, 5);
   if let Some(radius) = radius {
     if let [src, dst] = this.map_points(&coords).as_slice(){
+      if radius < 0.0 {
+        return cx.throw_range_error("radius cannot be negative")
+      }
       if this.path.is_empty(){ this.path.move_to(*src); }
       this.path.arc_to_tangent(*src, *dst, radius);
     }
@@ -426,7 +521,31 @@ pub fn clip(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   if clip.is_some() { shift += 1; }
 
   let rule = fill_rule_arg_or(&mut cx, shift, "nonzero")?;
-  this.clip_path(clip, rule);
+  this.clip_path(clip, rule, ClipOp::Intersect);
+
+  Ok(cx.undefined())
+}
+
+pub fn clipOut(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+
+  let mut shift = 1;
+  let clip = opt_path2d_arg(&mut cx, 1);
+  if clip.is_some() { shift += 1; }
+
+  let rule = fill_rule_arg_or(&mut cx, shift, "nonzero")?;
+  this.clip_path(clip, rule, ClipOp::Difference);
+
+  Ok(cx.undefined())
+}
+
+pub fn clipStroke(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+
+  let clip = opt_path2d_arg(&mut cx, 1);
+  this.clip_stroke(clip);
 
   Ok(cx.undefined())
 }
@@ -452,9 +571,133 @@ pub fn stroke(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   Ok(cx.undefined())
 }
 
+pub fn drawPath(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let mut path = match opt_path2d_arg(&mut cx, 1){
+    Some(path) => path,
+    None => return cx.throw_type_error("Expected a Path2D")
+  };
+  let style = match string_arg(&mut cx, 2, "style")?.as_str(){
+    "fill" => Fill,
+    _ => Stroke
+  };
+  path.set_fill_type(fill_rule_arg_or(&mut cx, 3, "nonzero")?);
+
+  // build the paint from a copy of the current state with the options layered on top, leaving
+  // the context's own settings (and the recorder's matrix & clip) untouched
+  let opts = cx.argument::<JsObject>(4)?;
+  let mut state = this.state.clone();
+  apply_draw_options(&mut cx, &mut state, opts)?;
+
+  let saved = std::mem::replace(&mut this.state, state);
+  if this.state.matrix.invert().is_some(){
+    let paint = this.paint_for_drawing(style);
+    this.draw_path_with_paint(&path, &paint);
+  }
+  this.state = saved;
+
+  Ok(cx.undefined())
+}
+
+fn apply_draw_options(cx: &mut FunctionContext, state: &mut State, opts: Handle<JsObject>) -> NeonResult<()>{
+  // values are validated the same way as by the corresponding property setters
+  let keys = opts.get_own_property_names(cx)?.to_vec(cx)?;
+  for name in strings_in(cx, &keys){
+    let val:Handle<JsValue> = opts.get(cx, name.as_str())?;
+    if val.is_a::<JsUndefined, _>(cx){ continue }
+
+    let num = val.downcast::<JsNumber, _>(cx).ok().map(|n| n.value(cx) as f32).filter(|n| n.is_finite());
+    let text = val.downcast::<JsString, _>(cx).ok().map(|s| s.value(cx)).unwrap_or_default();
+    match name.as_str(){
+      "fillStyle" => if let Some(dye) = Dye::new(cx, val, state.current_color){
+        state.fill_style = dye;
+      },
+      "strokeStyle" => if let Some(dye) = Dye::new(cx, val, state.current_color){
+        state.stroke_style = dye;
+      },
+      "globalAlpha" => if let Some(alpha) = num.filter(|n| (0.0..=1.0).contains(n)){
+        state.global_alpha = alpha;
+      },
+      "globalCompositeOperation" => if let Some(mode) = to_blend_mode(&text){
+        state.global_composite_operation = mode;
+        state.paint.set_blend_mode(mode);
+      },
+      "filter" => if let Ok(spec) = val.downcast::<JsObject, _>(cx){
+        let (filter_text, specs) = filter_in(cx, spec)?;
+        state.filter = Filter::new(&filter_text, &specs);
+      },
+      "imageSmoothingEnabled" => if let Ok(flag) = val.downcast::<JsBoolean, _>(cx){
+        state.image_filter.smoothing = flag.value(cx);
+      },
+      "imageSmoothingQuality" => if let Some(mode) = to_filter_quality(&text){
+        state.image_filter.quality = mode;
+      },
+      "lineCap" => if let Some(mode) = to_stroke_cap(&text){
+        state.paint.set_stroke_cap(mode);
+      },
+      "lineDash" => if let Ok(list) = val.downcast::<JsArray, _>(cx){
+        let list = list.to_vec(cx)?;
+        let mut intervals = floats_in(cx, &list).into_iter()
+          .filter(|n| *n >= 0.0)
+          .collect::<Vec<f32>>();
+        if list.len() == intervals.len(){
+          if intervals.len() % 2 == 1{
+            intervals.append(&mut intervals.clone());
+          }
+          state.line_dash_list = intervals;
+          state.line_dash_caps = vec![];
+        }
+      },
+      "lineDashFit" => if let Some(fit) = to_1d_style(&text){
+        state.line_dash_fit = fit;
+      },
+      "lineDashMarker" => {
+        if let Ok(marker) = val.downcast::<BoxedPath2D, _>(cx){
+          state.line_dash_marker = Some(marker.borrow().path.clone());
+        }else if val.is_a::<JsNull, _>(cx){
+          state.line_dash_marker = None;
+        }else{
+          return cx.throw_type_error("Expected a Path2D object (or null)")
+        }
+      },
+      "lineDashOffset" => if let Some(offset) = num{
+        state.line_dash_offset = offset;
+      },
+      "lineJoin" => if let Some(mode) = to_stroke_join(&text){
+        state.paint.set_stroke_join(mode);
+      },
+      "lineWidth" => if let Some(width) = num.filter(|n| *n > 0.0){
+        state.paint.set_stroke_width(width);
+        state.stroke_width = width;
+      },
+      "miterLimit" => if let Some(limit) = num.filter(|n| *n > 0.0){
+        state.paint.set_stroke_miter(limit);
+      },
+      "shadowBlur" => if let Some(blur) = num.filter(|n| *n >= 0.0){
+        state.shadow_blur = blur;
+      },
+      "shadowColor" => if let Some(color) = color_or_current(cx, val, state.current_color){
+        let Color4f{r, g, b, a} = color.into();
+        state.set_shadow_color_f(r, g, b, a);
+      },
+      "shadowOffsetX" => if let Some(x) = num{
+        state.shadow_offset.x = x;
+      },
+      "shadowOffsetY" => if let Some(y) = num{
+        state.shadow_offset.y = y;
+      },
+      _ => {}
+    }
+  }
+  Ok(())
+}
+
 pub fn fillRect(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
-  let nums = float_args(&mut cx, 1..5)?;
+  check_argc(&mut cx, 5)?;
+
+  let nums = opt_float_args(&mut cx, 1..5);
   if let [x, y, w, h] = nums.as_slice() {
     let rect = Rect::from_xywh(*x, *y, *w, *h);
     let path = Path::rect(rect, None);
@@ -465,7 +708,9 @@ pub fn fillRect(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
 pub fn strokeRect(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
-  let nums = float_args(&mut cx, 1..5)?;
+  check_argc(&mut cx, 5)?;
+
+  let nums = opt_float_args(&mut cx, 1..5);
   if let [x, y, w, h] = nums.as_slice() {
     let rect = Rect::from_xywh(*x, *y, *w, *h);
     let path = Path::rect(rect, None);
@@ -477,7 +722,9 @@ pub fn strokeRect(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 pub fn clearRect(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
-  let nums = float_args(&mut cx, 1..5)?;
+  check_argc(&mut cx, 5)?;
+
+  let nums = opt_float_args(&mut cx, 1..5);
   if let [x, y, w, h] = nums.as_slice() {
     let rect = Rect::from_xywh(*x, *y, *w, *h);
     this.clear_rect(&rect);
@@ -485,6 +732,68 @@ pub fn clearRect(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   Ok(cx.undefined())
 }
 
+pub fn drawCircle(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  check_argc(&mut cx, 4)?;
+
+  let nums = opt_float_args(&mut cx, 1..4);
+  if let [x, y, radius] = nums.as_slice() {
+    if *radius < 0.0 {
+      return cx.throw_range_error("radius cannot be negative")
+    }
+    this.draw_circle(Point::new(*x, *y), *radius);
+  }
+  Ok(cx.undefined())
+}
+
+pub fn drawOval(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  check_argc(&mut cx, 5)?;
+
+  let style = match string_arg_or(&mut cx, 5, "fill").as_str(){
+    "stroke" => PaintStyle::Stroke,
+    _ => PaintStyle::Fill
+  };
+
+  let nums = opt_float_args(&mut cx, 1..5);
+  if let [x, y, w, h] = nums.as_slice() {
+    let rect = Rect::from_xywh(*x, *y, *w, *h).sorted();
+    this.draw_oval(&rect, style);
+  }
+  Ok(cx.undefined())
+}
+
+pub fn drawLine(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  check_argc(&mut cx, 5)?;
+
+  let nums = opt_float_args(&mut cx, 1..5);
+  if let [x0, y0, x1, y1] = nums.as_slice() {
+    this.draw_line(Point::new(*x0, *y0), Point::new(*x1, *y1));
+  }
+  Ok(cx.undefined())
+}
+
+pub fn drawPoints(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mode = match string_arg(&mut cx, 1, "mode")?.as_str(){
+    "points" => PointMode::Points,
+    "lines" => PointMode::Lines,
+    "polygon" => PointMode::Polygon,
+    name => return cx.throw_type_error(format!("Unknown drawPoints mode \"{}\" (expected \"points\", \"lines\", or \"polygon\")", name))
+  };
+  let points = cx.argument::<JsTypedArray<f32>>(2)?.as_slice(&cx)
+    .chunks_exact(2)
+    .map(|xy| Point::new(xy[0], xy[1]))
+    .collect::<Vec<Point>>();
+
+  this.borrow_mut().draw_points(mode, &points);
+  Ok(cx.undefined())
+}
+
 
 // fill & stoke properties --------------------------------------------------------------
 
@@ -500,7 +809,8 @@ pub fn set_fillStyle(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let mut this = this.borrow_mut();
   let arg = cx.argument::<JsValue>(1)?;
 
-  if let Some(dye) = Dye::new(&mut cx, arg) {
+  let current_color = this.state.current_color;
+  if let Some(dye) = Dye::new(&mut cx, arg, current_color) {
     this.state.fill_style = dye;
   }
   Ok(cx.undefined())
@@ -518,12 +828,29 @@ pub fn set_strokeStyle(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let mut this = this.borrow_mut();
   let arg = cx.argument::<JsValue>(1)?;
 
-  if let Some(dye) = Dye::new(&mut cx, arg) {
+  let current_color = this.state.current_color;
+  if let Some(dye) = Dye::new(&mut cx, arg, current_color) {
     this.state.stroke_style = dye;
   }
   Ok(cx.undefined())
 }
 
+pub fn get_currentColor(mut cx: FunctionContext) -> JsResult<JsValue> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let this = this.borrow();
+  let current_color = this.state.current_color;
+  color_to_css(&mut cx, &current_color)
+}
+
+pub fn set_currentColor(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  if let Some(color) = color_arg(&mut cx, 1){
+    this.state.current_color = color;
+  }
+  Ok(cx.undefined())
+}
+
 //
 // Line Style
 //
@@ -592,8 +919,34 @@ pub fn setLineDash(mut cx: FunctionContext) -> JsResult<JsUndefined> {
         intervals.append(&mut intervals.clone());
       }
 
-      this.state.line_dash_list = intervals
+      this.set_line_dash_caps(intervals, vec![]);
+    }
+  }
+
+  Ok(cx.undefined())
+}
+
+pub fn setLineDashAdvanced(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let lengths = cx.argument::<JsArray>(1)?.to_vec(&mut cx)?;
+  let caps = cx.argument::<JsArray>(2)?.to_vec(&mut cx)?;
+
+  let mut intervals = floats_in(&mut cx, &lengths).iter().cloned()
+    .filter(|n| *n >= 0.0 && n.is_finite())
+    .collect::<Vec<f32>>();
+  let mut caps = strings_in(&mut cx, &caps).iter()
+    .map(|name| to_stroke_cap(name))
+    .collect::<Option<Vec<PaintCap>>>()
+    .unwrap_or_default();
+
+  // like setLineDash, invalid lists are ignored and odd-length lists are repeated to make them even
+  if intervals.len() == lengths.len() && caps.len() == lengths.len(){
+    if intervals.len() % 2 == 1{
+      intervals.append(&mut intervals.clone());
+      caps.append(&mut caps.clone());
     }
+    this.set_line_dash_caps(intervals, caps);
   }
 
   Ok(cx.undefined())
@@ -714,7 +1067,9 @@ fn _layout_rects(width:f32, height:f32, nums:&[f32]) -> Option<(Rect, Rect)> {
            Rect::from_xywh(nums[4], nums[5], nums[6], nums[7]) ),
     _ => return None
   };
-  Some((src, dst))
+
+  // negative widths & heights shift the rects' origins rather than mirroring the image
+  Some((src.sorted(), dst.sorted()))
 }
 
 pub fn drawImage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
@@ -724,7 +1079,18 @@ pub fn drawImage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     if let Ok(obj) = source.downcast::<BoxedImage, _>(&mut cx){
       (&obj.borrow().image).clone()
     }else if let Ok(obj) = source.downcast::<BoxedContext2D, _>(&mut cx){
+      if let Some(msg) = obj.borrow().source_error(){
+        return cx.throw_error(msg)
+      }
       obj.borrow().get_image()
+    }else if let Ok(obj) = source.downcast::<JsObject, _>(&mut cx){
+      let width = float_for_key(&mut cx, &obj, "width")?;
+      let height = float_for_key(&mut cx, &obj, "height")?;
+      if width < 1.0 || height < 1.0 {
+        return cx.throw_error("Cannot draw an ImageData with a width or height of zero")
+      }
+      let buffer: Handle<JsBuffer> = obj.get(&mut cx, "data")?;
+      Image::from_pixels(buffer.as_slice(&cx), width, height)
     }else{
       return Ok(cx.undefined())
     }
@@ -743,6 +1109,10 @@ pub fn drawImage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let nums = float_args(&mut cx, 2..argc)?;
   match _layout_rects(width, height, &nums){
     Some((src, dst)) => {
+      if src.is_empty() || dst.is_empty(){
+        return Ok(cx.undefined()) // zero-sized rects are a no-op
+      }
+
       // shrink src to lie within the image bounds and adjust dst proportionately
       let (src, dst) = fit_bounds(width, height, src, dst);
 
@@ -754,10 +1124,150 @@ pub fn drawImage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   }
 }
 
+fn image_source<'a>(cx: &mut FunctionContext<'a>, source:Handle<'a, JsValue>) -> NeonResult<Option<SkImage>> {
+  // returns None for unsupported sources (which are silently ignored) and throws if the image isn't drawable
+  let image = {
+    if let Ok(obj) = source.downcast::<BoxedImage, _>(cx){
+      (&obj.borrow().image).clone()
+    }else if let Ok(obj) = source.downcast::<BoxedContext2D, _>(cx){
+      if let Some(msg) = obj.borrow().source_error(){
+        return cx.throw_error(msg)
+      }
+      obj.borrow().get_image()
+    }else{
+      return Ok(None)
+    }
+  };
+
+  match image{
+    Some(image) => Ok(Some(image)),
+    None => cx.throw_error("Cannot draw incomplete image (has it finished loading?)")
+  }
+}
+
+pub fn drawNinePatch(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let source = cx.argument::<JsValue>(1)?;
+  let image = match image_source(&mut cx, source)?{
+    Some(image) => Some(image),
+    None => return Ok(cx.undefined())
+  };
+
+  let nums = float_args(&mut cx, 2..10)?;
+  if let [c_x, c_y, c_w, c_h, x, y, w, h] = nums.as_slice(){
+    let center = Rect::from_xywh(*c_x, *c_y, *c_w, *c_h).sorted().round();
+    let dst = Rect::from_xywh(*x, *y, *w, *h).sorted();
+    if !dst.is_empty(){
+      this.borrow_mut().draw_nine_patch(&image, &center, &dst);
+    }
+  }
+  Ok(cx.undefined())
+}
+
+pub fn tileImage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let source = cx.argument::<JsValue>(1)?;
+  let image = match image_source(&mut cx, source)?{
+    Some(image) => Some(image),
+    None => return Ok(cx.undefined())
+  };
+
+  let nums = float_args(&mut cx, 2..12)?;
+  if let [s_x, s_y, s_w, s_h, x, y, w, h, x_spacing, y_spacing] = nums.as_slice(){
+    if *x_spacing < 0.0 || *y_spacing < 0.0 {
+      return cx.throw_range_error("Tile spacing cannot be negative")
+    }
+    let src = Rect::from_xywh(*s_x, *s_y, *s_w, *s_h).sorted();
+    let dst = Rect::from_xywh(*x, *y, *w, *h).sorted();
+    if !src.is_empty() && !dst.is_empty(){
+      this.borrow_mut().tile_image(&image, &src, &dst, (*x_spacing, *y_spacing));
+    }
+  }
+  Ok(cx.undefined())
+}
+
+pub fn drawSprite(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let source = cx.argument::<JsValue>(1)?;
+  let image = match image_source(&mut cx, source)?{
+    Some(image) => Some(image),
+    None => return Ok(cx.undefined())
+  };
+
+  let nums = float_args(&mut cx, 2..8)?;
+  if let [s_x, s_y, s_w, s_h, x, y] = nums.iter().map(|n| n.floor() as i32).collect::<Vec<i32>>().as_slice(){
+    let src = IRect::from_xywh(*s_x, *s_y, *s_w, *s_h).sorted();
+    if !src.is_empty(){
+      this.borrow_mut().draw_sprite(&image, &src, *x, *y);
+    }
+  }
+  Ok(cx.undefined())
+}
+
+pub fn drawYUVFrame(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let frame = cx.argument::<JsObject>(1)?;
+  let width = float_for_key(&mut cx, &frame, "width")?;
+  let height = float_for_key(&mut cx, &frame, "height")?;
+  if width < 1.0 || height < 1.0 || width.fract() != 0.0 || height.fract() != 0.0 {
+    return cx.throw_range_error(format!("YUV frame dimensions must be positive integers (got {}×{})", width, height))
+  }
+
+  let color_space = match string_for_key(&mut cx, &frame, "colorSpace")?.as_str(){
+    "bt709" => YUVColorSpace::Rec709_Limited,
+    "bt601" => YUVColorSpace::Rec601_Limited,
+    "jpeg" => YUVColorSpace::JPEG_Full,
+    other => return cx.throw_error(format!("Unknown YUV color space \"{}\" (expected bt709, bt601, or jpeg)", other))
+  };
+
+  // planes can contain either 8- or 16-bit samples (but must all use the same depth)
+  let mut planes = vec![];
+  let mut depth = None;
+  for key in ["y", "u", "v"]{
+    let plane:Handle<JsValue> = frame.get(&mut cx, key)?;
+    let (bytes, bits) = if let Ok(samples) = plane.downcast::<JsTypedArray<u8>, _>(&mut cx){
+      (samples.as_slice(&cx).to_vec(), 8)
+    }else if let Ok(samples) = plane.downcast::<JsTypedArray<u16>, _>(&mut cx){
+      (samples.as_slice(&cx).iter().flat_map(|n| n.to_ne_bytes()).collect(), 16)
+    }else{
+      return cx.throw_type_error(format!("Expected a Uint8Array or Uint16Array for the \"{}\" plane", key))
+    };
+    if *depth.get_or_insert(bits) != bits {
+      return cx.throw_type_error("YUV planes must all use the same bit depth")
+    }
+    planes.push(bytes);
+  }
+
+  let dims = ISize::new(width as i32, height as i32);
+  let image = match Image::from_yuv([&planes[0], &planes[1], &planes[2]], dims, depth==Some(16), color_space){
+    Ok(image) => Some(image),
+    Err(msg) => return cx.throw_error(msg)
+  };
+
+  let argc = cx.len() as usize;
+  let nums = float_args(&mut cx, 2..argc)?;
+  match _layout_rects(width, height, &nums){
+    Some((src, dst)) => {
+      if src.is_empty() || dst.is_empty(){
+        return Ok(cx.undefined()) // zero-sized rects are a no-op
+      }
+
+      let (src, dst) = fit_bounds(width, height, src, dst);
+      let mut this = this.borrow_mut();
+      this.draw_image(&image, &src, &dst);
+      Ok(cx.undefined())
+    },
+    None => cx.throw_error(format!("Expected 2, 4, or 8 coordinates (got {})", nums.len()))
+  }
+}
+
 pub fn drawCanvas(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let context = cx.argument::<BoxedContext2D>(1)?;
 
+  if let Some(msg) = context.borrow().source_error(){
+    return cx.throw_error(msg)
+  }
   let (width, height) = {
     let bounds = context.borrow().bounds;
     (bounds.width(), bounds.height())
@@ -767,19 +1277,153 @@ pub fn drawCanvas(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let nums = float_args(&mut cx, 2..argc)?;
   match _layout_rects(width, height, &nums){
     Some((src, dst)) => {
+      if src.is_empty() || dst.is_empty(){
+        return Ok(cx.undefined()) // zero-sized rects are a no-op
+      }
+
       let pict = {
         let mut ctx = context.borrow_mut();
         ctx.get_picture()
       };
 
       let mut this = this.borrow_mut();
-      this.draw_picture(&pict, &src, &dst);
+      this.draw_picture_rect(&pict, &src, &dst);
       Ok(cx.undefined())
     },
     None => cx.throw_error(format!("Expected 2, 4, or 8 coordinates (got {})", nums.len()))
   }
 }
 
+pub fn drawPicture(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let context = cx.argument::<BoxedContext2D>(1)?;
+  let matrix = opt_matrix_arg(&mut cx, 2).unwrap_or_else(Matrix::new_identity);
+  let alpha = float_arg_or(&mut cx, 3, 1.0);
+
+  if let Some(msg) = context.borrow().source_error(){
+    return cx.throw_error(msg)
+  }
+
+  let pict = {
+    let mut ctx = context.borrow_mut();
+    ctx.get_picture()
+  };
+
+  let mut this = this.borrow_mut();
+  this.draw_picture(&pict, &matrix, alpha);
+  Ok(cx.undefined())
+}
+
+pub fn compositeWith(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let context = cx.argument::<BoxedContext2D>(1)?;
+  let mode_name = string_arg(&mut cx, 2, "blendMode")?;
+  let alpha = float_arg_or(&mut cx, 3, 1.0);
+  let dst_point = match opt_float_args(&mut cx, 4..6).as_slice(){
+    [x, y] => Some(Point::new(*x, *y)),
+    _ => None
+  };
+
+  let blend_mode = match to_blend_mode(&mode_name){
+    Some(mode) => mode,
+    None => return cx.throw_type_error(format!("Unknown blend mode: \"{}\"", mode_name))
+  };
+
+  if let Some(msg) = context.borrow().source_error(){
+    return cx.throw_error(msg)
+  }
+
+  let pict = {
+    let mut ctx = context.borrow_mut();
+    ctx.get_picture()
+  };
+
+  let mut this = this.borrow_mut();
+  this.composite_from(&pict, blend_mode, alpha, dst_point);
+  Ok(cx.undefined())
+}
+
+pub fn applyBackdropFilter(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let (filter_text, specs) = filter_arg(&mut cx, 1)?;
+  let mut filter = Filter::new(&filter_text, &specs);
+
+  let nums = opt_float_args(&mut cx, 2..6);
+  let rect = match nums.as_slice(){
+    [x, y, w, h] => Some(Rect::from_xywh(*x, *y, *w, *h).sorted()),
+    _ => None
+  };
+
+  this.backdrop_filter(&mut filter, rect);
+  Ok(cx.undefined())
+}
+
+pub fn drawMesh(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let to_points = |nums:&[f32]| nums.chunks_exact(2).map(|xy| Point::new(xy[0], xy[1])).collect::<Vec<Point>>();
+
+  let positions = to_points(cx.argument::<JsTypedArray<f32>>(1)?.as_slice(&cx));
+  let tex_coords = match cx.argument::<JsValue>(2)?.downcast::<JsTypedArray<f32>, _>(&mut cx){
+    Ok(nums) => Some(to_points(nums.as_slice(&cx))),
+    Err(_) => None
+  };
+  let colors = match cx.argument::<JsValue>(3)?.downcast::<JsArray, _>(&mut cx){
+    Ok(list) => {
+      let list = list.to_vec(&mut cx)?;
+      let colors = list.iter().filter_map(|clr| color_in(&mut cx, *clr)).collect::<Vec<Color>>();
+      if colors.len() != list.len(){
+        return cx.throw_type_error("Could not parse mesh colors as CSS colors")
+      }
+      Some(colors)
+    },
+    Err(_) => None
+  };
+  let indices = match cx.argument::<JsValue>(4)?.downcast::<JsTypedArray<u16>, _>(&mut cx){
+    Ok(idxs) => Some(idxs.as_slice(&cx).to_vec()),
+    Err(_) => None
+  };
+  let blend_mode = match string_arg_or(&mut cx, 5, "modulate").as_str(){
+    "modulate" => Some(BlendMode::Modulate),
+    name => to_blend_mode(name)
+  };
+
+  // validate the mesh's geometry before handing it off to skia
+  let count = positions.len();
+  let corners = indices.as_ref().map(|idxs| idxs.len()).unwrap_or(count);
+  if count < 3 || corners % 3 != 0 {
+    return cx.throw_error("Mesh vertices must describe complete triangles")
+  }
+  if indices.as_ref().map(|idxs| idxs.iter().any(|i| *i as usize >= count)).unwrap_or(false){
+    return cx.throw_range_error("Mesh indices must refer to existing vertices")
+  }
+  if tex_coords.as_ref().map(|pts| pts.len() != count).unwrap_or(false){
+    return cx.throw_error("Expected one texture coordinate per vertex")
+  }
+  if colors.as_ref().map(|clrs| clrs.len() != count).unwrap_or(false){
+    return cx.throw_error("Expected one color per vertex")
+  }
+  let blend_mode = match blend_mode{
+    Some(mode) => mode,
+    None => return cx.throw_error("Unknown blend mode")
+  };
+
+  let mut flags = vertices::BuilderFlags::empty();
+  if tex_coords.is_some(){ flags |= vertices::BuilderFlags::HAS_TEX_COORDS }
+  if colors.is_some(){ flags |= vertices::BuilderFlags::HAS_COLORS }
+
+  let idx_count = indices.as_ref().map(|idxs| idxs.len()).unwrap_or(0);
+  let mut builder = vertices::Builder::new(VertexMode::Triangles, count, idx_count, flags);
+  builder.positions().copy_from_slice(&positions);
+  if let (Some(dst), Some(src)) = (builder.tex_coords(), &tex_coords){ dst.copy_from_slice(src) }
+  if let (Some(dst), Some(src)) = (builder.colors(), &colors){ dst.copy_from_slice(src) }
+  if let (Some(dst), Some(src)) = (builder.indices(), &indices){ dst.copy_from_slice(src) }
+
+  let mesh = builder.detach();
+  this.borrow_mut().draw_mesh(&mesh, blend_mode);
+  Ok(cx.undefined())
+}
+
 pub fn getImageData(mut cx: FunctionContext) -> JsResult<JsBuffer> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
@@ -794,6 +1438,73 @@ pub fn getImageData(mut cx: FunctionContext) -> JsResult<JsBuffer> {
   Ok(buffer)
 }
 
+pub fn readPixels(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let x = float_arg(&mut cx, 1, "x")? as i32;
+  let y = float_arg(&mut cx, 2, "y")? as i32;
+  let width = float_arg(&mut cx, 3, "width")? as i32;
+  let height = float_arg(&mut cx, 4, "height")? as i32;
+  let rect = IRect::from_xywh(x, y, width, height);
+  let color_type = match string_arg(&mut cx, 5, "colorType")?.as_str(){
+    "u8" => ColorType::RGBA8888,
+    "f16" => ColorType::RGBAF16,
+    "f32" => ColorType::RGBAF32,
+    name => return cx.throw_type_error(format!("Unknown colorType \"{}\" (expected \"u8\", \"f16\", or \"f32\")", name))
+  };
+  let alpha_type = match string_arg(&mut cx, 6, "alphaType")?.as_str(){
+    "premul" => AlphaType::Premul,
+    "unpremul" => AlphaType::Unpremul,
+    name => return cx.throw_type_error(format!("Unknown alphaType \"{}\" (expected \"premul\" or \"unpremul\")", name))
+  };
+
+  if rect.is_empty(){
+    return cx.throw_range_error("Width and height must be non-zero")
+  }
+
+  let pixels = this.read_pixels_typed(&rect, color_type, alpha_type);
+  let mut buffer = cx.buffer(pixels.len())?;
+  buffer.as_mut_slice(&mut cx).copy_from_slice(&pixels);
+  Ok(buffer)
+}
+
+pub fn hitTest(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let x = float_arg(&mut cx, 1, "x")?.floor() as i32;
+  let y = float_arg(&mut cx, 2, "y")?.floor() as i32;
+  Ok(cx.boolean(this.hit_test_pixel(x, y)))
+}
+
+pub fn colorAtPoint(mut cx: FunctionContext) -> JsResult<JsObject> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let x = float_arg(&mut cx, 1, "x")?.floor() as i32;
+  let y = float_arg(&mut cx, 2, "y")?.floor() as i32;
+  let color = this.color_at(x, y);
+
+  let js_object: Handle<JsObject> = cx.empty_object();
+  for (channel, val) in [("r", color.r), ("g", color.g), ("b", color.b), ("a", color.a)]{
+    let val = cx.number(val);
+    js_object.set(&mut cx, channel, val)?;
+  }
+  Ok(js_object)
+}
+
+pub fn blitImage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let src = cx.argument::<BoxedImage>(1)?;
+  let image = match &src.borrow().image{
+    Some(image) => image.clone(),
+    None => return cx.throw_error("Cannot use incomplete image (has it finished loading?)")
+  };
+
+  let bounds = Rect::from_iwh(image.width(), image.height());
+  let mut this = this.borrow_mut();
+  this.blit_image(&image, &bounds, &bounds);
+  Ok(cx.undefined())
+}
+
 pub fn putImageData(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
@@ -808,16 +1519,20 @@ pub fn putImageData(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   if !dirty.is_empty() && dirty.len() != 4 {
     return cx.throw_type_error("expected either 2 or 6 numbers")
   }
-  let (mut src, mut dst) = match dirty.as_mut_slice(){
+  let mut src = match dirty.as_mut_slice(){
     [dx, dy, dw, dh] => {
       if *dw < 0.0 { *dw *= -1.0; *dx -= *dw; }
       if *dh < 0.0 { *dh *= -1.0; *dy -= *dh; }
-      (Rect::from_xywh(*dx, *dy, *dw, *dh), Rect::from_xywh(*dx + x, *dy + y, *dw, *dh))
+      Rect::from_xywh(*dx, *dy, *dw, *dh)
     },
-    _ => (
-      Rect::from_xywh(0.0, 0.0, width, height),
-      Rect::from_xywh(x, y, width, height)
-  )};
+    _ => Rect::from_xywh(0.0, 0.0, width, height)
+  };
+
+  // only the portion of the dirty rect that overlaps the image data gets copied
+  if !src.intersect(Rect::from_wh(width, height)){
+    return Ok(cx.undefined())
+  }
+  let dst = src.with_offset((x, y));
 
   let buffer: Handle<JsBuffer> = img_data.get(&mut cx, "data")?;
   let info = Image::info(width, height);
@@ -830,7 +1545,6 @@ pub fn putImageData(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 pub fn get_imageSmoothingEnabled(mut cx: FunctionContext) -> JsResult<JsBoolean> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
-  // Ok(cx.boolean(this.state.image_smoothing_enabled))
   Ok(cx.boolean(this.state.image_filter.smoothing))
 }
 
@@ -947,7 +1661,16 @@ pub fn set_textAlign(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let mut this = this.borrow_mut();
   let name = string_arg(&mut cx, 1, "textAlign")?;
 
-  if let Some(mode) = to_text_align(&name){
+  // without a parent element to inherit from, `matchParent` resolves to the side the direction starts from
+  let mode = match name.as_str(){
+    "matchParent" => match this.state.graf_style.text_direction(){
+      TextDirection::LTR => Some(TextAlign::Left),
+      TextDirection::RTL => Some(TextAlign::Right),
+    },
+    _ => to_text_align(&name)
+  };
+
+  if let Some(mode) = mode{
     this.state.graf_style.set_text_align(mode);
   }
   Ok(cx.undefined())
@@ -971,12 +1694,27 @@ pub fn set_textBaseline(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   Ok(cx.undefined())
 }
 
+pub fn get_fontBoundingBoxAscent(mut cx: FunctionContext) -> JsResult<JsNumber> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let this = this.borrow();
+  let (ascent, _) = this.font_bounding_box();
+  Ok(cx.number(ascent))
+}
+
+pub fn get_fontBoundingBoxDescent(mut cx: FunctionContext) -> JsResult<JsNumber> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let this = this.borrow();
+  let (_, descent) = this.font_bounding_box();
+  Ok(cx.number(descent))
+}
+
 pub fn get_direction(mut cx: FunctionContext) -> JsResult<JsString> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
-  let name = match this.state.graf_style.text_direction(){
-    TextDirection::LTR => "ltr",
-    TextDirection::RTL => "rtl",
+  let name = match (this.state.auto_direction, this.state.graf_style.text_direction()){
+    (true, _) => "auto",
+    (false, TextDirection::LTR) => "ltr",
+    (false, TextDirection::RTL) => "rtl",
   };
   Ok(cx.string(name))
 }
@@ -989,10 +1727,16 @@ pub fn set_direction(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let direction = match name.to_lowercase().as_str(){
     "ltr" => Some(TextDirection::LTR),
     "rtl" => Some(TextDirection::RTL),
+    "auto" => {
+      // the direction will be chosen based on the contents of each string when it's typeset
+      this.state.auto_direction = true;
+      return Ok(cx.undefined())
+    },
     _ => None
   };
 
   if let Some(dir) = direction{
+    this.state.auto_direction = false;
     this.state.graf_style.set_text_direction(dir);
   }
   Ok(cx.undefined())
@@ -1028,10 +1772,7 @@ pub fn set_textTracking(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
   let tracking = float_arg(&mut cx, 1, "tracking")?;
-
-  let em = this.state.char_style.font_size();
-  this.state.text_tracking = tracking as i32;
-  this.state.char_style.set_letter_spacing(tracking as f32 / 1000.0 * em);
+  this.set_text_tracking(tracking as i32);
   Ok(cx.undefined())
 }
 
@@ -1049,6 +1790,49 @@ pub fn set_textWrap(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   Ok(cx.undefined())
 }
 
+pub fn get_fontKerning(mut cx: FunctionContext) -> JsResult<JsString> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let this = this.borrow();
+  let mode = match this.state.font_kerning{
+    None => "auto",
+    Some(true) => "normal",
+    Some(false) => "none",
+  };
+  Ok(cx.string(mode))
+}
+
+pub fn set_fontKerning(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let name = string_arg(&mut cx, 1, "fontKerning")?;
+
+  match name.as_str(){
+    "auto" => this.state.font_kerning = None,
+    "normal" => this.state.font_kerning = Some(true),
+    "none" => this.state.font_kerning = Some(false),
+    _ => {}
+  }
+  Ok(cx.undefined())
+}
+
+pub fn get_writingMode(mut cx: FunctionContext) -> JsResult<JsString> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let mode = from_writing_mode(this.state.writing_mode);
+  Ok(cx.string(mode))
+}
+
+pub fn set_writingMode(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  let name = string_arg(&mut cx, 1, "writingMode")?;
+
+  if let Some(mode) = to_writing_mode(&name){
+    this.state.writing_mode = mode;
+  }
+  Ok(cx.undefined())
+}
+
 //
 // Effects
 //
@@ -1140,8 +1924,20 @@ pub fn get_shadowColor(mut cx: FunctionContext) -> JsResult<JsValue> {
 pub fn set_shadowColor(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedContext2D>(0)?;
   let mut this = this.borrow_mut();
-  if let Some(color) = color_arg(&mut cx, 1){
-    this.state.shadow_color = color;
+  let arg = cx.argument::<JsValue>(1)?;
+  if let Some(color) = color_or_current(&mut cx, arg, this.state.current_color){
+    let Color4f{r, g, b, a} = color.into();
+    this.set_shadow_color_f(r, g, b, a);
+  }
+  Ok(cx.undefined())
+}
+
+pub fn setShadowColor(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedContext2D>(0)?;
+  let mut this = this.borrow_mut();
+  // skip the css parser entirely (channels are in the 0–1 range and out-of-range values are clamped)
+  if let [r, g, b, a] = opt_float_args(&mut cx, 1..5).as_slice(){
+    this.set_shadow_color_f(*r, *g, *b, *a);
   }
   Ok(cx.undefined())
 }
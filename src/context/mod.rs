@@ -5,12 +5,13 @@
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex, MutexGuard};
 use neon::prelude::*;
-use skia_safe::{Canvas as SkCanvas, Surface, Paint, Path, PathOp, Image, ImageInfo, Contains,
-                Matrix, Rect, Point, IPoint, Size, ISize, Color, Color4f, ColorType, Data,
+use skia_safe::{Canvas as SkCanvas, Surface, Paint, Path, PathOp, Image, ImageInfo, Contains, ColorSpace,
+                Matrix, M44, Rect, IRect, Point, IPoint, Size, ISize, Color, Color4f, ColorType, Data,
                 PaintStyle, BlendMode, AlphaType, ClipOp, PictureRecorder, Picture, Drawable,
-                image::CachingHint, image_filters, dash_path_effect, path_1d_path_effect};
+                Vertices, TileMode, PaintCap, PathEffect, ContourMeasure, ContourMeasureIter, image::CachingHint, image_filters,
+                dash_path_effect, path_1d_path_effect};
 use skia_safe::textlayout::{ParagraphStyle, TextStyle};
-use skia_safe::canvas::SrcRectConstraint::Strict;
+use skia_safe::canvas::{SaveLayerRec, PointMode, SrcRectConstraint::Strict};
 use skia_safe::path::FillType;
 
 pub mod api;
@@ -19,7 +20,7 @@ pub mod page;
 use crate::FONT_LIBRARY;
 use crate::utils::*;
 use crate::typography::*;
-use crate::filter::{Filter, ImageFilter, FilterQuality};
+use crate::filter::{Filter, ImageFilter, FilterQuality, apply_global_alpha_to_filter};
 use crate::gradient::{CanvasGradient, BoxedCanvasGradient};
 use crate::pattern::{CanvasPattern, BoxedCanvasPattern};
 use crate::texture::{CanvasTexture, BoxedCanvasTexture};
@@ -27,6 +28,7 @@ use page::{PageRecorder, Page};
 
 const BLACK:Color = Color::BLACK;
 const TRANSPARENT:Color = Color::TRANSPARENT;
+const MAX_DASH_COUNT:f32 = 1_000_000.0; // the same limit used by skia's dash path effect
 
 pub type BoxedContext2D = JsBox<RefCell<Context2D>>;
 impl Finalize for Context2D {}
@@ -37,15 +39,32 @@ unsafe impl Send for Context2D {
 pub struct Context2D{
   pub bounds: Rect,
   recorder: Arc<Mutex<PageRecorder>>,
+  attributes: ContextAttributes,
   state: State,
   stack: Vec<State>,
   path: Path,
+  text_metrics: Option<MetricsCache>,
+  disposed: bool,
+}
+
+#[derive(Clone, Copy)]
+pub struct ContextAttributes{
+  pub alpha: bool,
+  pub will_read_frequently: bool,
+  pub measure_text_cache: usize,
+}
+
+impl Default for ContextAttributes {
+  fn default() -> Self {
+    ContextAttributes{ alpha:true, will_read_frequently:false, measure_text_cache:0 }
+  }
 }
 
 #[derive(Clone)]
 pub struct State{
   clip: Option<Path>,
   matrix: Matrix,
+  matrix_3d: Option<M44>,
   paint: Paint,
 
   fill_style: Dye,
@@ -53,10 +72,12 @@ pub struct State{
   shadow_blur: f32,
   shadow_color: Color,
   shadow_offset: Point,
+  current_color: Color,
 
   stroke_width: f32,
   line_dash_offset: f32,
   line_dash_list: Vec<f32>,
+  line_dash_caps: Vec<PaintCap>,
   line_dash_marker: Option<Path>,
   line_dash_fit: path_1d_path_effect::Style,
 
@@ -72,7 +93,10 @@ pub struct State{
   graf_style: ParagraphStyle,
   text_baseline: Baseline,
   text_tracking: i32,
+  writing_mode: WritingMode,
   text_wrap: bool,
+  font_kerning: Option<bool>, // None = "auto" (use the font's defaults), otherwise force kerning on or off
+  auto_direction: bool,
 }
 
 impl Default for State {
@@ -92,6 +116,7 @@ impl Default for State {
     State {
       clip: None,
       matrix: Matrix::new_identity(),
+      matrix_3d: None,
 
       paint,
       stroke_style: Dye::Color(BLACK),
@@ -99,6 +124,7 @@ impl Default for State {
       stroke_width: 1.0,
       line_dash_offset: 0.0,
       line_dash_list: vec![],
+      line_dash_caps: vec![],
       line_dash_marker: None,
       line_dash_fit: path_1d_path_effect::Style::Rotate,
 
@@ -110,6 +136,7 @@ impl Default for State {
       shadow_blur: 0.0,
       shadow_color: TRANSPARENT,
       shadow_offset: (0.0, 0.0).into(),
+      current_color: BLACK,
 
       font: "10px sans-serif".to_string(),
       font_variant: "normal".to_string(),
@@ -118,21 +145,45 @@ impl Default for State {
       graf_style,
       text_baseline: Baseline::Alphabetic,
       text_tracking: 0,
-      text_wrap: false
+      writing_mode: WritingMode::Horizontal,
+      text_wrap: false,
+      font_kerning: None,
+      auto_direction: false
     }
   }
 }
 
 impl State{
-  pub fn typography(&self) -> (TextStyle, ParagraphStyle, Baseline, bool) {
+  pub fn typography(&self) -> (TextStyle, ParagraphStyle, Baseline, WritingMode, bool, bool) {
+    // kerning is applied at layout time so it persists when the font (and its features) change
+    let mut char_style = self.char_style.clone();
+    if let Some(kerning) = self.font_kerning{
+      char_style.add_font_feature("kern", kerning as i32);
+    }
+
     (
-      self.char_style.clone(),
+      char_style,
       self.graf_style.clone(),
       self.text_baseline,
-      self.text_wrap
+      self.writing_mode,
+      self.text_wrap,
+      self.auto_direction
+    )
+  }
+
+  pub fn typography_key(&self) -> String {
+    // flatten the settings that affect text layout into a string since TextStyle isn't hashable
+    format!("{}|{}|{}|{}|{}|{}|{}|{}|{}|{:?}",
+      self.font, self.font_variant, self.text_tracking, self.text_baseline as i32, self.writing_mode as i32,
+      self.text_wrap, self.auto_direction, self.graf_style.text_align() as i32, self.graf_style.text_direction() as i32,
+      self.font_kerning
     )
   }
 
+  pub fn set_shadow_color_f(&mut self, r:f32, g:f32, b:f32, a:f32){
+    self.shadow_color = Color4f::new(r, g, b, a).to_color();
+  }
+
   fn dye(&self, style:PaintStyle) -> &Dye{
     if style == PaintStyle::Stroke{ &self.stroke_style }
     else{ &self.fill_style }
@@ -149,14 +200,35 @@ impl State{
 
 impl Context2D{
   pub fn new() -> Self {
-    let bounds = Rect::from_wh(300.0, 150.0);
+    Self::with_size((300.0, 150.0), ContextAttributes::default())
+  }
 
-    Context2D{
+  pub fn with_size(dims: impl Into<Size>, attributes: ContextAttributes) -> Self {
+    // create the recorder at its final size rather than resizing a default-sized one
+    let bounds = Rect::from_size(dims);
+
+    let ctx = Context2D{
       bounds,
-      recorder: Arc::new(Mutex::new(PageRecorder::new(bounds))),
+      recorder: Arc::new(Mutex::new(PageRecorder::new(bounds, !attributes.alpha))),
+      attributes,
       path: Path::new(),
       stack: vec![],
       state: State::default(),
+      text_metrics: match attributes.measure_text_cache{
+        0 => None,
+        capacity => Some(MetricsCache::new(capacity))
+      },
+      disposed: false,
+    };
+    ctx.fill_backdrop();
+    ctx
+  }
+
+  fn fill_backdrop(&self){
+    // contexts without an alpha channel start out (and are cleared to) opaque black, which is also
+    // recorded so vector output and other canvases drawing this one see the same backdrop
+    if !self.attributes.alpha {
+      self.with_canvas(|canvas| { canvas.clear(BLACK); });
     }
   }
 
@@ -167,6 +239,10 @@ impl Context2D{
     }
   }
 
+  pub fn set_shadow_color_f(&mut self, r:f32, g:f32, b:f32, a:f32){
+    self.state.set_shadow_color_f(r, g, b, a);
+  }
+
   pub fn width(&self) -> f32{
     self.bounds.width()
   }
@@ -200,6 +276,42 @@ impl Context2D{
     });
   }
 
+  pub fn concat_matrix(&mut self, matrix:&Matrix){
+    self.with_matrix(|ctm| ctm.pre_concat(matrix) );
+    if let Some(m44) = self.state.matrix_3d.as_mut(){
+      m44.pre_concat(&(*matrix).into());
+    }
+  }
+
+  pub fn set_matrix(&mut self, matrix:&Matrix){
+    self.state.matrix_3d = None;
+    self.with_matrix(|ctm| ctm.reset().pre_concat(matrix) );
+  }
+
+  pub fn reset_transform(&mut self){
+    // discards any 4×4 matrix as well as the 2D ctm
+    self.set_matrix(&Matrix::new_identity());
+  }
+
+  pub fn set_matrix_3d(&mut self, m44:M44){
+    // drawing only takes place in the z=0 plane, so rendering just needs the 3×3 projection of the
+    // matrix (but the full 4×4 version is retained so that getTransform() can round-trip it)
+    self.set_matrix(&m44.to_m33());
+    self.state.matrix_3d = Some(m44);
+  }
+
+  pub fn matrix_3d(&self) -> Option<M44> {
+    self.state.matrix_3d.clone()
+  }
+
+  pub fn transform_point(&self, x:f32, y:f32) -> Point {
+    self.state.matrix.map_point((x, y))
+  }
+
+  pub fn inverse_transform_point(&self, x:f32, y:f32) -> Option<Point> {
+    self.state.matrix.invert().map(|inverse| inverse.map_point((x, y)))
+  }
+
   pub fn render_to_canvas<F>(&self, paint:&Paint, f:F)
     where F:Fn(&mut SkCanvas, &Paint)
   {
@@ -262,6 +374,27 @@ impl Context2D{
 
   }
 
+  pub fn transformed_path(&self) -> Path{
+    // the current path is stored in screen space (i.e., with the ctm already applied)
+    self.path.clone()
+  }
+
+  pub fn current_path(&self) -> Path{
+    // map the path back into the coordinate system defined by the current transform
+    match self.state.matrix.invert(){
+      Some(inverse) => self.path.with_transform(&inverse),
+      None => Path::new()
+    }
+  }
+
+  pub fn path_bounds(&self, path:Option<&Path>) -> Rect{
+    // measure the path in screen space (the current path already has the ctm applied)
+    match path{
+      Some(path) => path.with_transform(&self.state.matrix).compute_tight_bounds(),
+      None => self.path.compute_tight_bounds()
+    }
+  }
+
   pub fn map_points(&self, coords:&[f32]) -> Vec<Point>{
     coords.chunks_exact(2)
           .map(|pair| self.state.matrix.map_xy(pair[0], pair[1]))
@@ -279,6 +412,33 @@ impl Context2D{
     self.with_recorder(|mut recorder| {
       recorder.set_bounds(self.bounds);
     });
+    self.fill_backdrop();
+  }
+
+  pub fn dispose(&mut self){
+    // drop the recorded layers, cached bitmap, and any images or pictures referenced by the
+    // state (the blank recorder keeps the bounds so the canvas can still be exported)
+    self.path = Path::default();
+    self.stack = vec![];
+    self.state = State::default();
+    self.text_metrics = None;
+    self.recorder = Arc::new(Mutex::new(PageRecorder::new(self.bounds, !self.attributes.alpha)));
+    self.disposed = true;
+  }
+
+  pub fn is_disposed(&self) -> bool{
+    self.disposed
+  }
+
+  pub fn source_error(&self) -> Option<&'static str>{
+    // reasons the context can't be used as the source for drawing into another context
+    if self.disposed{
+      Some("Cannot draw a disposed canvas")
+    }else if self.bounds.is_empty(){
+      Some("Cannot draw a canvas with a width or height of zero")
+    }else{
+      None
+    }
   }
 
   pub fn resize(&mut self, dims: impl Into<Size>) {
@@ -307,15 +467,34 @@ impl Context2D{
   }
 
   pub fn draw_path(&mut self, path:Option<Path>, style:PaintStyle, rule:Option<FillType>){
+    // a singular transform (e.g., from scale(0, 1)) collapses everything to zero area
+    let inverse = match self.state.matrix.invert(){
+      Some(inverse) => inverse,
+      None => return
+    };
+
     let mut path = path.unwrap_or_else(|| {
       // the current path has already incorporated its transform state
-      let inverse = self.state.matrix.invert().unwrap();
       self.path.with_transform(&inverse)
     });
     path.set_fill_type(rule.unwrap_or(FillType::Winding));
 
     let paint = self.paint_for_drawing(style);
-    let texture = self.state.texture(style);
+    self.draw_path_with_paint(&path, &paint);
+  }
+
+  pub fn draw_path_with_paint(&self, path:&Path, paint:&Paint){
+    // fills or strokes the path (depending on the paint's style) with a fully configured paint
+    let mut path = path.clone();
+    let mut paint = paint.clone();
+    let texture = self.state.texture(paint.style());
+
+    // dashes with per-segment caps are stroked individually then filled as a single outline
+    if let Some(outline) = self.dashes_with_caps(&path, &paint){
+      path = outline;
+      paint.set_style(PaintStyle::Fill)
+           .set_path_effect(None::<PathEffect>);
+    }
 
     self.render_to_canvas(&paint, |canvas, paint| {
       if let Some(tile) = texture{
@@ -326,7 +505,8 @@ impl Context2D{
         let stencil_frame = &Path::rect(stencil.bounds().with_offset(offset).with_outset(spacing), None);
 
         let mut tile_paint = paint.clone();
-        tile.mix_into(&mut tile_paint, self.state.global_alpha);
+        let alpha = if paint.image_filter().is_some(){ 1.0 } else { self.state.global_alpha };
+        tile.mix_into(&mut tile_paint, alpha);
         let tile_path = tile_paint.get_fill_path(stencil_frame, None, None).unwrap();
 
         let mut fill_paint = paint.clone();
@@ -340,9 +520,135 @@ impl Context2D{
     });
   }
 
-  pub fn clip_path(&mut self, path: Option<Path>, rule:FillType){
-    let mut clip = path.unwrap_or_else(|| self.path.clone()) ;
+  pub fn set_line_dash_caps(&mut self, intervals:Vec<f32>, caps:Vec<PaintCap>){
+    self.state.line_dash_list = intervals;
+    self.state.line_dash_caps = caps;
+  }
+
+  fn has_dash_caps(&self, style:PaintStyle) -> bool{
+    style == PaintStyle::Stroke && !self.state.line_dash_caps.is_empty()
+  }
+
+  fn dashes_with_caps(&self, path:&Path, paint:&Paint) -> Option<Path>{
+    // only applies to dashed strokes whose caps were set per-segment (and are not using a marker)
+    let State {line_dash_list, line_dash_caps, line_dash_offset, line_dash_marker, ..} = &self.state;
+    let period:f32 = line_dash_list.iter().sum();
+    if paint.style() != PaintStyle::Stroke || line_dash_caps.is_empty() || line_dash_marker.is_some() || period <= 0.0 {
+      return None
+    }
+
+    // leave overly dense patterns to the standard dash effect (which refuses to generate more
+    // than ~1M dashes rather than hanging)
+    let contours:Vec<ContourMeasure> = ContourMeasureIter::from_path(path, false, None).collect();
+    let dash_count = contours.iter().map(|c| c.length() / period).sum::<f32>() * (line_dash_list.len() / 2) as f32;
+    if dash_count > MAX_DASH_COUNT {
+      return None
+    }
+
+    let mut seg_paint = paint.clone();
+    seg_paint.set_path_effect(None::<PathEffect>);
+
+    let mut outline = Path::new();
+    for contour in contours{
+      // walk along the contour one interval at a time (even intervals are dashes, odd ones are gaps)
+      let length = contour.length();
+      let mut dist = -line_dash_offset.rem_euclid(period);
+      for (interval, cap) in line_dash_list.iter().zip(line_dash_caps.iter()).enumerate().cycle()
+        .map(|(i, (interval, cap))| (*interval, (i % 2 == 0).then(|| *cap)))
+      {
+        if dist >= length { break }
+        let (start, end) = (dist.max(0.0), (dist + interval).min(length));
+        // zero-length dashes are kept since they become dots when given round or square caps
+        let visible = end > start || (interval == 0.0 && dist >= 0.0);
+        dist += interval;
+
+        if let (Some(cap), true) = (cap, visible){
+          seg_paint.set_stroke_cap(cap);
+          if let Some(dash) = contour.segment(start, end, true).and_then(|seg| seg_paint.get_fill_path(&seg, None, None)){
+            outline.add_path(&dash, (0.0, 0.0), None);
+          }
+        }
+      }
+    }
+    Some(outline)
+  }
+
+  pub fn draw_circle(&mut self, center:Point, radius:f32){
+    // textures are drawn by intersecting the tile pattern with a path, so use the general case
+    if self.state.texture(PaintStyle::Fill).is_some(){
+      let circle = Path::circle(center, radius, None);
+      return self.draw_path(Some(circle), PaintStyle::Fill, None)
+    }
+
+    let paint = self.paint_for_drawing(PaintStyle::Fill);
+    self.render_to_canvas(&paint, |canvas, paint| {
+      canvas.draw_circle(center, radius, paint);
+    });
+  }
+
+  pub fn draw_oval(&mut self, rect:&Rect, style:PaintStyle){
+    // as with circles, textures (and per-segment dash caps) require an actual path
+    if self.state.texture(style).is_some() || self.has_dash_caps(style){
+      let oval = Path::oval(rect, None);
+      return self.draw_path(Some(oval), style, None)
+    }
+
+    let paint = self.paint_for_drawing(style);
+    self.render_to_canvas(&paint, |canvas, paint| {
+      canvas.draw_oval(rect, paint);
+    });
+  }
+
+  pub fn draw_line(&mut self, start:Point, end:Point){
+    if self.state.texture(PaintStyle::Stroke).is_some() || self.has_dash_caps(PaintStyle::Stroke){
+      let mut line = Path::new();
+      line.move_to(start).line_to(end);
+      return self.draw_path(Some(line), PaintStyle::Stroke, None)
+    }
+
+    let paint = self.paint_for_drawing(PaintStyle::Stroke);
+    self.render_to_canvas(&paint, |canvas, paint| {
+      canvas.draw_line(start, end, paint);
+    });
+  }
+
+  pub fn draw_points(&mut self, mode:PointMode, points:&[Point]){
+    if self.state.texture(PaintStyle::Stroke).is_some() || self.has_dash_caps(PaintStyle::Stroke){
+      let mut path = Path::new();
+      match mode{
+        PointMode::Points => points.iter().for_each(|pt| { path.move_to(*pt).line_to(*pt); }),
+        PointMode::Lines => points.chunks_exact(2).for_each(|pts| { path.move_to(pts[0]).line_to(pts[1]); }),
+        PointMode::Polygon => { path.add_poly(points, false); }
+      }
+
+      // skia draws butt-capped points as squares, but stroking a zero-length segment with butt caps
+      // leaves nothing behind, so trace the points with square caps instead
+      let cap = self.state.paint.stroke_cap();
+      if mode == PointMode::Points && cap == PaintCap::Butt {
+        self.state.paint.set_stroke_cap(PaintCap::Square);
+      }
+      self.draw_path(Some(path), PaintStyle::Stroke, None);
+      self.state.paint.set_stroke_cap(cap);
+      return
+    }
+
+    let paint = self.paint_for_drawing(PaintStyle::Stroke);
+    self.render_to_canvas(&paint, |canvas, paint| {
+      canvas.draw_points(mode, points, paint);
+    });
+  }
+
+  pub fn clip_path(&mut self, path: Option<Path>, rule:FillType, op:ClipOp){
+    // the current path is already in screen space but Path2D arguments need to have the ctm applied
+    let mut clip = match path{
+      Some(path) => path.with_transform(&self.state.matrix),
+      None => self.path.clone()
+    };
     clip.set_fill_type(rule);
+    if op == ClipOp::Difference{
+      // excluding a region is equivalent to intersecting with its inverse
+      clip.toggle_inverse_fill_type();
+    }
 
     self.state.clip = match &self.state.clip {
       Some(old_clip) => old_clip.op(&clip, PathOp::Intersect),
@@ -354,6 +660,13 @@ impl Context2D{
     });
   }
 
+  pub fn clip_stroke(&mut self, path: Option<Path>){
+    // trace the stroke in user space so the line width and dashes are scaled by the ctm
+    let path = path.unwrap_or_else(|| self.current_path());
+    let outline = self.stroke_outline(&path).unwrap_or_default();
+    self.clip_path(Some(outline), FillType::Winding, ClipOp::Intersect);
+  }
+
   pub fn hit_test_path(&mut self, path: &mut Path, point:impl Into<Point>, rule:Option<FillType>, style: PaintStyle) -> bool {
     let point = point.into();
     let point = self.in_local_coordinates(point.x, point.y);
@@ -377,34 +690,54 @@ impl Context2D{
     is_in
   }
 
-  pub fn clear_rect(&mut self, rect:&Rect){
-    match self.state.matrix.map_rect(rect).0.contains(self.bounds){
+  pub fn stroke_outline(&mut self, path:&Path) -> Option<Path>{
+    // trace the outline of the stroke (including its dashes, caps, and joins) as a fillable path
+    let paint = self.paint_for_drawing(PaintStyle::Stroke);
+    paint.get_fill_path(path, None, None)
+  }
 
-      // if rect fully encloses canvas, erase existing content (but preserve CTM, path, etc.)
-      true =>  self.with_recorder(|mut recorder|{
-        recorder.set_bounds(self.bounds);
-        recorder.set_matrix(self.state.matrix);
-        recorder.set_clip(&self.state.clip);
-      }),
+  pub fn clear_rect(&mut self, rect:&Rect){
+    let encloses = self.state.matrix.map_rect(rect).0.contains(self.bounds);
+    match encloses && self.state.clip.is_none(){
+
+      // if rect fully encloses an unclipped canvas, erase existing content (but preserve CTM, path, etc.)
+      true => {
+        self.with_recorder(|mut recorder|{
+          recorder.set_bounds(self.bounds);
+          recorder.set_matrix(self.state.matrix);
+          recorder.set_clip(&self.state.clip);
+        });
+        self.fill_backdrop();
+      },
 
       // otherwise, paint over the specified region but preserve overdrawn vectors
-      false => self.with_canvas(|canvas| {
+      false => {
         let mut paint = Paint::default();
         paint.set_anti_alias(true)
-             .set_style(PaintStyle::Fill)
-             .set_blend_mode(BlendMode::Clear);
-        canvas.draw_rect(&rect, &paint);
-      })
+             .set_style(PaintStyle::Fill);
+        match self.attributes.alpha{
+          true => paint.set_blend_mode(BlendMode::Clear),
+          false => paint.set_blend_mode(BlendMode::Src).set_color(BLACK),
+        };
+        self.with_canvas(|canvas| {
+          canvas.draw_rect(&rect, &paint);
+        })
+      }
     }
   }
 
-  pub fn draw_picture(&mut self, picture:&Option<Picture>, src_rect:&Rect, dst_rect:&Rect){
-    let paint = self.paint_for_image();
-    let size = ISize::new(dst_rect.width() as i32, dst_rect.height() as i32);
+  pub fn draw_picture_rect(&mut self, picture:&Option<Picture>, src_rect:&Rect, dst_rect:&Rect){
     let mag = Point::new(dst_rect.width()/src_rect.width(), dst_rect.height()/src_rect.height());
     let mut matrix = Matrix::new_identity();
     matrix.pre_scale( (mag.x, mag.y), None )
     .pre_translate((dst_rect.x()/mag.x - src_rect.x(), dst_rect.y()/mag.y - src_rect.y()));
+    self.draw_picture(picture, &matrix, 1.0);
+  }
+
+  pub fn draw_picture(&mut self, picture:&Option<Picture>, matrix:&Matrix, alpha:f32){
+    // the alpha is applied on top of the context's globalAlpha
+    let mut paint = self.paint_for_image();
+    paint.set_alpha_f(paint.alpha_f() * alpha.clamp(0.0, 1.0));
 
     if let Some(picture) = picture{
       self.render_to_canvas(&paint, |canvas, paint| {
@@ -414,7 +747,43 @@ impl Context2D{
           (Some(BlendMode::SrcOver), 255, None) => None,
           _ => Some(paint)
         };
-        canvas.draw_picture(&picture, Some(&matrix), paint);
+        canvas.draw_picture(&picture, Some(matrix), paint);
+      });
+    }
+  }
+
+  pub fn composite_from(&mut self, src:&Option<Picture>, blend_mode:BlendMode, alpha:f32, dst_point:Option<Point>){
+    // merge another context's contents as a layer in device space, ignoring this context's transform,
+    // globalAlpha, and globalCompositeOperation (but still honoring its clipping region)
+    let mut paint = Paint::default();
+    paint.set_blend_mode(blend_mode)
+         .set_alpha_f(alpha.clamp(0.0, 1.0));
+    let matrix = Matrix::translate(dst_point.unwrap_or_default());
+
+    if let Some(picture) = src{
+      self.with_canvas(|canvas| {
+        canvas.save();
+        canvas.reset_matrix();
+        canvas.draw_picture(&picture, Some(&matrix), Some(&paint));
+        canvas.restore();
+      });
+    }
+  }
+
+  pub fn backdrop_filter(&mut self, filter:&mut Filter, rect:Option<Rect>){
+    // apply the filter to whatever has already been drawn beneath the (clipped) region
+    let mut paint = Paint::default();
+    filter.mix_into(&mut paint, self.state.matrix, true);
+
+    if let Some(image_filter) = paint.image_filter(){
+      self.with_canvas(|canvas| {
+        canvas.save();
+        if let Some(rect) = rect{
+          canvas.clip_rect(rect, ClipOp::Intersect, true);
+        }
+        canvas.save_layer(&SaveLayerRec::default().backdrop(&image_filter));
+        canvas.restore();
+        canvas.restore();
       });
     }
   }
@@ -429,6 +798,66 @@ impl Context2D{
     }
   }
 
+  pub fn draw_sprite(&mut self, img:&Option<Image>, src:&IRect, dst_x:i32, dst_y:i32){
+    // copy the source region at 1:1 in device space, bypassing the transform so pixels are never resampled
+    let paint = self.paint_for_image();
+    if let Some(image) = &img {
+      let src_rect = Rect::from(*src);
+      let dst_rect = Rect::from_xywh(dst_x as f32, dst_y as f32, src_rect.width(), src_rect.height());
+      let inverse = match self.state.matrix.invert(){
+        Some(inverse) => inverse,
+        None => return
+      };
+      self.render_to_canvas(&paint, |canvas, paint| {
+        // undo the ctm but keep the (device-space) shadow offset when drawing the shadow
+        canvas.save();
+        canvas.concat(&inverse);
+        canvas.draw_image_rect(image, Some((&src_rect, Strict)), dst_rect, paint);
+        canvas.restore();
+      });
+    }
+  }
+
+  pub fn draw_nine_patch(&mut self, img:&Option<Image>, center:&IRect, dst_rect:&Rect){
+    // the corners are drawn unscaled, the edges are stretched along one axis, and the center along both
+    let paint = self.paint_for_image();
+    if let Some(image) = &img {
+      self.render_to_canvas(&paint, |canvas, paint| {
+        let filter_mode = self.state.image_filter.sampling().filter;
+        canvas.draw_image_nine(&image, center, dst_rect, filter_mode, Some(paint));
+      });
+    }
+  }
+
+  pub fn tile_image(&mut self, img:&Option<Image>, src_rect:&Rect, dst_rect:&Rect, spacing:(f32, f32)){
+    // record a single tile (the source region plus the gap that follows it) and repeat it across the destination
+    let mut paint = self.paint_for_image();
+    if let Some(image) = &img {
+      let sampling = self.state.image_filter.sampling();
+      let tile = Rect::from_wh(src_rect.width() + spacing.0, src_rect.height() + spacing.1);
+      let mut tile_recorder = PictureRecorder::new();
+      tile_recorder.begin_recording(tile, None)
+        .draw_image_rect_with_sampling_options(&image, Some((src_rect, Strict)), Rect::from_size(src_rect.size()), sampling, &Paint::default());
+
+      if let Some(pict) = tile_recorder.finish_recording_as_picture(Some(&tile)){
+        let origin = Matrix::translate((dst_rect.left, dst_rect.top));
+        let shader = pict.to_shader((TileMode::Repeat, TileMode::Repeat), sampling.filter, Some(&origin), Some(&tile));
+        paint.set_shader(shader);
+        self.render_to_canvas(&paint, |canvas, paint| {
+          canvas.draw_rect(dst_rect, paint);
+        });
+      }
+    }
+  }
+
+  pub fn draw_mesh(&mut self, vertices:&Vertices, blend_mode:BlendMode){
+    // vertex colors are combined with the fill's shader (if any) using the blend mode
+    let paint = self.paint_for_drawing(PaintStyle::Fill);
+    self.render_to_canvas(&paint, |canvas, paint| {
+      canvas.draw_vertices(vertices, blend_mode, paint);
+    });
+  }
+
   pub fn get_page(&self) -> Page {
     let recorder = Arc::clone(&self.recorder);
     let mut recorder = recorder.lock().unwrap();
@@ -448,40 +877,99 @@ impl Context2D{
   pub fn get_pixels(&mut self, buffer: &mut [u8], origin: impl Into<IPoint>, size: impl Into<ISize>){
     let origin = origin.into();
     let size = size.into();
-    let info = ImageInfo::new(size, ColorType::RGBA8888, AlphaType::Unpremul, None);
+
+    // skia stores pixels premultiplied but ImageData expects them to be unpremultiplied
+    let info = crate::image::Image::info(size.width as f32, size.height as f32);
 
     if let Some(img) = self.get_image(){
       img.read_pixels(&info, buffer, info.min_row_bytes(), origin, CachingHint::Allow);
     }
   }
 
+  pub fn hit_test_pixel(&mut self, x:i32, y:i32) -> bool {
+    // check whether anything visible has been drawn at a given device pixel
+    if x < 0 || y < 0 || x as f32 >= self.bounds.width() || y as f32 >= self.bounds.height(){
+      return false
+    }
+    let mut pixel = [0; 4];
+    self.get_pixels(&mut pixel, (x, y), (1, 1));
+    pixel[3] > 0
+  }
+
+  pub fn color_at(&mut self, x:i32, y:i32) -> Color4f {
+    // sample a single device pixel at full precision (pixels outside the canvas are transparent)
+    let pixel = self.read_pixels_typed(&IRect::from_xywh(x, y, 1, 1), ColorType::RGBAF32, AlphaType::Unpremul);
+    let channels:Vec<f32> = pixel.chunks_exact(4)
+      .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).clamp(0.0, 1.0))
+      .collect();
+    match channels.as_slice(){
+      [r, g, b, a] if *a > 0.0 => Color4f::new(*r, *g, *b, *a),
+      _ => Color4f::new(0.0, 0.0, 0.0, 0.0)
+    }
+  }
+
+  pub fn read_pixels_typed(&mut self, rect:&IRect, color_type:ColorType, alpha_type:AlphaType) -> Vec<u8> {
+    // replay the page at the requested bit depth (rather than reading from the cached 8-bit snapshot)
+    let color_space = Some(ColorSpace::new_srgb());
+    let info = ImageInfo::new(rect.size(), color_type, alpha_type, color_space.clone());
+    let surface_info = ImageInfo::new(rect.size(), color_type, AlphaType::Premul, color_space);
+    let mut pixels = vec![0; info.compute_min_byte_size()];
+
+    if let (Some(pict), Some(mut surface)) = (self.get_picture(), Surface::new_raster(&surface_info, None, None)){
+      let canvas = surface.canvas();
+      canvas.translate((-rect.left as f32, -rect.top as f32));
+      canvas.clip_rect(self.bounds, None, None);
+      canvas.draw_picture(&pict, None, None);
+      surface.read_pixels(&info, &mut pixels, info.min_row_bytes(), (0, 0));
+    }
+    pixels
+  }
+
   pub fn blit_pixels(&mut self, buffer: &[u8], info: &ImageInfo, src_rect:&Rect, dst_rect:&Rect){
     // works just like draw_image in terms of src/dst rects, but clears the dst_rect and then draws
     // without clips, transforms, alpha, blend, or shadows
     let data = Data::new_copy(buffer);
     if let Some(bitmap) = Image::from_raster_data(info, data, info.min_row_bytes()) {
-      self.push(); // cache matrix & clip in self.state
-      self.with_canvas(|canvas| {
-        let paint = Paint::default();
-        let mut eraser = Paint::default();
-        canvas.restore_to_count(1); // discard current matrix & clip
-        eraser.set_blend_mode(BlendMode::Clear);
-        canvas.draw_image_rect(&bitmap, Some((src_rect, Strict)), dst_rect, &eraser);
-        canvas.draw_image_rect(&bitmap, Some((src_rect, Strict)), dst_rect, &paint);
-      });
-      self.pop(); // restore discarded matrix & clip
+      self.blit_image(&bitmap, src_rect, dst_rect);
     }
   }
 
+  pub fn blit_image(&mut self, image: &Image, src_rect:&Rect, dst_rect:&Rect){
+    // replace the contents of dst_rect with the image, ignoring the drawing state (like blit_pixels)
+    self.push(); // cache matrix & clip in self.state
+    self.with_canvas(|canvas| {
+      let paint = Paint::default();
+      let mut eraser = Paint::default();
+      eraser.set_blend_mode(BlendMode::Clear);
+
+      canvas.restore_to_count(1); // discard current matrix & clip
+      canvas.save();
+      canvas.reset_matrix();
+      canvas.clip_rect(dst_rect, ClipOp::Intersect, false); // limit the eraser to the dirty rect
+      canvas.draw_rect(dst_rect, &eraser);
+      canvas.draw_image_rect(image, Some((src_rect, Strict)), dst_rect, &paint);
+      canvas.restore();
+    });
+    self.pop(); // restore discarded matrix & clip
+  }
+
   pub fn set_font(&mut self, spec: FontSpec){
     let mut library = FONT_LIBRARY.lock().unwrap();
     if let Some(new_style) = library.update_style(&self.state.char_style, &spec){
       self.state.font = spec.canonical;
       self.state.font_variant = spec.variant.to_string();
       self.state.char_style = new_style;
+      self.set_text_tracking(self.state.text_tracking); // rescale to the new em size
     }
   }
 
+  pub fn set_text_tracking(&mut self, tracking:i32){
+    // tracking is expressed in 1/1000 em units (like InDesign), skia expects pixels
+    let em = self.state.char_style.font_size();
+    self.state.text_tracking = tracking;
+    self.state.char_style.set_letter_spacing(tracking as f32 / 1000.0 * em);
+  }
+
   pub fn set_font_variant(&mut self, variant:&str, features:&[(String, i32)]){
     let mut library = FONT_LIBRARY.lock().unwrap();
     let new_style = library.update_features(&self.state.char_style, features);
@@ -491,17 +979,42 @@ impl Context2D{
 
 
   pub fn draw_text(&mut self, text: &str, x: f32, y: f32, width: Option<f32>, style:PaintStyle){
+    let mut typesetter = Typesetter::new(&self.state, text, width);
+
+    // per-segment dash caps can only be applied to glyph outlines (which use outlineText's typesetting)
+    if self.has_dash_caps(style){
+      if let Some(path) = typesetter.path(){
+        return self.draw_path(Some(path.with_offset((x, y))), style, None)
+      }
+    }
+
     let paint = self.paint_for_drawing(style);
-    let typesetter = Typesetter::new(&self.state, text, width);
     self.render_to_canvas(&paint, |canvas, paint| {
-      let point = Point::new(x, y);
-      let (paragraph, offset) = typesetter.layout(paint);
-      paragraph.paint(canvas, point + offset);
+      typesetter.paint(canvas, paint, Point::new(x, y));
     });
   }
 
   pub fn measure_text(&mut self, text: &str, width:Option<f32>) -> Vec<Vec<f32>>{
-    Typesetter::new(&self.state, text, width).metrics()
+    let cache = match &mut self.text_metrics{
+      Some(cache) => cache,
+      None => return Typesetter::new(&self.state, text, width).metrics()
+    };
+
+    let key = MetricsKey::new(&self.state, text, width);
+    cache.get(&key).unwrap_or_else(|| {
+      let metrics = Typesetter::new(&self.state, text, width).metrics();
+      cache.insert(key, metrics.clone());
+      metrics
+    })
+  }
+
+  pub fn font_bounding_box(&self) -> (f32, f32){
+    // ascent & descent of the current font measured from the textBaseline (as in measureText)
+    let metrics = self.state.char_style.font_metrics();
+    let size = self.state.char_style.font_size();
+    let offset = get_baseline_offset(&metrics, self.state.text_baseline, size);
+    let norm = get_baseline_offset(&metrics, Baseline::Alphabetic, size) - offset;
+    (norm - metrics.ascent, metrics.descent - norm)
   }
 
   pub fn outline_text(&self, text:&str) -> Option<Path>{
@@ -514,13 +1027,38 @@ impl Context2D{
     color.to_color()
   }
 
+  fn defer_global_alpha(&self, paint:&mut Paint) -> f32{
+    // if the paint has an image filter, apply the global alpha to its output (since the canvas spec
+    // applies filters before alpha) and return the alpha that should still be mixed into the source
+    match paint.image_filter(){
+      Some(filter) => {
+        if self.state.global_alpha < 1.0 {
+          paint.set_image_filter(apply_global_alpha_to_filter(filter, self.state.global_alpha));
+        }
+        1.0
+      },
+      None => self.state.global_alpha
+    }
+  }
+
   pub fn paint_for_drawing(&mut self, style:PaintStyle) -> Paint{
     let mut paint = self.state.paint.clone();
     self.state.filter.mix_into(&mut paint, self.state.matrix, false);
-    self.state.dye(style).mix_into(&mut paint, self.state.global_alpha, self.state.image_filter);
+    let alpha = self.defer_global_alpha(&mut paint);
+    self.state.dye(style).mix_into(&mut paint, alpha, self.state.image_filter);
     paint.set_style(style);
 
     if style==PaintStyle::Stroke && !self.state.line_dash_list.is_empty(){
+      // keep the offset within a single period to avoid losing precision in long-running animations
+      let period = match self.state.line_dash_marker{
+        Some(_) => self.state.line_dash_list[0],
+        None => self.state.line_dash_list.iter().sum()
+      };
+      let offset = match period > 0.0 {
+        true => self.state.line_dash_offset.rem_euclid(period),
+        false => self.state.line_dash_offset
+      };
+
       // if marker is set, apply the 1d_path_effect instead of the dash_path_effect
       let effect = match &self.state.line_dash_marker{
         Some(path) => {
@@ -531,11 +1069,11 @@ impl Context2D{
           path_1d_path_effect::new(
             &marker,
             self.state.line_dash_list[0],
-            self.state.line_dash_offset,
+            offset,
             self.state.line_dash_fit
           )
         }
-        None => dash_path_effect::new(&self.state.line_dash_list, self.state.line_dash_offset)
+        None => dash_path_effect::new(&self.state.line_dash_list, offset)
       };
 
       paint.set_path_effect(effect);
@@ -546,8 +1084,9 @@ impl Context2D{
 
   pub fn paint_for_image(&mut self) -> Paint {
     let mut paint = self.state.paint.clone();
-    self.state.filter.mix_into(&mut paint, self.state.matrix, true)
-      .set_alpha_f(self.state.global_alpha);
+    self.state.filter.mix_into(&mut paint, self.state.matrix, true);
+    let alpha = self.defer_global_alpha(&mut paint);
+    paint.set_alpha_f(alpha);
     paint
   }
 
@@ -557,10 +1096,20 @@ impl Context2D{
       return None
     }
 
-    let sigma_x = shadow_blur / (2.0 * self.state.matrix.scale_x());
-    let sigma_y = shadow_blur / (2.0 * self.state.matrix.scale_y());
+    // like chrome, treat the blur as a radius (and use half of it as the gaussian's std. deviation),
+    // then compensate for the ctm since shadows are unaffected by the current transform
+    let scale = matrix_scale(&self.state.matrix);
+    let sigma_x = shadow_blur / (2.0 * scale.x);
+    let sigma_y = shadow_blur / (2.0 * scale.y);
+    let mut shadow = image_filters::drop_shadow_only((0.0, 0.0), (sigma_x, sigma_y), shadow_color, None, None);
+
+    // if the base paint's alpha was deferred to its filter, the shadow needs to be faded the same way
+    if base_paint.image_filter().is_some() && self.state.global_alpha < 1.0 {
+      shadow = shadow.map(|filter| apply_global_alpha_to_filter(filter, self.state.global_alpha));
+    }
+
     let mut paint = base_paint.clone();
-    paint.set_image_filter(image_filters::drop_shadow_only((0.0, 0.0), (sigma_x, sigma_y), shadow_color, None, None));
+    paint.set_image_filter(shadow);
     Some(paint)
   }
 
@@ -579,7 +1128,7 @@ pub enum Dye{
 }
 
 impl Dye{
-  pub fn new<'a>(cx: &mut FunctionContext<'a>, value: Handle<'a, JsValue>) -> Option<Self> {
+  pub fn new<'a>(cx: &mut FunctionContext<'a>, value: Handle<'a, JsValue>, current_color:Color) -> Option<Self> {
     if let Ok(gradient) = value.downcast::<BoxedCanvasGradient, _>(cx){
       Some(Dye::Gradient(gradient.borrow().clone()) )
     }else if let Ok(pattern) = value.downcast::<BoxedCanvasPattern, _>(cx){
@@ -587,7 +1136,7 @@ impl Dye{
     }else if let Ok(texture) = value.downcast::<BoxedCanvasTexture, _>(cx){
       Some(Dye::Texture(texture.borrow().clone()) )
     }else{
-      color_in(cx, value).map(Dye::Color)
+      color_or_current(cx, value, current_color).map(Dye::Color)
     }
   }
 
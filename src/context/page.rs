@@ -2,10 +2,10 @@ use std::fs;
 use std::path::Path as FilePath;
 use rayon::prelude::*;
 use neon::prelude::*;
-use skia_safe::{Canvas as SkCanvas, Path, Matrix, Rect, ClipOp, Size, Data, Color, ColorSpace,
+use skia_safe::{Canvas as SkCanvas, Path, Matrix, Rect, ClipOp, Size, ISize, Data, Color, ColorSpace, Surface,
                 PictureRecorder, Picture, EncodedImageFormat, Image as SkImage,
-                svg::{self, canvas::Flags}, pdf, Document, ImageInfo,
-                image::BitDepth};
+                svg::{self, canvas::Flags}, pdf, Document, ImageInfo, ColorType, AlphaType,
+                image::{BitDepth, CachingHint}};
 
 use crc::{Crc, CRC_32_ISO_HDLC};
 const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
@@ -26,14 +26,15 @@ pub struct PageRecorder{
   matrix: Matrix,
   clip: Option<Path>,
   changed: bool,
+  opaque: bool,
 }
 
 impl PageRecorder{
-  pub fn new(bounds:Rect) -> Self {
+  pub fn new(bounds:Rect, opaque:bool) -> Self {
     let mut rec = PictureRecorder::new();
     rec.begin_recording(bounds, None);
     rec.recording_canvas().unwrap().save(); // start at depth 2
-    PageRecorder{ current:rec, changed:false, layers:vec![], cache:None, matrix:Matrix::default(), clip:None, bounds }
+    PageRecorder{ current:rec, changed:false, layers:vec![], cache:None, matrix:Matrix::default(), clip:None, bounds, opaque }
   }
 
   pub fn append<F>(&mut self, f:F)
@@ -46,7 +47,7 @@ impl PageRecorder{
   }
 
   pub fn set_bounds(&mut self, bounds:Rect){
-    *self = PageRecorder::new(bounds);
+    *self = PageRecorder::new(bounds, self.opaque);
   }
 
   pub fn update_bounds(&mut self, bounds:Rect){
@@ -91,15 +92,27 @@ impl PageRecorder{
     Page{
       layers: self.layers.clone(),
       bounds: self.bounds,
+      opaque: self.opaque,
     }
   }
 
   pub fn get_image(&mut self) -> Option<SkImage>{
     let page = self.get_page();
+    if page.bounds.is_empty(){
+      return None // a zero-width or zero-height canvas has no backing bitmap
+    }
+
     if self.cache.is_none(){
       if let Some(pict) = page.get_picture(None){
         let size = page.bounds.size().to_floor();
-        self.cache = SkImage::from_picture(pict, size, None, None, BitDepth::U8, Some(ColorSpace::new_srgb()));
+        self.cache = match self.opaque{
+          // contexts created with {alpha:false} are backed by a bitmap with no alpha channel
+          true => Surface::new_raster(&page.image_info(size), None, None).map(|mut surface|{
+            surface.canvas().draw_picture(&pict, None, None);
+            surface.image_snapshot()
+          }),
+          false => SkImage::from_picture(pict, size, None, None, BitDepth::U8, Some(ColorSpace::new_srgb()))
+        };
       }
     }
     self.cache.clone()
@@ -114,10 +127,18 @@ impl PageRecorder{
 pub struct Page{
   pub layers: Vec<Picture>,
   pub bounds: Rect,
+  pub opaque: bool,
 }
 
 impl Page{
 
+  pub fn image_info(&self, dims:ISize) -> ImageInfo {
+    match self.opaque{
+      true => ImageInfo::new(dims, ColorType::RGB888x, AlphaType::Opaque, Some(ColorSpace::new_srgb())),
+      false => ImageInfo::new_n32_premul(dims, Some(ColorSpace::new_srgb()))
+    }
+  }
+
   pub fn get_picture(&self, matte:Option<Color>) -> Option<Picture> {
     let mut compositor = PictureRecorder::new();
     compositor.begin_recording(self.bounds, None);
@@ -130,7 +151,8 @@ impl Page{
     compositor.finish_recording_as_picture(Some(&self.bounds))
   }
 
-  pub fn encoded_as(&self, format:&str, quality:f32, density:f32, outline:bool, matte:Option<Color>, engine:RenderingEngine) -> Result<Data, String> {
+  #[allow(clippy::too_many_arguments)]
+  pub fn encoded_as(&self, format:&str, quality:f32, density:f32, outline:bool, lossless:bool, matte:Option<Color>, engine:RenderingEngine) -> Result<Data, String> {
     let picture = self.get_picture(matte).ok_or("Could not generate an image")?;
 
     if self.bounds.is_empty(){
@@ -140,27 +162,17 @@ impl Page{
       let img_format = match format {
         "jpg" | "jpeg" => Some(EncodedImageFormat::JPEG),
         "png" => Some(EncodedImageFormat::PNG),
+        "webp" => Some(EncodedImageFormat::WEBP),
         _ => None
       };
 
       if let Some(img_format) = img_format{
-        let img_scale = Matrix::scale((density, density));
-        let img_dims = Size::new(img_dims.width * density, img_dims.height * density).to_floor();
-        let img_info = ImageInfo::new_n32_premul(img_dims, Some(ColorSpace::new_srgb()));
-
-        if let Some(mut surface) = engine.get_surface(&img_info){
-          surface
-            .canvas()
-            .set_matrix(&img_scale.into())
-            .draw_picture(&picture, None, None);
-          surface
-            .image_snapshot()
-            .encode_to_data_with_quality(img_format, (quality*100.0) as i32)
-            .map(|data| with_dpi(data, img_format, density))
-            .ok_or(format!("Could not encode as {}", format))
-        }else{
-          Err(format!("Could not allocate new {}×{} bitmap", img_dims.width, img_dims.height))
-        }
+        self.rasterize(&picture, img_dims, density, engine)?
+          .encode_to_data_with_quality(img_format, (quality*100.0) as i32)
+          .map(|data| with_dpi(data, img_format, density))
+          .ok_or(format!("Could not encode as {}", format))
+      }else if format == "jxl"{
+        encode_jxl(&self.rasterize(&picture, img_dims, density, engine)?, quality, lossless)
       }else if format == "pdf"{
         let mut document = pdf_document(quality, density).begin_page(img_dims, None);
         let canvas = document.canvas();
@@ -177,10 +189,27 @@ impl Page{
     }
   }
 
+  fn rasterize(&self, picture:&Picture, dims:Size, density:f32, engine:RenderingEngine) -> Result<SkImage, String>{
+    let img_scale = Matrix::scale((density, density));
+    let img_dims = Size::new(dims.width * density, dims.height * density).to_floor();
+    let img_info = self.image_info(img_dims);
+
+    match engine.get_surface(&img_info){
+      Some(mut surface) => {
+        surface
+          .canvas()
+          .set_matrix(&img_scale.into())
+          .draw_picture(picture, None, None);
+        Ok(surface.image_snapshot())
+      },
+      None => Err(format!("Could not allocate new {}×{} bitmap", img_dims.width, img_dims.height))
+    }
+  }
 
-  pub fn write(&self, filename: &str, file_format:&str, quality:f32, density:f32, outline:bool, matte:Option<Color>, engine:RenderingEngine) -> Result<(), String> {
+  #[allow(clippy::too_many_arguments)]
+  pub fn write(&self, filename: &str, file_format:&str, quality:f32, density:f32, outline:bool, lossless:bool, matte:Option<Color>, engine:RenderingEngine) -> Result<(), String> {
     let path = FilePath::new(&filename);
-    let data = self.encoded_as(file_format, quality, density, outline, matte, engine)?;
+    let data = self.encoded_as(file_format, quality, density, outline, lossless, matte, engine)?;
     fs::write(path, data.as_bytes()).map_err(|why|
       format!("{}: \"{}\"", why, path.display())
     )
@@ -230,12 +259,13 @@ impl PageSequence{
       .map(|doc| doc.close())
   }
 
-  pub fn write_image(&self, pattern:&str, format:&str, quality:f32, density:f32, outline:bool, matte:Option<Color>) -> Result<(), String>{
-    self.first().write(&pattern, &format, quality, density, outline, matte, self.engine)
+  #[allow(clippy::too_many_arguments)]
+  pub fn write_image(&self, pattern:&str, format:&str, quality:f32, density:f32, outline:bool, lossless:bool, matte:Option<Color>) -> Result<(), String>{
+    self.first().write(&pattern, &format, quality, density, outline, lossless, matte, self.engine)
   }
 
   #[allow(clippy::too_many_arguments)]
-  pub fn write_sequence(&self, pattern:&str, format:&str, padding:f32, quality:f32, density:f32, outline:bool, matte:Option<Color>) -> Result<(), String>{
+  pub fn write_sequence(&self, pattern:&str, format:&str, padding:f32, quality:f32, density:f32, outline:bool, lossless:bool, matte:Option<Color>) -> Result<(), String>{
     let padding = match padding as i32{
       -1 => (1.0 + (self.pages.len() as f32).log10().floor()) as usize,
       pad => pad as usize
@@ -247,7 +277,7 @@ impl PageSequence{
       .try_for_each(|(pp, page)|{
         let folio = format!("{:0width$}", pp+1, width=padding);
         let filename = pattern.replace("{}", folio.as_str());
-        page.write(&filename, format, quality, density, outline, matte, self.engine)
+        page.write(&filename, format, quality, density, outline, lossless, matte, self.engine)
       })
   }
 
@@ -279,6 +309,37 @@ pub fn pages_arg(cx: &mut FunctionContext, idx: i32, canvas:&BoxedCanvas) -> Neo
   Ok(PageSequence::from(pages, engine))
 }
 
+#[cfg(feature = "jxl")]
+fn encode_jxl(image:&SkImage, quality:f32, lossless:bool) -> Result<Data, String>{
+  use jpegxl_rs::encode::{EncoderFrame, EncoderResult};
+
+  let info = ImageInfo::new(image.dimensions(), ColorType::RGBA8888, AlphaType::Unpremul, None);
+  let mut pixels = vec![0u8; info.compute_min_byte_size()];
+  if !image.read_pixels(&info, &mut pixels, info.min_row_bytes(), (0, 0), CachingHint::Disallow){
+    return Err("Could not read pixels for JPEG XL encoding".to_string())
+  }
+
+  // map quality to a butteraugli distance (as libjxl does for cjxl's -q flag); it's ignored when lossless
+  let q = quality * 100.0;
+  let distance = if q >= 30.0 { 0.1 + (100.0 - q) * 0.09 } else { 6.4 + 2.5_f32.powf((30.0 - q) / 5.0) / 6.25 };
+  let mut encoder = jpegxl_rs::encoder_builder()
+    .has_alpha(true)
+    .lossless(lossless)
+    .quality(distance)
+    .build()
+    .map_err(|why| format!("Could not encode as jxl: {}", why))?;
+
+  let frame = EncoderFrame::new(&pixels).num_channels(4);
+  let result:EncoderResult<u8> = encoder.encode_frame(&frame, image.width() as u32, image.height() as u32)
+    .map_err(|why| format!("Could not encode as jxl: {}", why))?;
+  Ok(Data::new_copy(&result.data))
+}
+
+#[cfg(not(feature = "jxl"))]
+fn encode_jxl(_image:&SkImage, _quality:f32, _lossless:bool) -> Result<Data, String>{
+  Err("JPEG XL output is not supported by this build (it must be compiled with the `jxl` feature)".to_string())
+}
+
 fn pdf_document(quality:f32, density:f32) -> Document{
   let mut meta = pdf::Metadata::default();
   meta.producer = "Skia Canvas <https://github.com/samizdatco/skia-canvas>".to_string();
@@ -12,6 +12,7 @@ use crate::utils::*;
 pub enum FilterSpec{
   Plain{name:String, value:f32},
   Shadow{offset:Point, blur:f32, color:Color},
+  ColorMatrix{values:[f32; 20]},
 }
 
 #[derive(Clone, Debug)]
@@ -31,7 +32,7 @@ pub struct LastFilter {
 
 impl LastFilter {
   fn match_scale(&self, matrix:Matrix) -> Option<Self> {
-    if self.matrix.scale_x() == matrix.scale_x() && self.matrix.scale_y() == matrix.scale_y(){
+    if matrix_scale(&self.matrix) == matrix_scale(&matrix){
       Some(self.clone())
     }else{
       None
@@ -73,16 +74,22 @@ impl Filter {
       let image_filter = self.specs.iter().fold(None, |chain, next_filter|
         match next_filter {
           FilterSpec::Shadow{ offset, blur, color } => {
-            let scale = Point{x:matrix.scale_x(), y:matrix.scale_y()};
+            let scale = matrix_scale(&matrix);
             let point = (offset.x / scale.x, offset.y / scale.y);
-            let sigma = (    blur / scale.x,     blur / scale.y);
+            let sigma = (blur / (2.0 * scale.x), blur / (2.0 * scale.y)); // blur is a radius, not a std. deviation
             image_filters::drop_shadow(point, sigma, *color, chain, None)
           },
+          FilterSpec::ColorMatrix{ values } => {
+            // a 4×5 matrix in row-major order (like SVG's feColorMatrix)
+            let color_matrix = color_filters::matrix_row_major(values);
+            image_filters::color_filter(color_matrix, chain, None)
+          },
           FilterSpec::Plain{ name, value } => match name.as_ref() {
             "blur" => {
               if raster {
-                let sigma_x = value / (2.0 * matrix.scale_x());
-                let sigma_y = value / (2.0 * matrix.scale_y());
+                let scale = matrix_scale(&matrix);
+                let sigma_x = value / (2.0 * scale.x);
+                let sigma_y = value / (2.0 * scale.y);
                 image_filters::blur((sigma_x, sigma_y), TileMode::Decal, chain, None)
               } else {
                 mask_filter = MaskFilter::blur(BlurStyle::Normal, *value, false);
@@ -212,3 +219,14 @@ impl ImageFilter {
   }
 
 }
+
+pub fn apply_global_alpha_to_filter(filter:SkImageFilter, alpha:f32) -> SkImageFilter {
+  // fade the filter's output (rather than its input) so the filter sees the source at full opacity
+  let fade = color_filters::matrix_row_major(&[
+    1.0, 0.0, 0.0, 0.0,   0.0,
+    0.0, 1.0, 0.0, 0.0,   0.0,
+    0.0, 0.0, 1.0, 0.0,   0.0,
+    0.0, 0.0, 0.0, alpha, 0.0,
+  ]);
+  image_filters::color_filter(fade, filter.clone(), None).unwrap_or(filter)
+}
@@ -62,6 +62,11 @@ impl MetalEngine {
 
     }
 
+    pub fn context() -> Option<DirectContext> {
+        Self::init();
+        MTL_CONTEXT.with(|cell| cell.borrow().as_ref().map(|engine| engine.context.clone()))
+    }
+
     pub fn surface(image_info: &ImageInfo) -> Option<Surface> {
         Self::init();
         MTL_CONTEXT.with(|cell| {
@@ -23,6 +23,12 @@ impl Engine {
     pub fn surface(_: &ImageInfo) -> Option<Surface> { None }
 }
 
+// the current thread's gpu context (for building texture-backed images outside of a surface)
+#[cfg(any(feature = "vulkan", feature = "metal"))]
+pub fn context() -> Option<skia_safe::gpu::DirectContext> {
+    if Engine::supported() { Engine::context() } else { None }
+}
+
 #[cfg(feature = "metal")]
 pub use crate::gpu::metal::autoreleasepool as runloop;
 #[cfg(not(feature = "metal"))]
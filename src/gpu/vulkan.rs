@@ -79,6 +79,11 @@ impl VulkanEngine {
         })
     }
 
+    pub fn context() -> Option<DirectContext> {
+        Self::init();
+        VK_CONTEXT.with(|cell| cell.borrow().as_ref().map(|engine| engine.context.clone()))
+    }
+
     pub fn surface(image_info: &ImageInfo) -> Option<Surface> {
         Self::init();
         VK_CONTEXT.with(|cell| {
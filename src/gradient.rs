@@ -3,7 +3,7 @@
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 use neon::prelude::*;
-use skia_safe::{Shader, Color, Point, TileMode, Matrix};
+use skia_safe::{Shader, Color, Color4f, Point, TileMode, Matrix};
 use skia_safe::{gradient_shader, gradient_shader::GradientShaderColors::Colors};
 
 use crate::utils::*;
@@ -34,9 +34,83 @@ enum Gradient{
 pub type BoxedCanvasGradient = JsBox<RefCell<CanvasGradient>>;
 impl Finalize for CanvasGradient {}
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum Interpolation{
+  SRGB,
+  LinearSRGB,
+  Oklab,
+}
+
+impl Interpolation{
+  pub fn from_name(name:&str) -> Option<Self>{
+    match name.to_lowercase().as_str(){
+      "srgb" => Some(Interpolation::SRGB),
+      "srgb-linear" => Some(Interpolation::LinearSRGB),
+      "oklab" => Some(Interpolation::Oklab),
+      _ => None
+    }
+  }
+
+  // Skia blends between stops in gamma-encoded sRGB, so other spaces are approximated
+  // by subdividing each span into a series of intermediate stops mixed in that space
+  fn expand(&self, stops:&[f32], colors:&[Color]) -> (Vec<f32>, Vec<Color>){
+    const STEPS:usize = 16;
+    if *self == Interpolation::SRGB || stops.len() < 2 {
+      return (stops.to_vec(), colors.to_vec())
+    }
+
+    let (mut new_stops, mut new_colors) = (vec![], vec![]);
+    for i in 0..stops.len()-1 {
+      let (a, b) = (stops[i], stops[i+1]);
+      new_stops.push(a);
+      new_colors.push(colors[i]);
+      if b - a > f32::EPSILON && colors[i] != colors[i+1] {
+        for step in 1..STEPS {
+          let t = step as f32 / STEPS as f32;
+          new_stops.push(a + (b - a) * t);
+          new_colors.push(self.mix(colors[i], colors[i+1], t));
+        }
+      }
+    }
+    new_stops.push(stops[stops.len()-1]);
+    new_colors.push(colors[colors.len()-1]);
+    (new_stops, new_colors)
+  }
+
+  fn mix(&self, a:Color, b:Color, t:f32) -> Color{
+    let (a, b) = (Color4f::from(a), Color4f::from(b));
+    let alpha = a.a + (b.a - a.a) * t;
+    if alpha <= 0.0 { return Color::TRANSPARENT }
+
+    // interpolate premultiplied components then divide the alpha back out
+    let [a0, a1, a2] = self.encode(a);
+    let [b0, b1, b2] = self.encode(b);
+    let lerp = |x:f32, y:f32| (x * a.a + (y * b.a - x * a.a) * t) / alpha;
+    let [r, g, b] = self.decode([lerp(a0, b0), lerp(a1, b1), lerp(a2, b2)]);
+    Color4f::new(r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0), alpha).to_color()
+  }
+
+  fn encode(&self, c:Color4f) -> [f32; 3]{
+    let rgb = [to_linear(c.r), to_linear(c.g), to_linear(c.b)];
+    match self{
+      Interpolation::Oklab => linear_to_oklab(rgb),
+      _ => rgb
+    }
+  }
+
+  fn decode(&self, c:[f32; 3]) -> [f32; 3]{
+    let [r, g, b] = match self{
+      Interpolation::Oklab => oklab_to_linear(c),
+      _ => c
+    };
+    [from_linear(r), from_linear(g), from_linear(b)]
+  }
+}
+
 #[derive(Clone)]
 pub struct CanvasGradient{
-  gradient:Arc<Mutex<Gradient>>
+  gradient:Arc<Mutex<Gradient>>,
+  interpolation:Interpolation,
 }
 
 impl CanvasGradient{
@@ -47,13 +121,15 @@ impl CanvasGradient{
 
     match &*gradient{
       Gradient::Linear{start, end, stops, colors} => {
-        gradient_shader::linear((*start, *end), Colors(colors), Some(stops.as_slice()), TileMode::Clamp, None, None)
+        let (stops, colors) = self.interpolation.expand(stops, colors);
+        gradient_shader::linear((*start, *end), Colors(&colors), Some(stops.as_slice()), TileMode::Clamp, None, None)
       },
       Gradient::Radial{start_point, start_radius, end_point, end_radius, stops, colors} => {
+        let (stops, colors) = self.interpolation.expand(stops, colors);
         gradient_shader::two_point_conical(
           *start_point, *start_radius,
           *end_point, *end_radius,
-          Colors(colors), Some(stops.as_slice()),
+          Colors(&colors), Some(stops.as_slice()),
           TileMode::Clamp, None, None)
       },
       Gradient::Conic{center, angle, stops, colors} => {
@@ -64,9 +140,10 @@ impl CanvasGradient{
           .pre_rotate(*angle, None)
           .pre_translate((-x, -y));
 
+        let (stops, colors) = self.interpolation.expand(stops, colors);
         gradient_shader::sweep(
           *center,
-          Colors(colors),
+          Colors(&colors),
           Some(stops.as_slice()),
           TileMode::Clamp,
           None, // angles
@@ -103,61 +180,144 @@ impl CanvasGradient{
 // -- Javascript Methods --------------------------------------------------------------------------
 //
 
-pub fn linear(mut cx: FunctionContext) -> JsResult<BoxedCanvasGradient> {
-  if let [x1, y1, x2, y2] = opt_float_args(&mut cx, 1..5).as_slice(){
-    let start = Point::new(*x1, *y1);
-    let end = Point::new(*x2, *y2);
-    let ramp = Gradient::Linear{ start, end, stops:vec![], colors:vec![] };
-    let canvas_gradient = CanvasGradient{ gradient:Arc::new(Mutex::new(ramp)) };
-    let this = RefCell::new(canvas_gradient);
-    Ok(cx.boxed(this))
-  }else{
-    let msg = format!("Expected 4 arguments (x1, y1, x2, y2), received {}", cx.len() - 1);
-    cx.throw_type_error(msg)
+// Parse the coordinates for a given gradient style starting at argument `idx` (shared by the
+// constructors and cloneWithGeometry so they validate their arguments identically)
+fn geometry_args(cx: &mut FunctionContext, style:&str, idx:usize) -> NeonResult<Gradient>{
+  let (stops, colors) = (vec![], vec![]);
+  let style = style.to_lowercase();
+  let nums = match geometry_arity(&style){
+    Some(arity) => opt_float_args(cx, idx..idx+arity),
+    None => return cx.throw_type_error(format!("Unknown gradient style: \"{}\" (expected \"linear\", \"radial\", or \"conic\")", style))
+  };
+  match (style.as_str(), nums.as_slice()){
+    ("linear", [x1, y1, x2, y2]) => Ok(
+      Gradient::Linear{ start:Point::new(*x1, *y1), end:Point::new(*x2, *y2), stops, colors }
+    ),
+    ("radial", [x1, y1, r1, x2, y2, r2]) => {
+      if *r1 < 0.0 || *r2 < 0.0 {
+        return cx.throw_error("radii cannot be negative")
+      }
+      Ok(Gradient::Radial{
+        start_point:Point::new(*x1, *y1), start_radius:*r1,
+        end_point:Point::new(*x2, *y2), end_radius:*r2,
+        stops, colors
+      })
+    },
+    ("conic", [theta, x, y]) => Ok(
+      Gradient::Conic{ center:Point::new(*x, *y), angle:to_degrees(*theta) - 90.0, stops, colors }
+    ),
+    ("linear", _) => cx.throw_type_error(format!("Expected 4 arguments (x1, y1, x2, y2), received {}", nums.len())),
+    ("radial", _) => cx.throw_type_error(format!("Expected 6 arguments (x1, y1, r1, x2, y2, r2), received {}", nums.len())),
+    _ => cx.throw_type_error(format!("Expected 3 arguments (startAngle, x, y), received {}", nums.len())),
   }
 }
 
-pub fn radial(mut cx: FunctionContext) -> JsResult<BoxedCanvasGradient> {
-  if let [x1, y1, r1, x2, y2, r2] = opt_float_args(&mut cx, 1..7).as_slice(){
-    let start_point = Point::new(*x1, *y1);
-    let end_point = Point::new(*x2, *y2);
-    let bloom = Gradient::Radial{ start_point, start_radius:*r1, end_point, end_radius:*r2, stops:vec![], colors:vec![] };
-    let canvas_gradient = CanvasGradient{ gradient:Arc::new(Mutex::new(bloom)) };
-    let this = RefCell::new(canvas_gradient);
-    Ok(cx.boxed(this))
-  }else{
-    let msg = format!("Expected 6 arguments (x1, y1, r1, x2, y2, r2), received {}", cx.len() - 1);
-    cx.throw_type_error(msg)
+fn geometry_arity(style:&str) -> Option<usize>{
+  match style{
+    "linear" => Some(4),
+    "radial" => Some(6),
+    "conic" => Some(3),
+    _ => None
   }
 }
 
-pub fn conic(mut cx: FunctionContext) -> JsResult<BoxedCanvasGradient> {
-  if let [theta, x, y] = opt_float_args(&mut cx, 1..4).as_slice(){
-    let center = Point::new(*x, *y);
-    let angle = to_degrees(*theta) - 90.0;
-    let sweep = Gradient::Conic{ center, angle, stops:vec![], colors:vec![] };
-    let canvas_gradient = CanvasGradient{ gradient:Arc::new(Mutex::new(sweep)) };
-    let this = RefCell::new(canvas_gradient);
-    Ok(cx.boxed(this))
-  }else{
-    let msg = format!("Expected 3 arguments (startAngle, x, y), received {}", cx.len() - 1);
-    cx.throw_type_error(msg)
+fn interpolation_arg(cx: &mut FunctionContext, idx:usize) -> NeonResult<Interpolation>{
+  match opt_string_arg(cx, idx){
+    Some(name) => match Interpolation::from_name(&name){
+      Some(space) => Ok(space),
+      None => cx.throw_type_error(format!("Unknown interpolation space: \"{}\" (expected \"srgb\", \"srgb-linear\", or \"oklab\")", name))
+    },
+    None => Ok(Interpolation::SRGB)
   }
 }
 
+fn new_gradient(mut cx: FunctionContext, style:&str) -> JsResult<BoxedCanvasGradient> {
+  let gradient = geometry_args(&mut cx, style, 1)?;
+  let interpolation = interpolation_arg(&mut cx, 1 + geometry_arity(style).unwrap_or(0))?;
+  let canvas_gradient = CanvasGradient{ gradient:Arc::new(Mutex::new(gradient)), interpolation };
+  Ok(cx.boxed(RefCell::new(canvas_gradient)))
+}
+
+pub fn linear(cx: FunctionContext) -> JsResult<BoxedCanvasGradient> {
+  new_gradient(cx, "linear")
+}
+
+pub fn radial(cx: FunctionContext) -> JsResult<BoxedCanvasGradient> {
+  new_gradient(cx, "radial")
+}
+
+pub fn conic(cx: FunctionContext) -> JsResult<BoxedCanvasGradient> {
+  new_gradient(cx, "conic")
+}
+
+pub fn cloneWithGeometry(mut cx: FunctionContext) -> JsResult<BoxedCanvasGradient> {
+  let this = cx.argument::<BoxedCanvasGradient>(0)?;
+  let style = string_arg(&mut cx, 1, "style")?;
+
+  // the copy shares the original's color stops (and interpolation space) but not its geometry
+  let mut geometry = geometry_args(&mut cx, &style, 2)?;
+  let interpolation = {
+    let this = this.borrow();
+    let gradient = Arc::clone(&this.gradient);
+    let gradient = gradient.lock().unwrap();
+    let (stops, colors) = match &*gradient{
+      Gradient::Linear{stops, colors, ..} => (stops, colors),
+      Gradient::Radial{stops, colors, ..} => (stops, colors),
+      Gradient::Conic{stops, colors, ..} => (stops, colors),
+    };
+    match &mut geometry{
+      Gradient::Linear{stops:s, colors:c, ..} |
+      Gradient::Radial{stops:s, colors:c, ..} |
+      Gradient::Conic{stops:s, colors:c, ..} => { *s = stops.clone(); *c = colors.clone(); }
+    }
+    this.interpolation
+  };
+
+  let canvas_gradient = CanvasGradient{ gradient:Arc::new(Mutex::new(geometry)), interpolation };
+  Ok(cx.boxed(RefCell::new(canvas_gradient)))
+}
+
 pub fn addColorStop(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedCanvasGradient>(0)?;
   let offset = float_arg(&mut cx, 1, "offset")?;
-  let color = color_arg(&mut cx, 2);
+  let color = match color_arg(&mut cx, 2){
+    Some(color) => color,
+    None => return cx.throw_type_error("Could not parse color stop as a CSS color")
+  };
 
   let mut this = this.borrow_mut();
-  if let Some(color) = color {
-    this.add_color_stop(offset, color);
-  }
-
+  this.add_color_stop(offset, color);
   Ok(cx.undefined())
 }
 
+pub fn getColorStops(mut cx: FunctionContext) -> JsResult<JsArray> {
+  let this = cx.argument::<BoxedCanvasGradient>(0)?;
+  let this = this.borrow();
+  let gradient = Arc::clone(&this.gradient);
+  let gradient = gradient.lock().unwrap();
+
+  let (stops, colors) = match &*gradient{
+    Gradient::Linear{stops, colors, ..} => (stops, colors),
+    Gradient::Radial{stops, colors, ..} => (stops, colors),
+    Gradient::Conic{stops, colors, ..} => (stops, colors),
+  };
+
+  let array = JsArray::new(&mut cx, stops.len() as u32);
+  for (i, (offset, color)) in stops.iter().zip(colors.iter()).enumerate(){
+    let alpha = format!("{:.3}", color.a() as f32 / 255.0);
+    let alpha = alpha.trim_end_matches('0').trim_end_matches('.');
+    let css = format!("rgba({}, {}, {}, {})", color.r(), color.g(), color.b(), alpha);
+
+    let stop = cx.empty_object();
+    let offset = cx.number(*offset);
+    let css = cx.string(css);
+    stop.set(&mut cx, "offset", offset)?;
+    stop.set(&mut cx, "color", css)?;
+    array.set(&mut cx, i as u32, stop)?;
+  }
+  Ok(array)
+}
+
 pub fn repr(mut cx: FunctionContext) -> JsResult<JsString> {
   let this = cx.argument::<BoxedCanvasGradient>(0)?;
   let this = this.borrow();
@@ -4,7 +4,10 @@
 #![allow(dead_code)]
 use std::cell::RefCell;
 use neon::{prelude::*, types::buffer::TypedArray};
-use skia_safe::{Image as SkImage, ImageInfo, Size, ColorType, AlphaType, Data};
+use skia_safe::{Image as SkImage, ImageInfo, Size, ISize, Rect, ColorType, AlphaType, Data, Surface, Paint, Color,
+                BlendMode, ColorMatrix, color_filters, SamplingOptions, FilterMode, MipmapMode, YUVColorSpace,
+                YUVAInfo, YUVAPixmapInfo, YUVAPixmaps};
+use skia_safe::yuva_info::{PlaneConfig, Subsampling};
 
 use crate::utils::*;
 
@@ -18,11 +21,75 @@ pub struct Image{
 }
 
 impl Image{
+  // the pixel layout used by ImageData (both when reading from and writing to a context)
   pub fn info(width:f32, height:f32) -> ImageInfo {
     let dims = (width as i32, height as i32);
     ImageInfo::new(dims, ColorType::RGBA8888, AlphaType::Unpremul, None)
   }
 
+  // wraps a copy of an ImageData's pixels in a bitmap that can be drawn to a context
+  pub fn from_pixels(pixels:&[u8], width:f32, height:f32) -> Option<SkImage> {
+    let info = Image::info(width, height);
+    SkImage::from_raster_data(&info, Data::new_copy(pixels), info.min_row_bytes())
+  }
+
+  // wraps the planes of a 4:2:0 (a.k.a. I420) video frame and lets skia convert it to rgb
+  pub fn from_yuv(planes:[&[u8]; 3], dims:ISize, wide:bool, color_space:YUVColorSpace) -> Result<SkImage, String> {
+    let yuva_info = YUVAInfo::new(dims, PlaneConfig::Y_U_V, Subsampling::S420, color_space, None, None)
+      .ok_or(format!("Invalid dimensions for a YUV frame ({}×{})", dims.width, dims.height))?;
+
+    // each single-channel plane is stored as alpha (with 8- or 16-bit samples)
+    let color_type = if wide { ColorType::A16UNorm } else { ColorType::Alpha8 };
+    let pixmap_info = YUVAPixmapInfo::new(&yuva_info, &[color_type; 4], None)
+      .ok_or("Unsupported YUV plane format")?;
+
+    // copy the planes into a contiguous block (using minimal row-bytes) for the pixmaps to wrap
+    let mut plane_sizes = [0; 4];
+    let total = pixmap_info.compute_total_bytes(Some(&mut plane_sizes));
+    let mut data = Vec::with_capacity(total);
+    for (plane, size) in planes.iter().zip(plane_sizes){
+      if plane.len() < size {
+        return Err(format!("YUV planes are too small for a {}×{} frame", dims.width, dims.height))
+      }
+      data.extend_from_slice(&plane[..size]);
+    }
+    let pixmaps = YUVAPixmaps::from_data(&pixmap_info, Data::new_copy(&data))
+      .ok_or(format!("Could not allocate YUV planes for a {}×{} frame", dims.width, dims.height))?;
+
+    // when a gpu is available, convert the frame to a texture then read back the rgb pixels
+    #[cfg(any(feature = "vulkan", feature = "metal"))]
+    if let Some(mut context) = crate::gpu::context(){
+      let texture = SkImage::from_yuva_pixmaps(&mut context, &pixmaps, None, None, None);
+      if let Some(image) = texture.and_then(|img| img.make_non_texture_image()){
+        return Ok(image)
+      }
+    }
+
+    // otherwise tint the planes into the r, g, & b channels of an opaque bitmap (upsampling the
+    // chroma planes along the way) then apply skia's yuv→rgb matrix for the frame's color space
+    let bounds = Rect::from_isize(dims);
+    let sampling = SamplingOptions::new(FilterMode::Linear, MipmapMode::None);
+    let mut merged = Surface::new_raster_n32_premul(dims)
+      .ok_or(format!("Could not allocate new {}×{} bitmap", dims.width, dims.height))?;
+    let mut paint = Paint::default();
+    paint.set_blend_mode(BlendMode::Plus);
+    merged.canvas().clear(Color::BLACK);
+    for (plane, channel) in pixmaps.planes().iter().zip([Color::RED, Color::GREEN, Color::BLUE]){
+      let pixels = Data::new_copy(plane.bytes().unwrap_or_default());
+      if let Some(image) = SkImage::from_raster_data(plane.info(), pixels, plane.row_bytes()){
+        paint.set_color(channel);
+        merged.canvas().draw_image_rect_with_sampling_options(&image, None, bounds, sampling, &paint);
+      }
+    }
+
+    let mut paint = Paint::default();
+    paint.set_color_filter(color_filters::matrix(&ColorMatrix::yuv_to_rgb(color_space)));
+    let mut rgb = Surface::new_raster_n32_premul(dims)
+      .ok_or(format!("Could not allocate new {}×{} bitmap", dims.width, dims.height))?;
+    rgb.canvas().draw_image(&merged.image_snapshot(), (0, 0), Some(&paint));
+    Ok(rgb.image_snapshot())
+  }
+
   pub fn size(&self) -> Size{
     if let Some(img) = &self.image {
       let width = &img.width();
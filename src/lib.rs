@@ -5,6 +5,7 @@ use once_cell::sync::Lazy;
 
 mod canvas;
 mod context;
+mod pdf;
 mod path;
 mod image;
 mod filter;
@@ -40,6 +41,7 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("Path2D_new", path::new)?;
   cx.export_function("Path2D_from_path", path::from_path)?;
   cx.export_function("Path2D_from_svg", path::from_svg)?;
+  cx.export_function("Path2D_from_context", path::from_context)?;
   cx.export_function("Path2D_addPath", path::addPath)?;
   cx.export_function("Path2D_closePath", path::closePath)?;
   cx.export_function("Path2D_moveTo", path::moveTo)?;
@@ -54,12 +56,15 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("Path2D_roundRect", path::roundRect)?;
   cx.export_function("Path2D_op", path::op)?;
   cx.export_function("Path2D_interpolate", path::interpolate)?;
+  cx.export_function("Path2D_equals", path::equals)?;
   cx.export_function("Path2D_simplify", path::simplify)?;
   cx.export_function("Path2D_unwind", path::unwind)?;
+  cx.export_function("Path2D_reverse", path::reverse)?;
   cx.export_function("Path2D_round", path::round)?;
   cx.export_function("Path2D_trim", path::trim)?;
   cx.export_function("Path2D_jitter", path::jitter)?;
   cx.export_function("Path2D_offset", path::offset)?;
+  cx.export_function("Path2D_toStrokePath", path::toStrokePath)?;
   cx.export_function("Path2D_transform", path::transform)?;
   cx.export_function("Path2D_bounds", path::bounds)?;
   cx.export_function("Path2D_contains", path::contains)?;
@@ -73,12 +78,16 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("CanvasGradient_radial", gradient::radial)?;
   cx.export_function("CanvasGradient_conic", gradient::conic)?;
   cx.export_function("CanvasGradient_addColorStop", gradient::addColorStop)?;
+  cx.export_function("CanvasGradient_getColorStops", gradient::getColorStops)?;
+  cx.export_function("CanvasGradient_cloneWithGeometry", gradient::cloneWithGeometry)?;
   cx.export_function("CanvasGradient_repr", gradient::repr)?;
 
   // -- CanvasPattern -----------------------------------------------------------------------------
 
   cx.export_function("CanvasPattern_from_image", pattern::from_image)?;
   cx.export_function("CanvasPattern_from_canvas", pattern::from_canvas)?;
+  cx.export_function("CanvasPattern_from_noise", pattern::from_noise)?;
+  cx.export_function("CanvasPattern_from_path", pattern::from_path)?;
   cx.export_function("CanvasPattern_setTransform", pattern::setTransform)?;
   cx.export_function("CanvasPattern_repr", pattern::repr)?;
 
@@ -113,14 +122,24 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("Canvas_saveSync", canvas::saveSync)?;
   cx.export_function("Canvas_toBuffer", canvas::toBuffer)?;
   cx.export_function("Canvas_toBufferSync", canvas::toBufferSync)?;
+  cx.export_function("Canvas_features", canvas::features)?;
+
+  // -- PdfExporter -------------------------------------------------------------------------------
+
+  cx.export_function("PdfExporter_new", pdf::new)?;
+  cx.export_function("PdfExporter_addPage", pdf::addPage)?;
+  cx.export_function("PdfExporter_get_pages", pdf::get_pages)?;
+  cx.export_function("PdfExporter_finish", pdf::finish)?;
 
   // -- Context -----------------------------------------------------------------------------------
 
   cx.export_function("CanvasRenderingContext2D_new", ctx::new)?;
   cx.export_function("CanvasRenderingContext2D_resetSize", ctx::resetSize)?;
+  cx.export_function("CanvasRenderingContext2D_getContextAttributes", ctx::getContextAttributes)?;
   cx.export_function("CanvasRenderingContext2D_get_size", ctx::get_size)?;
   cx.export_function("CanvasRenderingContext2D_set_size", ctx::set_size)?;
   cx.export_function("CanvasRenderingContext2D_reset", ctx::reset)?;
+  cx.export_function("CanvasRenderingContext2D_dispose", ctx::dispose)?;
 
   // grid state
   cx.export_function("CanvasRenderingContext2D_save", ctx::save)?;
@@ -129,13 +148,18 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("CanvasRenderingContext2D_translate", ctx::translate)?;
   cx.export_function("CanvasRenderingContext2D_scale", ctx::scale)?;
   cx.export_function("CanvasRenderingContext2D_rotate", ctx::rotate)?;
+  cx.export_function("CanvasRenderingContext2D_rotateDeg", ctx::rotateDeg)?;
   cx.export_function("CanvasRenderingContext2D_resetTransform", ctx::resetTransform)?;
   cx.export_function("CanvasRenderingContext2D_get_currentTransform", ctx::get_currentTransform)?;
   cx.export_function("CanvasRenderingContext2D_set_currentTransform", ctx::set_currentTransform)?;
+  cx.export_function("CanvasRenderingContext2D_transformPoint", ctx::transformPoint)?;
+  cx.export_function("CanvasRenderingContext2D_inverseTransformPoint", ctx::inverseTransformPoint)?;
   cx.export_function("CanvasRenderingContext2D_createProjection", ctx::createProjection)?;
 
   // bézier paths
   cx.export_function("CanvasRenderingContext2D_beginPath", ctx::beginPath)?;
+  cx.export_function("CanvasRenderingContext2D_get_currentPath", ctx::get_currentPath)?;
+  cx.export_function("CanvasRenderingContext2D_pathBounds", ctx::pathBounds)?;
   cx.export_function("CanvasRenderingContext2D_rect", ctx::rect)?;
   cx.export_function("CanvasRenderingContext2D_roundRect", ctx::roundRect)?;
   cx.export_function("CanvasRenderingContext2D_arc", ctx::arc)?;
@@ -150,6 +174,8 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("CanvasRenderingContext2D_isPointInPath", ctx::isPointInPath)?;
   cx.export_function("CanvasRenderingContext2D_isPointInStroke", ctx::isPointInStroke)?;
   cx.export_function("CanvasRenderingContext2D_clip", ctx::clip)?;
+  cx.export_function("CanvasRenderingContext2D_clipOut", ctx::clipOut)?;
+  cx.export_function("CanvasRenderingContext2D_clipStroke", ctx::clipStroke)?;
 
   // fill & stroke
   cx.export_function("CanvasRenderingContext2D_fill", ctx::fill)?;
@@ -157,14 +183,21 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("CanvasRenderingContext2D_fillRect", ctx::fillRect)?;
   cx.export_function("CanvasRenderingContext2D_strokeRect", ctx::strokeRect)?;
   cx.export_function("CanvasRenderingContext2D_clearRect", ctx::clearRect)?;
+  cx.export_function("CanvasRenderingContext2D_drawCircle", ctx::drawCircle)?;
+  cx.export_function("CanvasRenderingContext2D_drawOval", ctx::drawOval)?;
+  cx.export_function("CanvasRenderingContext2D_drawLine", ctx::drawLine)?;
+  cx.export_function("CanvasRenderingContext2D_drawPoints", ctx::drawPoints)?;
   cx.export_function("CanvasRenderingContext2D_get_fillStyle", ctx::get_fillStyle)?;
   cx.export_function("CanvasRenderingContext2D_set_fillStyle", ctx::set_fillStyle)?;
   cx.export_function("CanvasRenderingContext2D_get_strokeStyle", ctx::get_strokeStyle)?;
   cx.export_function("CanvasRenderingContext2D_set_strokeStyle", ctx::set_strokeStyle)?;
+  cx.export_function("CanvasRenderingContext2D_get_currentColor", ctx::get_currentColor)?;
+  cx.export_function("CanvasRenderingContext2D_set_currentColor", ctx::set_currentColor)?;
 
   // line style
   cx.export_function("CanvasRenderingContext2D_getLineDash", ctx::getLineDash)?;
   cx.export_function("CanvasRenderingContext2D_setLineDash", ctx::setLineDash)?;
+  cx.export_function("CanvasRenderingContext2D_setLineDashAdvanced", ctx::setLineDashAdvanced)?;
   cx.export_function("CanvasRenderingContext2D_get_lineCap", ctx::get_lineCap)?;
   cx.export_function("CanvasRenderingContext2D_set_lineCap", ctx::set_lineCap)?;
   cx.export_function("CanvasRenderingContext2D_get_lineDashFit", ctx::get_lineDashFit)?;
@@ -183,7 +216,21 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   // imagery
   cx.export_function("CanvasRenderingContext2D_drawImage", ctx::drawImage)?;
   cx.export_function("CanvasRenderingContext2D_drawCanvas", ctx::drawCanvas)?;
+  cx.export_function("CanvasRenderingContext2D_drawPath", ctx::drawPath)?;
+  cx.export_function("CanvasRenderingContext2D_drawPicture", ctx::drawPicture)?;
+  cx.export_function("CanvasRenderingContext2D_compositeWith", ctx::compositeWith)?;
+  cx.export_function("CanvasRenderingContext2D_drawNinePatch", ctx::drawNinePatch)?;
+  cx.export_function("CanvasRenderingContext2D_tileImage", ctx::tileImage)?;
+  cx.export_function("CanvasRenderingContext2D_drawSprite", ctx::drawSprite)?;
+  cx.export_function("CanvasRenderingContext2D_applyBackdropFilter", ctx::applyBackdropFilter)?;
+  cx.export_function("CanvasRenderingContext2D_drawYUVFrame", ctx::drawYUVFrame)?;
+  cx.export_function("CanvasRenderingContext2D_drawMesh", ctx::drawMesh)?;
   cx.export_function("CanvasRenderingContext2D_getImageData", ctx::getImageData)?;
+  cx.export_function("CanvasRenderingContext2D_readPixels", ctx::readPixels)?;
+  cx.export_function("CanvasRenderingContext2D_hitTest", ctx::hitTest)?;
+  cx.export_function("CanvasRenderingContext2D_colorAtPoint", ctx::colorAtPoint)?;
+  cx.export_function("CanvasRenderingContext2D_blitImage", ctx::blitImage)?;
+  cx.export_function("CanvasRenderingContext2D_setShadowColor", ctx::setShadowColor)?;
   cx.export_function("CanvasRenderingContext2D_putImageData", ctx::putImageData)?;
   cx.export_function("CanvasRenderingContext2D_get_imageSmoothingEnabled", ctx::get_imageSmoothingEnabled)?;
   cx.export_function("CanvasRenderingContext2D_set_imageSmoothingEnabled", ctx::set_imageSmoothingEnabled)?;
@@ -201,6 +248,8 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("CanvasRenderingContext2D_set_textAlign", ctx::set_textAlign)?;
   cx.export_function("CanvasRenderingContext2D_get_textBaseline", ctx::get_textBaseline)?;
   cx.export_function("CanvasRenderingContext2D_set_textBaseline", ctx::set_textBaseline)?;
+  cx.export_function("CanvasRenderingContext2D_get_fontBoundingBoxAscent", ctx::get_fontBoundingBoxAscent)?;
+  cx.export_function("CanvasRenderingContext2D_get_fontBoundingBoxDescent", ctx::get_fontBoundingBoxDescent)?;
   cx.export_function("CanvasRenderingContext2D_get_direction", ctx::get_direction)?;
   cx.export_function("CanvasRenderingContext2D_set_direction", ctx::set_direction)?;
   cx.export_function("CanvasRenderingContext2D_get_fontVariant", ctx::get_fontVariant)?;
@@ -209,6 +258,10 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
   cx.export_function("CanvasRenderingContext2D_set_textTracking", ctx::set_textTracking)?;
   cx.export_function("CanvasRenderingContext2D_get_textWrap", ctx::get_textWrap)?;
   cx.export_function("CanvasRenderingContext2D_set_textWrap", ctx::set_textWrap)?;
+  cx.export_function("CanvasRenderingContext2D_get_fontKerning", ctx::get_fontKerning)?;
+  cx.export_function("CanvasRenderingContext2D_set_fontKerning", ctx::set_fontKerning)?;
+  cx.export_function("CanvasRenderingContext2D_get_writingMode", ctx::get_writingMode)?;
+  cx.export_function("CanvasRenderingContext2D_set_writingMode", ctx::set_writingMode)?;
 
   // effects
   cx.export_function("CanvasRenderingContext2D_get_globalAlpha", ctx::get_globalAlpha)?;
@@ -11,6 +11,7 @@ use skia_safe::{PathEffect, trim_path_effect};
 use skia_safe::path::{self, AddPathMode, Verb, FillType};
 
 use crate::utils::*;
+use crate::context::BoxedContext2D;
 
 pub type BoxedPath2D = JsBox<RefCell<Path2D>>;
 impl Finalize for Path2D {}
@@ -84,6 +85,57 @@ impl Path2D{
 
     self.path.transform(&rotated);
   }
+
+  pub fn interpolate(&self, other:&Path2D, weight:f32) -> Result<Path, String>{
+    // reverse path order since 0..1 = self..other is a less non-sensical mapping than the default
+    if let Some(path) = other.path.interpolate(&self.path, weight){
+      return Ok(path)
+    }
+
+    // report where the two paths' structures diverge
+    let name = |verb:Option<Verb>| verb.and_then(from_verb).unwrap_or_else(|| "missing".to_string());
+    let mut lhs = path::Iter::new(&self.path, false).map(|(verb, _)| verb);
+    let mut rhs = path::Iter::new(&other.path, false).map(|(verb, _)| verb);
+    let mut idx = 0;
+    loop{
+      match (lhs.next(), rhs.next()){
+        (None, None) => break,
+        (a, b) if a != b => return Err(format!(
+          "Can only interpolate between Path2D objects with matching commands (command {} is {} in one path and {} in the other)",
+          idx, name(a), name(b)
+        )),
+        _ => idx += 1
+      }
+    }
+    Err("Can only interpolate between two Path2D objects with the same number of points and control points".to_string())
+  }
+
+  pub fn equals(&self, other:&Path2D, tolerance:f32) -> bool{
+    if self.path.fill_type() != other.path.fill_type()
+    || self.path.count_verbs() != other.path.count_verbs()
+    || self.path.count_points() != other.path.count_points(){
+      return false
+    }
+
+    let near = |a:f32, b:f32| (a - b).abs() <= tolerance;
+    let mut lhs = path::Iter::new(&self.path, false);
+    let mut rhs = path::Iter::new(&other.path, false);
+    loop{
+      match (lhs.next(), rhs.next()){
+        (Some((verb, pts)), Some((other_verb, other_pts))) => {
+          if verb != other_verb || pts.len() != other_pts.len()
+          || pts.iter().zip(other_pts.iter()).any(|(a, b)| !near(a.x, b.x) || !near(a.y, b.y)){
+            return false
+          }
+          if verb == Verb::Conic && !near(lhs.conic_weight().unwrap_or(1.0), rhs.conic_weight().unwrap_or(1.0)){
+            return false
+          }
+        },
+        (None, None) => return true,
+        _ => return false
+      }
+    }
+  }
 }
 
 //
@@ -101,6 +153,12 @@ pub fn from_path(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   Ok(cx.boxed(RefCell::new(Path2D{path})))
 }
 
+pub fn from_context(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
+  let ctx = cx.argument::<BoxedContext2D>(1)?;
+  let path = ctx.borrow().transformed_path();
+  Ok(cx.boxed(RefCell::new(Path2D{path})))
+}
+
 pub fn from_svg(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   let svg_string = string_arg(&mut cx, 1, "svgPath")?;
   let path = Path::from_svg(svg_string).unwrap_or_else(Path::new);
@@ -218,6 +276,9 @@ pub fn arc(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let nums = opt_float_args(&mut cx, 1..6);
   let ccw = bool_arg_or(&mut cx, 6, false);
   if let [x, y, radius, start_angle, end_angle] = nums.as_slice(){
+    if *radius < 0.0 {
+      return cx.throw_range_error("radius cannot be negative")
+    }
     this.add_ellipse((*x, *y), (*radius, *radius), 0.0, *start_angle, *end_angle, ccw);
   }
 
@@ -232,6 +293,9 @@ pub fn arcTo(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
   let nums = opt_float_args(&mut cx, 1..6);
   if let [x1, y1, x2, y2, radius] = nums.as_slice(){
+    if *radius < 0.0 {
+      return cx.throw_range_error("radius cannot be negative")
+    }
     this.scoot(*x1, *y1);
     this.path.arc_to_tangent((*x1, *y1), (*x2, *y2), *radius);
   }
@@ -249,7 +313,7 @@ pub fn ellipse(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let ccw = bool_arg_or(&mut cx, 8, false);
   if let [x, y, x_radius, y_radius, rotation, start_angle, end_angle] = nums.as_slice(){
     if *x_radius < 0.0 || *y_radius < 0.0 {
-      return cx.throw_error("radii cannot be negative")
+      return cx.throw_range_error("radii cannot be negative")
     }
     this.add_ellipse((*x, *y), (*x_radius, *y_radius), *rotation, *start_angle, *end_angle, ccw);
   }
@@ -310,6 +374,17 @@ pub fn op(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   }
 }
 
+// Checks whether the two paths have the same fill-rule and verbs, with points matching to within a given tolerance
+pub fn equals(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+  let this = cx.argument::<BoxedPath2D>(0)?;
+  let other = cx.argument::<BoxedPath2D>(1)?;
+  let tolerance = float_arg_or(&mut cx, 2, 0.00001);
+
+  let this = this.borrow();
+  let other = other.borrow();
+  Ok(cx.boolean(this.equals(&other, tolerance)))
+}
+
 pub fn interpolate(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   let this = cx.argument::<BoxedPath2D>(0)?;
   let other = cx.argument::<BoxedPath2D>(1)?;
@@ -317,11 +392,9 @@ pub fn interpolate(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
 
   let this = this.borrow();
   let other = other.borrow();
-  // reverse path order since 0..1 = this..other is a less non-sensical mapping than the default
-  if let Some(path) = other.path.interpolate(&this.path, weight){
-    Ok(cx.boxed(RefCell::new(Path2D{ path })))
-  }else{
-    cx.throw_type_error("Can only interpolate between two Path2D objects with the same number of points and control points")
+  match this.interpolate(&other, weight){
+    Ok(path) => Ok(cx.boxed(RefCell::new(Path2D{ path }))),
+    Err(msg) => cx.throw_type_error(msg)
   }
 }
 
@@ -329,14 +402,16 @@ pub fn interpolate(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
 pub fn simplify(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   let this = cx.argument::<BoxedPath2D>(0)?;
   let rule = fill_rule_arg_or(&mut cx, 1, "nonzero")?;
-  let mut this = this.borrow_mut();
+  let this = this.borrow();
 
-  this.path.set_fill_type(rule);
+  // apply the rule to a copy so the original path's fill type is left unchanged
+  let mut path = this.path.clone();
+  path.set_fill_type(rule);
 
   let new_path = Path2D{
-    path:match this.path.simplify(){
+    path:match path.simplify(){
       Some(simpler) => simpler,
-      None => this.path.clone()
+      None => path
     }
   };
 
@@ -360,6 +435,17 @@ pub fn unwind(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   Ok(cx.boxed(RefCell::new(new_path)))
 }
 
+// Returns a copy that visits the same points in the opposite order (reversing its winding direction)
+pub fn reverse(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
+  let this = cx.argument::<BoxedPath2D>(0)?;
+  let this = this.borrow();
+
+  let mut path = Path::new();
+  path.reverse_add_path(&this.path);
+  path.set_fill_type(this.path.fill_type());
+  Ok(cx.boxed(RefCell::new(Path2D{path})))
+}
+
 // Returns a copy whose points have been shifted by (dx, dy)
 pub fn offset(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   let this = cx.argument::<BoxedPath2D>(0)?;
@@ -440,6 +526,16 @@ pub fn jitter(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
   Ok(cx.boxed(RefCell::new(Path2D{path:this.path.clone()})))
 }
 
+// Returns a copy tracing the outline that would be drawn if the path were stroked by a given context
+pub fn toStrokePath(mut cx: FunctionContext) -> JsResult<BoxedPath2D> {
+  let this = cx.argument::<BoxedPath2D>(0)?;
+  let ctx = cx.argument::<BoxedContext2D>(1)?;
+
+  let this = this.borrow();
+  let path = ctx.borrow_mut().stroke_outline(&this.path).unwrap_or_default();
+  Ok(cx.boxed(RefCell::new(Path2D{path})))
+}
+
 // Returns the computed `tight` bounds that contain all the points, control points, and connecting contours
 pub fn bounds(mut cx: FunctionContext) -> JsResult<JsObject> {
   let this = cx.argument::<BoxedPath2D>(0)?;
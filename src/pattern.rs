@@ -6,11 +6,12 @@
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 use neon::prelude::*;
-use skia_safe::{Shader, TileMode, TileMode::{Decal, Repeat}, SamplingOptions, Size,
-                Image as SkImage, Picture, Matrix, FilterMode};
+use skia_safe::{Shader, TileMode, TileMode::{Decal, Repeat}, SamplingOptions, Size, ISize, Rect, Path, Paint,
+                PaintStyle, Color, Image as SkImage, Picture, PictureRecorder, Matrix, FilterMode, perlin_noise_shader};
 
 use crate::utils::*;
 use crate::image::BoxedImage;
+use crate::path::BoxedPath2D;
 use crate::context::BoxedContext2D;
 use crate::filter::ImageFilter;
 
@@ -21,6 +22,8 @@ impl Finalize for CanvasPattern {}
 pub struct Stamp{
   image:Option<SkImage>,
   pict:Option<Picture>,
+  noise:Option<Shader>,
+  vector:bool, // whether the picture was recorded from a Path2D rather than a canvas
   dims:Size,
   repeat:(TileMode, TileMode),
   matrix:Matrix
@@ -41,14 +44,41 @@ impl CanvasPattern{
         shader.with_local_matrix(&stamp.matrix)
       )
     }else if let Some(pict) = &stamp.pict{
-      let shader = pict.to_shader(stamp.repeat, FilterMode::Linear, None, None);
+      // use the canvas dims as the tile (rather than the picture's cull-rect) so that decal-ed
+      // axes in no-repeat/repeat-x/repeat-y modes only draw a single copy of the source
+      let tile = Rect::from_size(stamp.dims);
+      let shader = pict.to_shader(stamp.repeat, FilterMode::Linear, None, Some(&tile));
       Some(shader.with_local_matrix(&stamp.matrix))
+    }else if let Some(noise) = &stamp.noise{
+      Some(noise.with_local_matrix(&stamp.matrix))
     }else{
       None
     }
   }
 }
 
+pub fn create_path_pattern(path:&Path, cell:&Rect, paint:&Paint) -> Option<CanvasPattern>{
+  // record the path as vectors so the pattern stays sharp regardless of the scale it's drawn at
+  let tile = Rect::from_size(cell.size());
+  let mut recorder = PictureRecorder::new();
+  recorder.begin_recording(tile, None)
+    .translate((-cell.left, -cell.top))
+    .draw_path(path, paint);
+
+  recorder.finish_recording_as_picture(Some(&tile)).map(|pict|{
+    let stamp = Stamp{
+      image:None,
+      pict:Some(pict),
+      noise:None,
+      vector:true,
+      dims:tile.size(),
+      repeat:(Repeat, Repeat),
+      matrix:Matrix::new_identity()
+    };
+    CanvasPattern{stamp:Arc::new(Mutex::new(stamp))}
+  })
+}
+
 //
 // -- Javascript Methods --------------------------------------------------------------------------
 //
@@ -67,6 +97,8 @@ pub fn from_image(mut cx: FunctionContext) -> JsResult<BoxedCanvasPattern> {
     let stamp = Stamp{
       image:src.image.clone(),
       pict:None,
+      noise:None,
+      vector:false,
       dims,
       repeat,
       matrix:Matrix::new_identity()
@@ -86,6 +118,10 @@ pub fn from_canvas(mut cx: FunctionContext) -> JsResult<BoxedCanvasPattern> {
     string_arg(&mut cx, 2, "repetition")?
   };
 
+  if src.borrow().is_disposed(){
+    return cx.throw_error("Cannot create a pattern from a disposed canvas")
+  }
+
   if let Some(repeat) = to_repeat_mode(&repetition){
     let mut ctx = src.borrow_mut();
 
@@ -93,6 +129,8 @@ pub fn from_canvas(mut cx: FunctionContext) -> JsResult<BoxedCanvasPattern> {
     let stamp = Stamp{
       image:None,
       pict:ctx.get_picture(),
+      noise:None,
+      vector:false,
       dims,
       repeat,
       matrix:Matrix::new_identity()
@@ -104,6 +142,82 @@ pub fn from_canvas(mut cx: FunctionContext) -> JsResult<BoxedCanvasPattern> {
   }
 }
 
+pub fn from_noise(mut cx: FunctionContext) -> JsResult<BoxedCanvasPattern> {
+  let freq = float_args(&mut cx, 1..3)?;
+  let octaves = float_arg(&mut cx, 3, "octaves")?;
+  let seed = float_arg(&mut cx, 4, "seed")?;
+  let style = string_arg(&mut cx, 5, "type")?;
+  let tile = opt_float_args(&mut cx, 6..8);
+
+  if freq.iter().any(|f| *f < 0.0){
+    return cx.throw_range_error("Noise frequency cannot be negative")
+  }
+  if octaves < 1.0 {
+    return cx.throw_range_error("Noise must have at least one octave")
+  }
+
+  // when a tile size is provided, the noise is adjusted to 'stitch' seamlessly at its edges
+  let tile = match tile.as_slice(){
+    [w, h] if *w >= 1.0 && *h >= 1.0 => Some(ISize::new(*w as i32, *h as i32)),
+    _ => None
+  };
+  let dims = tile.map(|ISize{width, height}| Size::new(width as f32, height as f32)).unwrap_or_default();
+  let base_frequency = (freq[0], freq[1]);
+  let octaves = octaves.floor() as usize;
+
+  let noise = match style.to_lowercase().as_str(){
+    "fractal" => perlin_noise_shader::fractal_noise(base_frequency, octaves, seed, tile),
+    "turbulence" => perlin_noise_shader::turbulence(base_frequency, octaves, seed, tile),
+    _ => return cx.throw_type_error(format!("Unknown noise type: \"{}\" (expected \"fractal\" or \"turbulence\")", style))
+  };
+
+  let stamp = Stamp{
+    image:None,
+    pict:None,
+    noise,
+    vector:false,
+    dims,
+    repeat:(Repeat, Repeat),
+    matrix:Matrix::new_identity()
+  };
+  let stamp = Arc::new(Mutex::new(stamp));
+  Ok(cx.boxed(RefCell::new(CanvasPattern{stamp})))
+}
+
+pub fn from_path(mut cx: FunctionContext) -> JsResult<BoxedCanvasPattern> {
+  let path = cx.argument::<BoxedPath2D>(1)?.borrow().path.clone();
+  let (width, height) = match opt_float_args(&mut cx, 2..4).as_slice(){
+    [width, height] => (*width, *height),
+    _ => return cx.throw_type_error("Pattern cell dimensions must be finite numbers")
+  };
+  let color = match cx.argument::<JsValue>(4)?.is_a::<JsUndefined, _>(&mut cx){
+    true => Color::BLACK,
+    false => match color_arg(&mut cx, 4){
+      Some(color) => color,
+      None => return cx.throw_type_error("Expected a CSS color string for the pattern's color")
+    }
+  };
+  let line = float_arg(&mut cx, 5, "line")?;
+
+  if width <= 0.0 || height <= 0.0 {
+    return cx.throw_range_error("Pattern cell dimensions must be non-zero")
+  }
+
+  // like textures, the path is filled unless a line width is specified
+  let mut paint = Paint::default();
+  paint.set_anti_alias(true);
+  paint.set_color(color);
+  if line > 0.0 {
+    paint.set_style(PaintStyle::Stroke);
+    paint.set_stroke_width(line);
+  }
+
+  match create_path_pattern(&path, &Rect::from_wh(width, height), &paint){
+    Some(pattern) => Ok(cx.boxed(RefCell::new(pattern))),
+    None => cx.throw_error("Could not record path pattern")
+  }
+}
+
 pub fn setTransform(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let this = cx.argument::<BoxedCanvasPattern>(0)?;
   let matrix = matrix_arg(&mut cx, 1)?;
@@ -121,6 +235,6 @@ pub fn repr(mut cx: FunctionContext) -> JsResult<JsString> {
 
   let stamp = Arc::clone(&this.stamp);
   let stamp = stamp.lock().unwrap();
-  let style = if stamp.image.is_some(){ "Bitmap" }else{ "Canvas" };
+  let style = if stamp.image.is_some(){ "Bitmap" }else if stamp.noise.is_some(){ "Noise" }else if stamp.vector{ "Path" }else{ "Canvas" };
   Ok(cx.string(format!("{} {}×{}", style, stamp.dims.width, stamp.dims.height)))
 }
\ No newline at end of file
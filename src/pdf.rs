@@ -0,0 +1,90 @@
+#![allow(non_snake_case)]
+use std::cell::RefCell;
+use neon::{prelude::*, types::buffer::TypedArray};
+use skia_safe::{Rect, Data, Color};
+
+use crate::utils::*;
+use crate::context::{Context2D, BoxedContext2D};
+use crate::context::page::{Page, PageSequence};
+use crate::gpu::RenderingEngine;
+
+pub type BoxedPdfExporter = JsBox<RefCell<PdfExporter>>;
+impl Finalize for PdfExporter {}
+
+pub struct PdfExporter{
+  pages: Vec<Page>
+}
+
+impl PdfExporter{
+  pub fn new() -> Self{
+    PdfExporter{ pages:vec![] }
+  }
+
+  pub fn add_page(&mut self, context:&Context2D, width:f32, height:f32){
+    // snapshot the context's drawing so later changes won't appear in the document
+    let mut page = context.get_page();
+    page.bounds = Rect::from_wh(width, height);
+    self.pages.push(page);
+  }
+
+  pub fn finish(&self, quality:f32, density:f32, matte:Option<Color>) -> Result<Data, String>{
+    if self.pages.is_empty(){
+      return Err("PDF has no pages (try calling addPage first)".to_string())
+    }
+
+    // vector output never touches the gpu, so the engine choice is moot
+    let pages = PageSequence::from(self.pages.clone(), RenderingEngine::CPU);
+    pages.as_pdf(quality, density, matte)
+  }
+}
+
+//
+// -- Javascript Methods --------------------------------------------------------------------------
+//
+
+pub fn new(mut cx: FunctionContext) -> JsResult<BoxedPdfExporter> {
+  let this = RefCell::new(PdfExporter::new());
+  Ok(cx.boxed(this))
+}
+
+pub fn addPage(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+  let this = cx.argument::<BoxedPdfExporter>(0)?;
+  let context = cx.argument::<BoxedContext2D>(1)?;
+  let context = context.borrow();
+  let (width, height) = match cx.len(){
+    2 => (context.width(), context.height()),
+    _ => match opt_float_args(&mut cx, 2..4).as_slice(){
+      [width, height] => (*width, *height),
+      _ => return cx.throw_type_error("Expected both a width and height (or neither) for the PDF page")
+    }
+  };
+
+  if width <= 0.0 || height <= 0.0 {
+    return cx.throw_range_error("Width and height must be non-zero to generate a PDF page")
+  }
+
+  this.borrow_mut().add_page(&context, width, height);
+  Ok(cx.undefined())
+}
+
+pub fn get_pages(mut cx: FunctionContext) -> JsResult<JsNumber> {
+  let this = cx.argument::<BoxedPdfExporter>(0)?;
+  let count = this.borrow().pages.len();
+  Ok(cx.number(count as f64))
+}
+
+pub fn finish(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+  let this = cx.argument::<BoxedPdfExporter>(0)?;
+  let quality = float_arg(&mut cx, 1, "quality")?;
+  let density = float_arg(&mut cx, 2, "density")?;
+  let matte = color_arg(&mut cx, 3);
+
+  match this.borrow().finish(quality, density, matte){
+    Ok(data) => {
+      let mut buffer = cx.buffer(data.len())?;
+      buffer.as_mut_slice(&mut cx).copy_from_slice(&data);
+      Ok(buffer)
+    },
+    Err(msg) => cx.throw_error(msg)
+  }
+}
@@ -11,7 +11,7 @@ use std::collections::HashMap;
 use neon::prelude::*;
 use neon::result::Throw;
 
-use skia_safe::{Font, FontMgr, FontMetrics, FontArguments, Typeface, Data, Paint, Point, Rect, Path as SkPath};
+use skia_safe::{Font, FontMgr, FontMetrics, FontArguments, Typeface, Data, Paint, Point, Rect, Matrix, Canvas, Path as SkPath};
 use skia_safe::font_style::{FontStyle, Weight, Width, Slant};
 use skia_safe::font_arguments::{VariationPosition, variation_position::{Coordinate}};
 use skia_safe::textlayout::{FontCollection, TypefaceFontProvider, TextStyle, TextAlign,
@@ -31,6 +31,7 @@ pub struct Typesetter{
   text: String,
   width: f32,
   baseline: Baseline,
+  writing_mode: WritingMode,
   typefaces: FontCollection,
   char_style: TextStyle,
   graf_style: ParagraphStyle,
@@ -39,7 +40,7 @@ pub struct Typesetter{
 impl Typesetter{
   pub fn new(state:&State, text: &str, width:Option<f32>) -> Self {
     let mut library = FONT_LIBRARY.lock().unwrap();
-    let (char_style, mut graf_style, baseline, wrap) = state.typography();
+    let (char_style, mut graf_style, baseline, writing_mode, wrap, auto_direction) = state.typography();
     let typefaces = library.collect_fonts(&char_style);
     let width = width.unwrap_or(GALLEY);
     let text = match wrap{
@@ -50,6 +51,12 @@ impl Typesetter{
       }
     };
 
+    if auto_direction {
+      if let Some(direction) = base_direction(&text){
+        graf_style.set_text_direction(direction);
+      }
+    }
+
     if wrap {
       // make sure line-breaks use the current leading
       let mut strut_style = graf_style.strut_style().clone();
@@ -66,7 +73,7 @@ impl Typesetter{
       graf_style.set_strut_style(strut_style);
     }
 
-    Typesetter{text, width, baseline, typefaces, char_style, graf_style}
+    Typesetter{text, width, baseline, writing_mode, typefaces, char_style, graf_style}
   }
 
   pub fn layout(&self, paint:&Paint) -> (Paragraph, Point) {
@@ -81,7 +88,7 @@ impl Typesetter{
     paragraph.layout(self.width);
 
     let metrics = self.char_style.font_metrics();
-    let shift = get_baseline_offset(&metrics, self.baseline);
+    let shift = get_baseline_offset(&metrics, self.baseline, self.char_style.font_size());
     let offset = (
       self.width * get_alignment_factor(&self.graf_style),
       shift - paragraph.alphabetic_baseline(),
@@ -90,24 +97,56 @@ impl Typesetter{
     (paragraph, offset.into())
   }
 
+  pub fn paint(&self, canvas:&mut Canvas, paint:&Paint, origin:Point){
+    let (paragraph, offset) = self.layout(paint);
+    if self.writing_mode == WritingMode::Horizontal {
+      return paragraph.paint(canvas, origin + offset)
+    }
+
+    // vertical text is set sideways, with each line rotated a quarter-turn clockwise around the origin
+    canvas.save();
+    canvas.translate(origin).rotate(90.0, None);
+
+    let lines = paragraph.get_line_metrics();
+    if self.writing_mode == WritingMode::VerticalRL || lines.len() < 2 {
+      paragraph.paint(canvas, offset);
+    }else{
+      // the rotation stacks lines right-to-left, so mirror each line's position for left-to-right
+      let origin = lines[0].baseline;
+      for line in &lines {
+        let (top, bottom) = (line.baseline - line.ascent, line.baseline + line.descent);
+        let shift = -2.0 * (line.baseline - origin) as f32;
+        canvas.save();
+        canvas.translate((0.0, shift));
+        canvas.clip_rect(Rect::new(-GALLEY, top as f32, 2.0 * GALLEY, bottom as f32).with_offset(offset), None, true);
+        paragraph.paint(canvas, offset);
+        canvas.restore();
+      }
+    }
+    canvas.restore();
+  }
+
   pub fn metrics(&self) -> Vec<Vec<f32>>{
     let (paragraph, _) = self.layout(&Paint::default());
     let font_metrics = self.char_style.font_metrics();
-    let offset = get_baseline_offset(&font_metrics, self.baseline);
-    let hang = get_baseline_offset(&font_metrics, Baseline::Hanging) - offset;
-    let norm = get_baseline_offset(&font_metrics, Baseline::Alphabetic) - offset;
-    let ideo = get_baseline_offset(&font_metrics, Baseline::Ideographic) - offset;
+    let size = self.char_style.font_size();
+    let offset = get_baseline_offset(&font_metrics, self.baseline, size);
+    let hang = get_baseline_offset(&font_metrics, Baseline::Hanging, size) - offset;
+    let norm = get_baseline_offset(&font_metrics, Baseline::Alphabetic, size) - offset;
+    let ideo = get_baseline_offset(&font_metrics, Baseline::Ideographic, size) - offset;
     let ascent = norm - font_metrics.ascent;
     let descent = font_metrics.descent - norm;
+    let em_ascent = norm + get_em_ascent(&font_metrics, size);
+    let em_descent = size - em_ascent;
     let alignment = get_alignment_factor(&self.graf_style) * self.width;
 
     if paragraph.line_number() == 0 {
-      return vec![vec![0.0, 0.0, 0.0, 0.0, 0.0, ascent, descent, ascent, descent, hang, norm, ideo]]
+      return vec![vec![0.0, 0.0, 0.0, 0.0, 0.0, ascent, descent, em_ascent, em_descent, hang, norm, ideo]]
     }
 
     // find the bounds and text-range for each individual line
     let origin = paragraph.get_line_metrics()[0].baseline;
-    let line_rects:Vec<(Rect, Range<usize>, f32)> = paragraph.get_line_metrics().iter().map(|line|{
+    let mut line_rects:Vec<(Rect, Range<usize>, f32)> = paragraph.get_line_metrics().iter().map(|line|{
       let baseline = line.baseline - origin;
       let rect = Rect::new(line.left as f32, (baseline - line.ascent) as f32,
                           (line.left + line.width) as f32, (baseline + line.descent) as f32);
@@ -117,16 +156,34 @@ impl Typesetter{
       (rect.with_offset((alignment, offset)), range, baseline as f32 + offset)
     }).collect();
 
+    // vertical text is rotated a quarter-turn clockwise (so the advance runs down the y-axis and the
+    // lines' extents run along the x-axis), with vertical-lr mirroring the lines' order as in paint()
+    if self.writing_mode != WritingMode::Horizontal {
+      let mirror = self.writing_mode == WritingMode::VerticalLR;
+      for (rect, _, baseline) in line_rects.iter_mut(){
+        let shift = if mirror { 2.0 * (offset - *baseline) } else { 0.0 };
+        let (top, bottom) = (rect.top + shift, rect.bottom + shift);
+        *rect = Rect::new(-bottom, rect.left, -top, rect.right);
+        *baseline = -(*baseline + shift);
+      }
+    }
+
     // take their union to find the bounds for the whole text run
     let (bounds, chars) = line_rects.iter().fold((Rect::new_empty(), 0), |(union, indices), (rect, range, _)|
       (Rect::join2(union, rect), range.end)
     );
 
     // return a list-of-lists whose first entry is the whole-run font metrics and subsequent entries are
-    // line-rect/range values (with the js side responsible for restructuring the whole bundle)
+    // line-rect/range values (with the js side responsible for restructuring the whole bundle).
+    // note that the bounds have already been shifted by the alignment offset, but per the spec the
+    // bounding-box-left distance is measured leftward from the origin (so it's positive when centered)
+    let advance = match self.writing_mode{
+      WritingMode::Horizontal => bounds.width(),
+      _ => bounds.height()
+    };
     let mut results = vec![vec![
-      bounds.width(), bounds.left, bounds.right, -bounds.top, bounds.bottom,
-      ascent, descent, ascent, descent, hang, norm, ideo
+      advance, -bounds.left, bounds.right, -bounds.top, bounds.bottom,
+      ascent, descent, em_ascent, em_descent, hang, norm, ideo
     ]];
     line_rects.iter().for_each(|(rect, range, baseline)|{
       results.push(vec![rect.left, rect.top, rect.width(), rect.height(),
@@ -141,19 +198,88 @@ impl Typesetter{
     if let Some(typeface) = matches.first(){
       let font = Font::from_typeface(typeface, self.char_style.font_size());
       let (leading, metrics) = font.metrics();
-      let (width, bounds) = font.measure_str(&self.text, None);
-      let offset = (
-        width * get_alignment_factor(&self.graf_style),
-        get_baseline_offset(&metrics, self.baseline)
-      );
+      let shift = get_baseline_offset(&metrics, self.baseline, self.char_style.font_size());
+
+      // newlines are only preserved when textWrap is enabled, in which case each line is
+      // positioned using the same baselines as the paragraph drawn by fillText
+      let (paragraph, _) = self.layout(&Paint::default());
+      let baselines:Vec<f32> = paragraph.get_line_metrics().iter().map(|line| line.baseline as f32).collect();
+      let origin = baselines.first().copied().unwrap_or(0.0);
+
+      let mut path = SkPath::new();
+      for (i, line) in self.text.split('\n').enumerate(){
+        let (width, bounds) = font.measure_str(line, None);
+        let offset = (
+          width * get_alignment_factor(&self.graf_style),
+          shift + baselines.get(i).map(|baseline| baseline - origin).unwrap_or(0.0)
+        );
+        path.add_path(&SkPath::from_str(line, offset, &font), (0, 0), None);
+      }
 
-      Some(SkPath::from_str(&self.text, offset, &font))
+      match self.writing_mode{
+        WritingMode::Horizontal => Some(path),
+        _ => Some(path.with_transform(&Matrix::rotate_deg(90.0)))
+      }
     }else{
       None
     }
   }
 }
 
+//
+// Memoized measureText results
+//
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MetricsKey{
+  text: String,
+  width: Option<u32>,
+  style: String,
+  generation: usize,
+}
+
+impl MetricsKey{
+  pub fn new(state:&State, text:&str, width:Option<f32>) -> Self {
+    let style = state.typography_key();
+    let width = width.map(|w| w.to_bits());
+    let generation = FONT_LIBRARY.lock().unwrap().generation;
+    MetricsKey{ text:text.to_string(), width, style, generation }
+  }
+}
+
+pub struct MetricsCache{
+  capacity: usize,
+  clock: usize,
+  entries: HashMap<MetricsKey, (usize, Vec<Vec<f32>>)>,
+}
+
+impl MetricsCache{
+  pub fn new(capacity:usize) -> Self {
+    MetricsCache{ capacity, clock:0, entries:HashMap::new() }
+  }
+
+  pub fn get(&mut self, key:&MetricsKey) -> Option<Vec<Vec<f32>>> {
+    self.clock += 1;
+    let clock = self.clock;
+    self.entries.get_mut(key).map(|(last_used, metrics)| {
+      *last_used = clock;
+      metrics.clone()
+    })
+  }
+
+  pub fn insert(&mut self, key:MetricsKey, metrics:Vec<Vec<f32>>){
+    // evict the least recently used entry once the cache is full
+    if self.entries.len() >= self.capacity {
+      let oldest = self.entries.iter().min_by_key(|(_, (last_used, _))| *last_used).map(|(k, _)| k.clone());
+      if let Some(oldest) = oldest {
+        self.entries.remove(&oldest);
+      }
+    }
+    self.clock += 1;
+    self.entries.insert(key, (self.clock, metrics));
+  }
+}
+
 //
 // Font argument packing & unpacking
 //
@@ -315,6 +441,30 @@ pub fn from_text_align(mode:TextAlign) -> String{
   }.to_string()
 }
 
+pub fn base_direction(text:&str) -> Option<TextDirection> {
+  // a simplified version of the unicode bidi algorithm's ‘first strong isolate’ rule: the first
+  // strongly-directional character (skipping over any isolated runs) determines the direction
+  let is_rtl = |c:char| matches!(c as u32,
+    0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+  );
+
+  let mut isolates = 0;
+  for c in text.chars(){
+    match c {
+      '\u{2066}' | '\u{2067}' | '\u{2068}' => isolates += 1,
+      '\u{2069}' => isolates = 0.max(isolates - 1),
+      _ if isolates > 0 => continue,
+      '\u{200F}' => return Some(TextDirection::RTL),
+      '\u{200E}' => return Some(TextDirection::LTR),
+      c if c.is_alphabetic() => return Some(
+        if is_rtl(c){ TextDirection::RTL }else{ TextDirection::LTR }
+      ),
+      _ => {}
+    }
+  }
+  None
+}
+
 pub fn get_alignment_factor(graf_style:&ParagraphStyle) -> f32 {
   match graf_style.text_direction() {
     TextDirection::LTR => match graf_style.text_align() {
@@ -359,9 +509,42 @@ pub fn from_text_baseline(mode:Baseline) -> String{
   }.to_string()
 }
 
-pub fn get_baseline_offset(metrics: &FontMetrics, mode:Baseline) -> f32 {
+#[derive(Copy, Clone, PartialEq)]
+pub enum WritingMode{ Horizontal, VerticalRL, VerticalLR }
+
+pub fn to_writing_mode(mode_name:&str) -> Option<WritingMode>{
+  let mode = match mode_name.to_lowercase().as_str(){
+    "horizontal-tb" => WritingMode::Horizontal,
+    "vertical-rl" => WritingMode::VerticalRL,
+    "vertical-lr" => WritingMode::VerticalLR,
+    _ => return None
+  };
+  Some(mode)
+}
+
+pub fn from_writing_mode(mode:WritingMode) -> String{
+  match mode{
+    WritingMode::Horizontal => "horizontal-tb",
+    WritingMode::VerticalRL => "vertical-rl",
+    WritingMode::VerticalLR => "vertical-lr",
+  }.to_string()
+}
+
+pub fn get_em_ascent(metrics: &FontMetrics, size:f32) -> f32 {
+  // the em square is divided above & below the baseline in the same proportion as the font's ascent & descent
+  let extent = metrics.descent - metrics.ascent;
+  match extent > 0.0 {
+    true => size * -metrics.ascent / extent,
+    false => -metrics.ascent
+  }
+}
+
+pub fn get_baseline_offset(metrics: &FontMetrics, mode:Baseline, size:f32) -> f32 {
   match mode{
-    Baseline::Top => -metrics.ascent,
+    Baseline::Top => match metrics.cap_height > 0.0 {
+      true => metrics.cap_height, // align with the top of the capitals (not the font's ascent)...
+      false => get_em_ascent(metrics, size) // ...or the em square if the font doesn't report a cap-height
+    },
     Baseline::Hanging => metrics.cap_height,
     Baseline::Middle => metrics.cap_height / 2.0,
     Baseline::Alphabetic => 0.0,
@@ -390,7 +573,9 @@ impl CollectionKey{
 pub struct FontLibrary{
   pub fonts: Vec<(Typeface, Option<String>)>,
   pub collection: FontCollection,
+  pub generation: usize, // incremented whenever the set of fonts changes
   collection_cache: HashMap<CollectionKey, FontCollection>,
+  font_mgr: FontMgr,
 }
 
 unsafe impl Send for FontLibrary {
@@ -399,16 +584,29 @@ unsafe impl Send for FontLibrary {
 
 impl FontLibrary{
   pub fn shared() -> Mutex<Self>{
+    // the system font manager is created once and shared by every collection the library builds
+    let font_mgr = FontMgr::new();
     let fonts = vec![];
     let collection_cache = HashMap::new();
+    let collection = Self::system_collection(&font_mgr);
+    Mutex::new(FontLibrary{ collection, collection_cache, fonts, font_mgr, generation:0 })
+  }
+
+  fn system_collection(font_mgr:&FontMgr) -> FontCollection {
     let mut collection = FontCollection::new();
-    collection.set_default_font_manager(FontMgr::new(), None);
-    Mutex::new(FontLibrary{ collection, collection_cache, fonts })
+    collection.set_default_font_manager(font_mgr.clone(), None);
+    collection
+  }
+
+  pub fn reset(&mut self){
+    self.fonts.clear();
+    self.collection = Self::system_collection(&self.font_mgr);
+    self.collection_cache.drain();
+    self.generation += 1;
   }
 
   fn families(&self) -> Vec<String>{
-    let font_mgr = FontMgr::new();
-    let mut names:Vec<String> = font_mgr.family_names().collect();
+    let mut names:Vec<String> = self.font_mgr.family_names().collect();
     for (font, alias) in &self.fonts {
       names.push(match alias{
         Some(name) => name.clone(),
@@ -426,7 +624,7 @@ impl FontLibrary{
     for (font, alias) in &self.fonts{
       dynamic.register_typeface(font.clone(), alias.clone());
     }
-    let std_mgr = FontMgr::new();
+    let std_mgr = &self.font_mgr;
     let dyn_mgr:FontMgr = dynamic.into();
     let mut std_set = std_mgr.match_family(&family);
     let mut dyn_set = dyn_mgr.match_family(&family);
@@ -436,8 +634,7 @@ impl FontLibrary{
 
     // set up a collection to query for variable fonts who specify their weights
     // via the 'wght' axis rather than through distinct files with different FontStyles
-    let mut var_fc = FontCollection::new();
-    var_fc.set_default_font_manager(FontMgr::new(), None);
+    let mut var_fc = Self::system_collection(&self.font_mgr);
     var_fc.set_asset_font_manager(Some(dyn_mgr));
 
     // pull style values out of each matching font
@@ -482,11 +679,11 @@ impl FontLibrary{
       assets.register_typeface(font.clone(), alias.as_ref());
     }
 
-    let mut collection = FontCollection::new();
-    collection.set_default_font_manager(FontMgr::new(), None);
+    let mut collection = Self::system_collection(&self.font_mgr);
     collection.set_asset_font_manager(Some(assets.into()));
     self.collection = collection;
     self.collection_cache.drain();
+    self.generation += 1;
   }
 
   pub fn update_style(&mut self, orig_style:&TextStyle, spec: &FontSpec) -> Option<TextStyle>{
@@ -559,8 +756,7 @@ impl FontLibrary{
             let mut dynamic = TypefaceFontProvider::new();
             dynamic.register_typeface(face, alias);
 
-            let mut collection = FontCollection::new();
-            collection.set_default_font_manager(FontMgr::new(), None);
+            let mut collection = Self::system_collection(&self.font_mgr);
             collection.set_asset_font_manager(Some(dynamic.into()));
             self.collection_cache.insert(key, collection.clone());
             return collection
@@ -616,6 +812,24 @@ pub fn family(mut cx: FunctionContext) -> JsResult<JsValue> {
   Ok(details.upcast())
 }
 
+fn decode_font_data(bytes:Vec<u8>) -> Result<Vec<u8>, String>{
+  // skia can only parse uncompressed TTF/OTF data, so WOFF2 files need to be expanded first
+  match bytes.starts_with(b"wOF2"){
+    true => decompress_woff2(&bytes),
+    false => Ok(bytes)
+  }
+}
+
+#[cfg(feature = "woff2")]
+fn decompress_woff2(bytes:&[u8]) -> Result<Vec<u8>, String>{
+  woff2::convert_woff2_to_ttf(&mut &bytes[..]).map_err(|_| "Could not decompress WOFF2 font data".to_string())
+}
+
+#[cfg(not(feature = "woff2"))]
+fn decompress_woff2(bytes:&[u8]) -> Result<Vec<u8>, String>{
+  Err("WOFF2 fonts are not supported by this build (it must be compiled with the `woff2` feature)".to_string())
+}
+
 pub fn addFamily(mut cx: FunctionContext) -> JsResult<JsValue> {
   let alias = opt_string_arg(&mut cx, 1);
   let filenames = cx.argument::<JsArray>(2)?.to_vec(&mut cx)?;
@@ -623,11 +837,14 @@ pub fn addFamily(mut cx: FunctionContext) -> JsResult<JsValue> {
 
   for (i, filename) in strings_in(&mut cx, &filenames).iter().enumerate(){
     let path = Path::new(&filename);
-    let typeface = match fs::read(path){
+    let typeface = match fs::read(path).map(decode_font_data){
       Err(why) => {
         return cx.throw_error(format!("{}: \"{}\"", why, path.display()))
       },
-      Ok(bytes) => Typeface::from_data(Data::new_copy(&bytes), None)
+      Ok(Err(why)) => {
+        return cx.throw_error(format!("{} in {}", why, path.display()))
+      },
+      Ok(Ok(bytes)) => Typeface::from_data(Data::new_copy(&bytes), None)
     };
 
     match typeface {
@@ -651,12 +868,6 @@ pub fn addFamily(mut cx: FunctionContext) -> JsResult<JsValue> {
 
 pub fn reset(mut cx: FunctionContext) -> JsResult<JsUndefined> {
   let mut library = FONT_LIBRARY.lock().unwrap();
-  library.fonts.clear();
-
-  let mut collection = FontCollection::new();
-  collection.set_default_font_manager(FontMgr::new(), None);
-  library.collection = collection;
-  library.collection_cache.drain();
-
+  library.reset();
   Ok(cx.undefined())
 }
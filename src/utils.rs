@@ -10,7 +10,7 @@ use neon::result::Throw;
 use neon::object::This;
 use css_color::Rgba;
 use skia_safe::{
-  Path, Matrix, Point, Color, Color4f, RGB, Rect, FontArguments,
+  Path, Matrix, M44, Point, Color, Color4f, RGB, Rect, FontArguments,
   font_style::{FontStyle, Weight, Width, Slant},
   font_arguments::{VariationPosition, variation_position::{Coordinate}}
 };
@@ -224,6 +224,18 @@ pub fn opt_float_arg(cx: &mut FunctionContext, idx: usize) -> Option<f32>{
   None
 }
 
+pub fn opt_double_arg(cx: &mut FunctionContext, idx: usize) -> Option<f64>{
+  // like opt_float_arg but without the loss of precision from casting to f32
+  if let Some(arg) = cx.argument_opt(idx as i32) {
+    if let Ok(num) = arg.downcast::<JsNumber, _>(cx){
+      if num.value(cx).is_finite(){
+        return Some(num.value(cx))
+      }
+    }
+  }
+  None
+}
+
 pub fn float_arg_or(cx: &mut FunctionContext, idx: usize, default:f64) -> f32{
   match opt_float_arg(cx, idx){
     Some(v) => v,
@@ -289,16 +301,129 @@ pub fn float_args(cx: &mut FunctionContext, rng: Range<usize>) -> NeonResult<Vec
 
 
 pub fn css_to_color<'a>(css:&str) -> Option<Color> {
-  css.parse::<Rgba>().ok().map(|Rgba{red, green, blue, alpha}|
-    Color::from_argb(
-      (alpha*255.0).round() as u8,
-      (red*255.0).round() as u8,
-      (green*255.0).round() as u8,
-      (blue*255.0).round() as u8,
+  css.parse::<Rgba>().ok()
+    .map(|Rgba{red, green, blue, alpha}| [red, green, blue, alpha])
+    .or_else(|| css4_to_rgba(css))
+    .map(|[red, green, blue, alpha]|
+      Color::from_argb(
+        (alpha*255.0).round() as u8,
+        (red*255.0).round() as u8,
+        (green*255.0).round() as u8,
+        (blue*255.0).round() as u8,
+      )
     )
-  )
 }
 
+fn css4_to_rgba(css:&str) -> Option<[f32; 4]> {
+  // handle the CSS Color Level 4 functions (and `none` components) that css-color doesn't support
+  let css = css.trim().to_lowercase();
+  let (name, args) = css.strip_suffix(')')?.split_once('(')?;
+  let (args, alpha) = match args.split_once('/'){
+    Some((args, alpha)) => (args, css_number(alpha.trim(), 1.0)?),
+    None => (args, 1.0)
+  };
+  let args:Vec<&str> = args.split_whitespace().collect();
+
+  let [r, g, b] = match (name.trim(), args.as_slice()){
+    ("rgb" | "rgba", [r, g, b]) => [
+      css_number(r, 255.0)? / 255.0, css_number(g, 255.0)? / 255.0, css_number(b, 255.0)? / 255.0
+    ],
+    ("hsl" | "hsla", [h, s, l]) => hsl_to_rgb(
+      css_angle(h)?, css_number(s, 100.0)? / 100.0, css_number(l, 100.0)? / 100.0
+    ),
+    ("oklab", [l, a, b]) => {
+      let lab = [css_number(l, 1.0)?, css_number(a, 0.4)?, css_number(b, 0.4)?];
+      oklab_to_linear(lab).map(from_linear)
+    },
+    ("oklch", [l, c, h]) => {
+      let (c, h) = (css_number(c, 0.4)?.max(0.0), css_angle(h)?.to_radians());
+      let lab = [css_number(l, 1.0)?, c * h.cos(), c * h.sin()];
+      oklab_to_linear(lab).map(from_linear)
+    },
+    ("color", [space, r, g, b]) => {
+      let rgb = [css_number(r, 1.0)?, css_number(g, 1.0)?, css_number(b, 1.0)?];
+      match *space{
+        "srgb" => rgb,
+        "srgb-linear" => rgb.map(from_linear),
+        "display-p3" => p3_to_linear_srgb(rgb.map(to_linear)).map(from_linear),
+        _ => return None
+      }
+    },
+    _ => return None
+  };
+
+  // colors outside of the sRGB gamut are clipped
+  Some([r, g, b, alpha].map(|c| c.clamp(0.0, 1.0)))
+}
+
+fn css_number(arg:&str, percent:f32) -> Option<f32> {
+  // treat `none` as zero and scale percentages relative to the component's reference range
+  let num = match arg{
+    "none" => 0.0,
+    _ => match arg.strip_suffix('%'){
+      Some(pct) => pct.parse::<f32>().ok()? * percent / 100.0,
+      None => arg.parse::<f32>().ok()?
+    }
+  };
+  num.is_finite().then(|| num)
+}
+
+fn css_angle(arg:&str) -> Option<f32> {
+  let units = [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / PI), ("turn", 360.0)];
+  match units.iter().find(|(unit, _)| arg.ends_with(unit)){
+    Some((unit, scale)) => css_number(arg.strip_suffix(unit)?, 1.0).map(|n| n * scale),
+    None => css_number(arg, 1.0)
+  }
+}
+
+fn hsl_to_rgb(hue:f32, sat:f32, lum:f32) -> [f32; 3]{
+  let (sat, lum) = (sat.clamp(0.0, 1.0), lum.clamp(0.0, 1.0));
+  let channel = |n:f32| {
+    let k = (n + hue.rem_euclid(360.0) / 30.0) % 12.0;
+    lum - sat * lum.min(1.0 - lum) * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+  };
+  [channel(0.0), channel(8.0), channel(4.0)]
+}
+
+fn p3_to_linear_srgb([r, g, b]:[f32; 3]) -> [f32; 3]{
+  [
+     1.2249401 * r - 0.2249404 * g,
+    -0.0420569 * r + 1.0420571 * g,
+    -0.0196376 * r - 0.0786361 * g + 1.0982735 * b,
+  ]
+}
+
+pub fn to_linear(c:f32) -> f32{
+  if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+pub fn from_linear(c:f32) -> f32{
+  if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.max(0.0).powf(1.0 / 2.4) - 0.055 }
+}
+
+pub fn linear_to_oklab([r, g, b]:[f32; 3]) -> [f32; 3]{
+  let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+  let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+  let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+  [
+    0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+    1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+    0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+  ]
+}
+
+pub fn oklab_to_linear([l, a, b]:[f32; 3]) -> [f32; 3]{
+  let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+  let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+  let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+  [
+     4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+    -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+    -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+  ]
+}
+
+
 pub fn color_in<'a>(cx: &mut FunctionContext<'a>, val: Handle<'a, JsValue>) -> Option<Color> {
   if val.is_a::<JsString, _>(cx) {
     let css = val.downcast::<JsString, _>(cx).unwrap().value(cx);
@@ -322,6 +447,16 @@ pub fn color_in<'a>(cx: &mut FunctionContext<'a>, val: Handle<'a, JsValue>) -> O
   None
 }
 
+pub fn color_or_current<'a>(cx: &mut FunctionContext<'a>, val: Handle<'a, JsValue>, current:Color) -> Option<Color> {
+  // resolve the `currentColor` keyword to the context's current color (at assignment time)
+  if let Ok(css) = val.downcast::<JsString, _>(cx){
+    if css.value(cx).trim().eq_ignore_ascii_case("currentcolor"){
+      return Some(current)
+    }
+  }
+  color_in(cx, val)
+}
+
 pub fn color_arg(cx: &mut FunctionContext, idx: usize) -> Option<Color> {
   match cx.argument_opt(idx as i32) {
     Some(arg) => color_in(cx, arg),
@@ -355,6 +490,11 @@ pub fn color_to_css<'a>(cx: &mut FunctionContext<'a>, color:&Color) -> JsResult<
 //   }
 // }
 
+pub fn matrix_scale(matrix:&Matrix) -> Point{
+  // the length of each transformed unit axis (which remains accurate when the matrix is rotated or skewed)
+  Point::new(matrix.scale_x().hypot(matrix.skew_y()), matrix.skew_x().hypot(matrix.scale_y()))
+}
+
 pub fn to_matrix(t:&[f32]) -> Option<Matrix>{
   match t.len(){
     6 => Some(Matrix::new_all(t[0], t[1], t[2], t[3], t[4], t[5], 0.0, 0.0, 1.0)),
@@ -384,6 +524,21 @@ pub fn opt_matrix_arg(cx: &mut FunctionContext, idx: usize) -> Option<Matrix>{
   None
 }
 
+pub fn opt_matrix_3d_arg(cx: &mut FunctionContext, idx: usize) -> Option<M44>{
+  // expects the 16 terms of a 4×4 matrix in row-major order
+  if let Some(arg) = cx.argument_opt(idx as i32) {
+    if let Ok(array) = arg.downcast::<JsArray, _>(cx) {
+      if let Ok(vals) = array.to_vec(cx){
+        let terms = floats_in(cx, &vals);
+        if let Ok(terms) = <[f32; 16]>::try_from(terms.as_slice()){
+          return Some(M44::row_major(&terms))
+        }
+      }
+    }
+  }
+  None
+}
+
 pub fn matrix_arg(cx: &mut FunctionContext, idx:usize) -> NeonResult<Matrix> {
   match opt_matrix_arg(cx, idx){
     Some(v) => Ok(v),
@@ -444,6 +599,10 @@ use crate::filter::{FilterSpec, FilterQuality};
 
 pub fn filter_arg(cx: &mut FunctionContext, idx: usize) -> NeonResult<(String, Vec<FilterSpec>)> {
   let arg = cx.argument::<JsObject>(idx as i32)?;
+  filter_in(cx, arg)
+}
+
+pub fn filter_in(cx: &mut FunctionContext, arg: Handle<JsObject>) -> NeonResult<(String, Vec<FilterSpec>)> {
   let canonical = string_for_key(cx, &arg, "canonical")?;
 
   let obj:Handle<JsObject> = arg.get(cx, "filters")?;
@@ -462,6 +621,12 @@ pub fn filter_arg(cx: &mut FunctionContext, idx: usize) -> NeonResult<(String, V
           });
         }
       },
+      "matrix" => {
+        let values = obj.get::<JsArray, _, _>(cx, key)?.to_vec(cx)?;
+        if let Ok(values) = <[f32; 20]>::try_from(floats_in(cx, &values)) {
+          filters.push(FilterSpec::ColorMatrix{ values });
+        }
+      },
       _ => {
         let value = obj.get::<JsNumber, _, _>(cx, key)?.value(cx) as f32;
         filters.push(FilterSpec::Plain{
@@ -0,0 +1,37 @@
+// Compares drawSprite() with the equivalent (unscaled) drawImage() call
+// usage: node test/benchmarks/drawSprite.js [iterations]
+
+"use strict"
+
+const {Canvas} = require('../../lib')
+
+const FRAME = 32,
+      FRAMES = 16,
+      ITERATIONS = parseInt(process.argv[2]) || 100000
+
+let sheet = new Canvas(FRAME * FRAMES, FRAME),
+    sctx = sheet.getContext('2d')
+for (let i=0; i<FRAMES; i++){
+  sctx.fillStyle = `hsl(${i * 360 / FRAMES}, 100%, 50%)`
+  sctx.fillRect(i * FRAME, 0, FRAME, FRAME)
+}
+
+function time(label, draw){
+  let canvas = new Canvas(800, 600),
+      ctx = canvas.getContext('2d')
+
+  let start = process.hrtime.bigint()
+  for (let i=0; i<ITERATIONS; i++){
+    let frame = i % FRAMES,
+        x = (i * 37) % (canvas.width - FRAME),
+        y = (i * 53) % (canvas.height - FRAME)
+    draw(ctx, frame * FRAME, x, y)
+  }
+  ctx.getImageData(0, 0, 1, 1) // render the recorded drawing commands
+  let elapsed = Number(process.hrtime.bigint() - start) / 1e6
+  console.log(`${label.padEnd(12)} ${elapsed.toFixed(1).padStart(9)} ms  (${(elapsed * 1000 / ITERATIONS).toFixed(2)} µs/call)`)
+}
+
+console.log(`Drawing ${ITERATIONS} ${FRAME}×${FRAME} sprites…`)
+time('drawImage', (ctx, sx, x, y) => ctx.drawImage(sheet, sx, 0, FRAME, FRAME, x, y, FRAME, FRAME))
+time('drawSprite', (ctx, sx, x, y) => ctx.drawSprite(sheet, [sx, 0, FRAME, FRAME], x, y))
@@ -4,7 +4,8 @@ const _ = require('lodash'),
       fs = require('fs'),
       tmp = require('tmp'),
       glob = require('glob').sync,
-      {Canvas, Image} = require('../lib');
+      {Canvas, CanvasEncoder, Image, PdfExporter} = require('../lib'),
+      FEATURES = require('../lib/v6').Canvas_features();
 
 const BLACK = [0,0,0,255],
       WHITE = [255,255,255,255],
@@ -12,12 +13,14 @@ const BLACK = [0,0,0,255],
       MAGIC = {
         jpg: Buffer.from([0xFF, 0xD8, 0xFF]),
         png: Buffer.from([0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]),
+        webp: Buffer.from(`RIFF`, 'utf-8'),
         pdf: Buffer.from([0x25, 0x50, 0x44, 0x46, 0x2d]),
         svg: Buffer.from(`<?xml version`, 'utf-8')
       },
       MIME = {
         png: "image/png",
         jpg: "image/jpeg",
+        webp: "image/webp",
         pdf: "application/pdf",
         svg: "image/svg+xml"
       };
@@ -52,6 +55,17 @@ describe("Canvas", ()=>{
       expect(canvas.height).toBe(456)
       expect(ctx.fillStyle).toBe('#000000')
       expect(pixel(0,0)).toEqual(CLEAR)
+
+      // dimensions are truncated to integers
+      canvas.width = 12.7
+      canvas.height = 34.2
+      expect(canvas.width).toBe(12)
+      expect(canvas.height).toBe(34)
+
+      // zero-sized canvases can't be drawn as images
+      canvas.width = 0
+      let dst = new Canvas(10, 10).getContext("2d")
+      expect(() => dst.drawImage(canvas, 0, 0)).toThrow()
     })
   })
 
@@ -260,8 +274,35 @@ describe("Canvas", ()=>{
       expect(header.equals(MAGIC.pdf)).toBe(true)
     })
 
+    test("PDFs from multiple canvases", () => {
+      let pdf = new PdfExporter(),
+          small = new Canvas(100, 50)
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      small.getContext("2d").fillRect(0, 0, 100, 50)
+
+      expect(pdf.addPage(ctx)).toBe(pdf)
+      pdf.addPage(small).addPage(ctx, 300, 200)
+      expect(pdf.pages).toBe(3)
+
+      let doc = pdf.finish(),
+          text = doc.toString('latin1'),
+          pages = text.match(/\/Type \/Page\b/g)
+      expect(doc.slice(0, MAGIC.pdf.length)).toEqual(MAGIC.pdf)
+      expect(pages.length).toBe(3)
+      expect(text).toContain(`/MediaBox [0 0 ${WIDTH} ${HEIGHT}]`)
+      expect(text).toContain('/MediaBox [0 0 100 50]')
+      expect(text).toContain('/MediaBox [0 0 300 200]')
+
+      expect(() => new PdfExporter().finish()).toThrow('no pages')
+      expect(() => pdf.addPage({})).toThrow('Expected a CanvasRenderingContext2D')
+      expect(() => pdf.addPage(ctx, 0, 100)).toThrow('must be non-zero')
+      expect(() => pdf.addPage(ctx, 300)).toThrow(TypeError)
+      expect(() => pdf.addPage(ctx, undefined, 200)).toThrow('both a width and height')
+      expect(() => pdf.addPage(ctx, 300, NaN)).toThrow('both a width and height')
+    })
+
     test("image Buffers", async () => {
-      for (let ext of ["png", "jpg", "pdf", "svg"]){
+      for (let ext of ["png", "jpg", "webp", "pdf", "svg"]){
         // use extension to specify type
         let path = `${TMP}/output.${ext}`
         let buf = await canvas.toBuffer(ext)
@@ -282,6 +323,18 @@ describe("Canvas", ()=>{
       }
     })
 
+    test("JPEG XL Buffers", async () => {
+      if (FEATURES.includes('jxl')){
+        // bare codestreams start with 0xFF0A
+        let buf = await canvas.toBuffer('jxl')
+        expect(buf.slice(0, 2).equals(Buffer.from([0xFF, 0x0A]))).toBe(true)
+        expect(canvas.toBufferSync('image/jxl', {lossless:true}).length).toBeGreaterThan(0)
+      }else{
+        await expect(canvas.toBuffer('jxl')).rejects.toThrow('compiled with the `jxl` feature')
+        expect(() => canvas.toBufferSync('image/jxl')).toThrow('compiled with the `jxl` feature')
+      }
+    })
+
     test("data URLs", async () => {
       for (let ext in MIME){
         let magic = MAGIC[ext],
@@ -427,7 +480,7 @@ describe("Canvas", ()=>{
     })
 
     test("image Buffers", () => {
-      for (let ext of ["png", "jpg", "pdf", "svg"]){
+      for (let ext of ["png", "jpg", "webp", "pdf", "svg"]){
         // use extension to specify type
         let path = `${TMP}/output.${ext}`
         let buf = canvas.toBufferSync(ext)
@@ -477,6 +530,61 @@ describe("Canvas", ()=>{
       expect(canvas).toMatchObject({width, height})
       expect( () => canvas.saveAsSync(`${TMP}/zeroed.png`)).toThrowError("must be non-zero")
     })
+
+    test("animated PNGs", async () => {
+      let enc = new CanvasEncoder("apng", {loops:3})
+      for (const color of ['red', 'green', 'blue']){
+        ctx.fillStyle = color
+        ctx.fillRect(0, 0, WIDTH, HEIGHT)
+        enc.addFrame(canvas, 250)
+      }
+      expect(enc.frames).toBe(3)
+
+      let apng = enc.finish(),
+          chunks = type => apng.reduce((n, _, i) => n + (apng.toString('latin1', i, i + 4) == type), 0),
+          acTL = apng.indexOf('acTL')
+      expect(apng.slice(0, MAGIC.png.length)).toEqual(MAGIC.png)
+      expect(apng.readUInt32BE(acTL + 4)).toBe(3) // № frames
+      expect(apng.readUInt32BE(acTL + 8)).toBe(3) // № plays
+      expect(chunks('fcTL')).toBe(3)
+      expect(chunks('fdAT')).toBeGreaterThanOrEqual(2)
+
+      // the first frame is also the image's default (non-animated) contents
+      let img = new Image()
+      img.src = apng
+      await img.decode()
+      ctx.drawImage(img, 0, 0)
+      expect(pixel(0, 0)).toEqual([255, 0, 0, 255])
+
+      expect(() => new CanvasEncoder("gif")).toThrow('Unsupported animation format')
+      expect(() => enc.addFrame(new Canvas(10, 10))).toThrow('same dimensions')
+      expect(() => new CanvasEncoder().finish()).toThrow('no frames')
+    })
+
+    test("canvases from images", async () => {
+      ctx.fillStyle = 'red'
+      ctx.fillRect(0, 0, WIDTH / 2, HEIGHT / 2)
+      let img = new Image()
+      img.src = canvas.toBufferSync("png")
+      await img.decode()
+
+      // the new canvas matches the image's size and starts out with its pixels
+      let copy = Canvas.fromImage(img),
+          copyCtx = copy.getContext("2d"),
+          copyPixel = (x, y) => Array.from(copyCtx.getImageData(x, y, 1, 1).data)
+      expect([copy.width, copy.height]).toEqual([WIDTH, HEIGHT])
+      expect(copyPixel(10, 10)).toEqual([255, 0, 0, 255])
+      expect(copyPixel(WIDTH - 10, 10)).toEqual(CLEAR)
+
+      // and can be drawn on top of
+      copyCtx.fillStyle = 'black'
+      copyCtx.fillRect(0, 0, 5, 5)
+      expect(copyPixel(2, 2)).toEqual(BLACK)
+      expect(copyPixel(10, 10)).toEqual([255, 0, 0, 255])
+
+      expect(() => Canvas.fromImage(new Image())).toThrow('finished loading')
+      expect(() => Canvas.fromImage(canvas)).toThrow('Expected an Image')
+    })
   })
 
 })
@@ -3,7 +3,8 @@
 "use strict"
 
 const _ = require('lodash'),
-      {Canvas, DOMMatrix, DOMPoint, ImageData, loadImage} = require('../lib'),
+      {inspect} = require('util'),
+      {Canvas, CanvasGradient, DOMMatrix, DOMPoint, FontLibrary, ImageData, Path2D, loadImage} = require('../lib'),
       css = require('../lib/css');
 
 const BLACK = [0,0,0,255],
@@ -49,6 +50,175 @@ describe("Context2D", ()=>{
       )
     })
 
+    test('resetTransform()', () => {
+      ctx.translate(40, 60)
+      ctx.rotate(Math.PI / 3)
+      ctx.scale(2, 5)
+      ctx.transform(1, 0.5, 0.5, 1, 7, 9)
+      ctx.resetTransform()
+      let matrix = ctx.getTransform()
+      expect(matrix.isIdentity).toBe(true)
+      _.each({a:1, b:0, c:0, d:1, e:0, f:0}, (val, term) =>
+        expect(matrix[term]).toBeCloseTo(val)
+      )
+
+      // subsequent drawing is unaffected by the prior transforms
+      ctx.fillRect(0, 0, 10, 10)
+      expect(pixel(5, 5)).toEqual(BLACK)
+      expect(pixel(45, 65)).toEqual(CLEAR)
+    })
+
+    test('3D transforms', () => {
+      let tilt = new DOMMatrix().translate(10, 20, 30).rotateAxisAngle(1, 1, 0, 45)
+      ctx.setTransform(tilt)
+      let matrix = ctx.getTransform()
+      expect(matrix.is2D).toBe(false)
+      _.each(tilt.toFloat64Array(), (val, i) =>
+        expect(matrix.toFloat64Array()[i]).toBeCloseTo(val)
+      )
+
+      // 2D operations are applied to the full 4×4 matrix
+      ctx.translate(5, 5)
+      ctx.scale(2, 3)
+      let expected = tilt.translate(5, 5).scale(2, 3)
+      _.each(expected.toFloat64Array(), (val, i) =>
+        expect(ctx.getTransform().toFloat64Array()[i]).toBeCloseTo(val)
+      )
+
+      // drawing uses the matrix's effect on the z=0 plane
+      let pt = ctx.transformPoint(7, 11),
+          projected = expected.transformPoint({x:7, y:11})
+      expect(pt.x).toBeCloseTo(projected.x / projected.w)
+      expect(pt.y).toBeCloseTo(projected.y / projected.w)
+
+      ctx.resetTransform()
+      expect(ctx.getTransform().is2D).toBe(true)
+      expect(ctx.getTransform().isIdentity).toBe(true)
+
+      ctx.setTransform(tilt.toFloat64Array())
+      expect(ctx.getTransform().is2D).toBe(false)
+      ctx.setTransform(1, 0, 0, 1, 0, 0)
+      expect(ctx.getTransform().is2D).toBe(true)
+    })
+
+    test('rotation angles', () => {
+      let terms = () => _.pick(ctx.getTransform(), ['a', 'b', 'c', 'd'])
+
+      ctx.rotateDeg(90)
+      let deg = terms()
+      ctx.resetTransform()
+      ctx.rotate(Math.PI / 2)
+      _.each(terms(), (val, term) => expect(val).toBeCloseTo(deg[term]) )
+
+      // huge angles are reduced to a single revolution before losing precision
+      // (1e9 radians is 159154943 full turns plus ~0.577 radians)
+      let huge = 1e9, normalized = huge - 2 * Math.PI * Math.floor(huge / (2 * Math.PI))
+      expect(normalized).toBeCloseTo(0.577, 3)
+      ctx.resetTransform()
+      ctx.rotate(huge)
+      _.each({a:Math.cos(normalized), b:Math.sin(normalized), c:-Math.sin(normalized), d:Math.cos(normalized)}, (val, term) =>
+        expect(ctx.getTransform()[term]).toBeCloseTo(val, 4)
+      )
+
+      ctx.resetTransform()
+      ctx.rotateDeg(360 * 1e7 + 30)
+      _.each({a:Math.sqrt(3)/2, b:0.5, c:-0.5, d:Math.sqrt(3)/2}, (val, term) =>
+        expect(ctx.getTransform()[term]).toBeCloseTo(val, 4)
+      )
+
+      // 10 seconds of an animation at 60fps that rotates once every 3 seconds
+      let fps = 60, period = 3
+      for (let frame=1; frame<=fps * 10; frame++){
+        ctx.resetTransform()
+        ctx.rotate(2 * Math.PI * frame / (fps * period))
+      }
+      let angle = 2 * Math.PI * 10 / period
+      _.each({a:Math.cos(angle), b:Math.sin(angle), c:-Math.sin(angle), d:Math.cos(angle)}, (val, term) =>
+        expect(ctx.getTransform()[term]).toBeCloseTo(val, 4)
+      )
+
+      // incrementally accumulated rotations (10 full revolutions) return to the identity matrix
+      ctx.resetTransform()
+      for (let frame=0; frame<fps * 10; frame++) ctx.rotate(2 * Math.PI / fps)
+      _.each({a:1, b:0, c:0, d:1}, (val, term) =>
+        expect(ctx.getTransform()[term]).toBeCloseTo(val, 3)
+      )
+    })
+
+    test('currentPath', () => {
+      expect(ctx.currentPath).toBeInstanceOf(Path2D)
+      expect(ctx.currentPath.edges).toEqual([])
+
+      ctx.translate(100, 100)
+      ctx.rect(0, 0, 50, 50)
+      ctx.scale(2, 2)
+      ctx.moveTo(0, 0)
+      ctx.lineTo(10, 10)
+
+      // coordinates are relative to the current transform
+      let {left, top, right, bottom} = ctx.currentPath.bounds
+      expect([left, top, right, bottom]).toEqual([0, 0, 25, 25])
+      expect(Path2D.transformed(ctx).bounds.right).toEqual(150)
+
+      // the snapshot is independent of the context's path
+      let snapshot = ctx.currentPath,
+          count = snapshot.edges.length
+      snapshot.lineTo(100, 0)
+      ctx.lineTo(0, 20)
+      expect(snapshot.edges.length).toBe(count + 1)
+      expect(snapshot.bounds.right).toEqual(100)
+      expect(ctx.currentPath.edges.length).toBe(count + 1)
+      expect(ctx.currentPath.bounds.right).toEqual(25)
+
+      ctx.beginPath()
+      expect(ctx.currentPath.edges).toEqual([])
+    })
+
+    test('pathBounds()', () => {
+      let square = new Path2D()
+      square.rect(0, 0, 10, 10)
+      ctx.translate(100, 50)
+      ctx.scale(2, 2)
+
+      let {x, y, width, height, right, bottom} = ctx.pathBounds(square)
+      expect([x, y, width, height, right, bottom]).toEqual([100, 50, 20, 20, 120, 70])
+
+      // bounds are tight even when rotated
+      ctx.rotate(Math.PI / 4)
+      let rotated = ctx.pathBounds(square)
+      expect(rotated.width).toBeCloseTo(20 * Math.SQRT2)
+      expect(rotated.x).toBeCloseTo(100 - 10 * Math.SQRT2)
+
+      // the current path can be measured without modifying it
+      ctx.resetTransform()
+      expect(ctx.pathBounds().width).toBe(0)
+      ctx.moveTo(5, 5)
+      ctx.lineTo(15, 25)
+      let current = ctx.pathBounds()
+      expect([current.x, current.y, current.width, current.height]).toEqual([5, 5, 10, 20])
+      expect(ctx.currentPath.edges.length).toBe(2)
+
+      expect(() => ctx.pathBounds({})).toThrowError("Expected a Path2D")
+    })
+
+    test('fontBoundingBoxAscent & fontBoundingBoxDescent', () => {
+      for (const baseline of ['alphabetic', 'top', 'middle', 'bottom']){
+        ctx.font = "20px Arial, DejaVu Sans"
+        ctx.textBaseline = baseline
+        let {fontBoundingBoxAscent, fontBoundingBoxDescent} = ctx.measureText("")
+        expect(ctx.fontBoundingBoxAscent).toBeCloseTo(fontBoundingBoxAscent)
+        expect(ctx.fontBoundingBoxDescent).toBeCloseTo(fontBoundingBoxDescent)
+      }
+
+      ctx.textBaseline = 'alphabetic'
+      let ascent = ctx.fontBoundingBoxAscent
+      expect(ascent).toBeGreaterThan(0)
+      expect(ctx.fontBoundingBoxDescent).toBeGreaterThan(0)
+
+      ctx.font = "40px Arial, DejaVu Sans"
+      expect(ctx.fontBoundingBoxAscent).toBeCloseTo(ascent * 2, 0)
+    })
+
     test('font', () => {
       expect(ctx.font).toBe('10px sans-serif')
       let font = '16px Baskerville, serif',
@@ -59,6 +229,155 @@ describe("Context2D", ()=>{
       expect(ctx.font).toBe(canonical)
     })
 
+    test('fontVariantNumeric', () => {
+      expect(ctx.fontVariantNumeric).toBe('normal')
+      ctx.fontVariant = 'small-caps'
+      ctx.fontVariantNumeric = 'oldstyle-nums tabular-nums'
+      expect(ctx.fontVariantNumeric).toBe('oldstyle-nums tabular-nums')
+      expect(ctx.fontVariant).toBe('small-caps oldstyle-nums tabular-nums')
+
+      // conflicting or non-numeric values are ignored
+      ctx.fontVariantNumeric = 'oldstyle-nums lining-nums'
+      expect(ctx.fontVariantNumeric).toBe('oldstyle-nums tabular-nums')
+      ctx.fontVariantNumeric = 'small-caps'
+      expect(ctx.fontVariantNumeric).toBe('oldstyle-nums tabular-nums')
+
+      ctx.fontVariantNumeric = 'normal'
+      expect(ctx.fontVariantNumeric).toBe('normal')
+      expect(ctx.fontVariant).toBe('small-caps')
+    })
+
+    test('fontVariantLigatures', () => {
+      expect(ctx.fontVariantLigatures).toBe('normal')
+      ctx.fontVariant = 'small-caps'
+      ctx.fontVariantLigatures = 'no-common-ligatures no-contextual'
+      expect(ctx.fontVariantLigatures).toBe('no-common-ligatures no-contextual')
+      expect(ctx.fontVariant).toBe('small-caps no-common-ligatures no-contextual')
+
+      // conflicting or non-ligature values are ignored
+      ctx.fontVariantLigatures = 'common-ligatures no-common-ligatures'
+      expect(ctx.fontVariantLigatures).toBe('no-common-ligatures no-contextual')
+      ctx.fontVariantLigatures = 'tabular-nums'
+      expect(ctx.fontVariantLigatures).toBe('no-common-ligatures no-contextual')
+      ctx.fontVariantLigatures = 'none contextual'
+      expect(ctx.fontVariantLigatures).toBe('no-common-ligatures no-contextual')
+
+      ctx.fontVariantLigatures = 'none'
+      expect(ctx.fontVariantLigatures).toBe('none')
+      expect(ctx.fontVariant).toBe('small-caps none')
+
+      ctx.fontVariantLigatures = 'normal'
+      expect(ctx.fontVariantLigatures).toBe('normal')
+      expect(ctx.fontVariant).toBe('small-caps')
+
+      // disabling ligatures changes how text is shaped: DejaVu's ff, ffi & ffl ligatures are each
+      // 31/2048 em narrower than the letters they replace
+      FontLibrary.use("Ligatures", `${__dirname}/assets/DejaVuSans-ExtraLight.ttf`)
+      ctx.font = '200px Ligatures'
+      ctx.fontVariantLigatures = 'normal'
+      let ligated = ctx.measureText('ff ffi ffl').width
+      ctx.fontVariantLigatures = 'none'
+      let unligated = ctx.measureText('ff ffi ffl').width
+      expect(unligated - ligated).toBeGreaterThan(6) // ≈9px
+      FontLibrary.reset()
+    })
+
+    test('fontKerning', () => {
+      expect(ctx.fontKerning).toBe('auto')
+      ctx.fontKerning = 'none'
+      expect(ctx.fontKerning).toBe('none')
+      ctx.fontKerning = 'kerning'
+      expect(ctx.fontKerning).toBe('none')
+
+      // the setting survives font changes and turns off pair adjustments
+      ctx.font = '16px Menlo, DejaVu Sans Mono, monospace'
+      expect(ctx.fontKerning).toBe('none')
+      let [a, v, av] = ['A', 'V', 'AV'].map(str => ctx.measureText(str).width)
+      expect(av).toBeCloseTo(a + v, 3)
+
+      ctx.font = '32px Arial, DejaVu Sans'
+      let unkerned = ctx.measureText('AV').width
+      ctx.fontKerning = 'normal'
+      expect(ctx.fontKerning).toBe('normal')
+      expect(ctx.measureText('AV').width).toBeLessThan(unkerned)
+    })
+
+    test('setShadowColor()', () => {
+      ctx.setShadowColor(0, 0, 1)
+      expect(ctx.shadowColor).toBe('#0000ff')
+      ctx.setShadowColor(1, 0, 0, 0.5)
+      expect(ctx.shadowColor).toBe('rgba(255, 0, 0, 0.502)')
+
+      // out-of-range channels are clamped and non-finite ones are ignored
+      ctx.setShadowColor(2, -1, 0, 1)
+      expect(ctx.shadowColor).toBe('#ff0000')
+      ctx.setShadowColor(NaN, 0, 0, 1)
+      expect(ctx.shadowColor).toBe('#ff0000')
+    })
+
+    test('fillStyle & strokeStyle objects', () => {
+      let gradient = ctx.createLinearGradient(0, 0, 10, 10),
+          pattern = ctx.createPattern(new Canvas(4, 4), 'repeat')
+      ctx.fillStyle = gradient
+      ctx.strokeStyle = pattern
+
+      // symbols set by user code don't clobber the retained style objects
+      ctx[Symbol.for('fill')] = 'oops'
+      ctx[Symbol.for('stroke')] = 'oops'
+      expect(ctx.fillStyle).toBe(gradient)
+      expect(ctx.strokeStyle).toBe(pattern)
+    })
+
+    test('currentColor', () => {
+      expect(ctx.currentColor).toBe('#000000')
+      ctx.currentColor = 'rgb(255, 0, 0)'
+      expect(ctx.currentColor).toBe('#ff0000')
+      ctx.currentColor = 'not a color'
+      expect(ctx.currentColor).toBe('#ff0000')
+
+      // the keyword is resolved at assignment time
+      ctx.fillStyle = 'currentColor'
+      ctx.strokeStyle = 'CurrentColor'
+      ctx.shadowColor = 'currentcolor'
+      ctx.currentColor = 'green'
+      expect(ctx.fillStyle).toBe('#ff0000')
+      expect(ctx.strokeStyle).toBe('#ff0000')
+      expect(ctx.shadowColor).toBe('#ff0000')
+      ctx.fillStyle = 'currentColor'
+      expect(ctx.fillStyle).toBe('#008000')
+
+      // it's part of the saved state
+      ctx.save()
+      ctx.currentColor = 'blue'
+      ctx.restore()
+      expect(ctx.currentColor).toBe('#008000')
+    })
+
+    test('shadowOffsetX & shadowOffsetY', () => {
+      ctx.shadowOffsetX = 3
+      ctx.shadowOffsetY = '4px'
+      expect(ctx.shadowOffsetX).toBe(3)
+      expect(ctx.shadowOffsetY).toBe(4)
+
+      ctx.font = '20px serif'
+      ctx.shadowOffsetX = '0.5em'
+      ctx.shadowOffsetY = '-1rem'
+      expect(ctx.shadowOffsetX).toBe(10)
+      expect(ctx.shadowOffsetY).toBe(-16)
+
+      ctx.rootFontSize = 10
+      ctx.shadowOffsetY = '2rem'
+      expect(ctx.shadowOffsetY).toBe(20)
+      ctx.shadowOffsetX = '12pt'
+      expect(ctx.shadowOffsetX).toBe(16)
+
+      // invalid lengths are ignored
+      ctx.shadowOffsetX = 'far'
+      ctx.rootFontSize = -1
+      expect(ctx.shadowOffsetX).toBe(16)
+      expect(ctx.rootFontSize).toBe(10)
+    })
+
     test('globalAlpha', () => {
       expect(ctx.globalAlpha).toBe(1)
       ctx.globalAlpha = 0.25
@@ -128,6 +447,16 @@ describe("Context2D", ()=>{
       expect(ctx.getLineDash()).toEqual([1,2,3,4])
       ctx.setLineDash(null)
       expect(ctx.getLineDash()).toEqual([1,2,3,4])
+
+      // odd-length lists are doubled
+      ctx.setLineDash([5, 10, 15])
+      expect(ctx.getLineDash()).toEqual([5, 10, 15, 5, 10, 15])
+
+      // lists with negative or non-finite entries are ignored
+      ctx.setLineDash([5, -10])
+      expect(ctx.getLineDash()).toEqual([5, 10, 15, 5, 10, 15])
+      ctx.setLineDash([5, NaN])
+      expect(ctx.getLineDash()).toEqual([5, 10, 15, 5, 10, 15])
     })
 
     test('lineJoin', () => {
@@ -167,8 +496,79 @@ describe("Context2D", ()=>{
         ctx.textAlign = val
         expect(ctx.textAlign).toBe(val)
       }
+
+      // matchParent resolves to a physical side based on the direction
+      ctx.textAlign = 'matchParent'
+      expect(ctx.textAlign).toBe('left')
+      ctx.direction = 'rtl'
+      ctx.textAlign = 'matchParent'
+      expect(ctx.textAlign).toBe('right')
+
+      // the resolved value doesn't track later changes to the direction
+      ctx.direction = 'ltr'
+      expect(ctx.textAlign).toBe('right')
     })
 
+    test('direction', () => {
+      expect(ctx.direction).toBe('ltr')
+      for (let val of ['rtl', 'auto', 'ltr']){
+        ctx.direction = val
+        expect(ctx.direction).toBe(val)
+      }
+      ctx.direction = 'invalid'
+      expect(ctx.direction).toBe('ltr')
+
+      // in auto mode, `start` alignment depends on the first strongly-directional character
+      let startsAt = text => {
+        let {width, actualBoundingBoxLeft} = ctx.measureText(text)
+        return actualBoundingBoxLeft / width
+      }
+      ctx.direction = 'auto'
+      ctx.textAlign = 'start'
+      expect(startsAt('Hello, עולם!')).toBeCloseTo(0)
+      expect(startsAt('123 שלום, world!')).toBeCloseTo(1)
+      expect(startsAt('⁧שלום⁩ world')).toBeCloseTo(0) // isolates are skipped
+      expect(startsAt('123')).toBeCloseTo(0) // falls back to the prior direction
+
+      ctx.direction = 'rtl'
+      expect(startsAt('Hello, עולם!')).toBeCloseTo(1)
+    })
+
+    test('getContextAttributes()', () => {
+      expect(ctx.getContextAttributes()).toEqual({
+        alpha: true, colorSpace: 'srgb', desynchronized: false, willReadFrequently: false, measureTextCache: 0
+      })
+
+      let opaque = new Canvas(WIDTH, HEIGHT),
+          octx = opaque.getContext("2d", {alpha:false, willReadFrequently:true}),
+          opixel = (x, y) => Array.from(octx.getImageData(x, y, 1, 1).data)
+      expect(octx.getContextAttributes()).toEqual({
+        alpha: false, colorSpace: 'srgb', desynchronized: false, willReadFrequently: true, measureTextCache: 0
+      })
+      expect(opaque.getContext("2d", {alpha:true})).toBe(octx)
+      expect(opaque.newPage().getContextAttributes().alpha).toBe(false)
+
+      // opaque canvases start out black and are cleared to black
+      expect(opixel(0, 0)).toEqual(BLACK)
+      octx.fillStyle = 'white'
+      octx.fillRect(0, 0, WIDTH, HEIGHT)
+      octx.clearRect(0, 0, 10, 10)
+      expect(opixel(5, 5)).toEqual(BLACK)
+      expect(opixel(15, 15)).toEqual(WHITE)
+      octx.clearRect(0, 0, WIDTH, HEIGHT)
+      expect(opixel(15, 15)).toEqual(BLACK)
+
+      // the bitmap has no alpha channel, so even 'copy'-ing translucent colors leaves it opaque
+      octx.globalCompositeOperation = 'copy'
+      octx.fillStyle = 'rgba(255, 255, 255, 0.5)'
+      octx.fillRect(0, 0, 10, 10)
+      expect(opixel(5, 5)[3]).toBe(255)
+      expect(opixel(15, 15)).toEqual(BLACK)
+
+      // and PNGs are exported as RGB (color type 2) rather than RGBA (color type 6)
+      expect(opaque.toBufferSync('png')[25]).toBe(2)
+      expect(canvas.toBufferSync('png')[25]).toBe(6)
+    })
   })
 
   describe("can create", ()=>{
@@ -265,6 +665,51 @@ describe("Context2D", ()=>{
         }
       })
 
+      test("from Canvas (as a snapshot)", () => {
+        let src = new Canvas(4, 4),
+            srcCtx = src.getContext("2d");
+        srcCtx.fillStyle = 'black';
+        srcCtx.fillRect(0,0,4,4);
+
+        // changes made to the source after the pattern is created should not be reflected
+        let pattern = ctx.createPattern(src, 'repeat')
+        srcCtx.fillStyle = 'white';
+        srcCtx.fillRect(0,0,4,4);
+
+        ctx.fillStyle = pattern
+        ctx.fillRect(0,0,20,20)
+        expect(pixel(1, 1)).toEqual(BLACK)
+        expect(pixel(10, 10)).toEqual(BLACK)
+
+        ctx.fillStyle = ctx.createPattern(src, 'repeat')
+        ctx.fillRect(0,0,20,20)
+        expect(pixel(1, 1)).toEqual(WHITE)
+        expect(pixel(10, 10)).toEqual(WHITE)
+      })
+
+      test("with repetition modes", () => {
+        let dot = new Canvas(4, 4),
+            dotCtx = dot.getContext("2d");
+        dotCtx.fillStyle = 'black';
+        dotCtx.fillRect(0,0,4,4);
+
+        let modes = {
+          "repeat":    [BLACK, BLACK, BLACK],
+          "repeat-x":  [BLACK, BLACK, CLEAR],
+          "repeat-y":  [BLACK, CLEAR, BLACK],
+          "no-repeat": [BLACK, CLEAR, CLEAR],
+        }
+
+        for (const [mode, [origin, right, below]] of Object.entries(modes)){
+          ctx.clearRect(0,0,WIDTH,HEIGHT)
+          ctx.fillStyle = ctx.createPattern(dot, mode)
+          ctx.fillRect(0,0,20,20)
+          expect(pixel(1, 1)).toEqual(origin)
+          expect(pixel(10, 1)).toEqual(right)
+          expect(pixel(1, 10)).toEqual(below)
+        }
+      })
+
       test("with local transform", () => {
         // call func with an ImageData-offset and pixel color value appropriate for a 4-quadrant pattern within
         // the width and height that's white in the upper-left & lower-right and black in the other corners
@@ -313,6 +758,94 @@ describe("Context2D", ()=>{
           isCheckerboard(ctx, w*mag, h*mag);
         })
       })
+
+      test("setTransform() argument forms", () => {
+        let dot = new Canvas(4, 4),
+            dotCtx = dot.getContext("2d");
+        dotCtx.fillStyle = 'black';
+        dotCtx.fillRect(0,0,4,4);
+
+        let pat = ctx.createPattern(dot, 'no-repeat'),
+            shifted = new DOMMatrix().translate(10, 10),
+            forms = [
+              shifted, shifted.toJSON(), {e:10, f:10}, [1, 0, 0, 1, 10, 10],
+              new Float32Array([1, 0, 0, 1, 10, 10])
+            ]
+
+        ctx.fillStyle = pat
+        for (let form of forms){
+          ctx.clearRect(0, 0, WIDTH, HEIGHT)
+          pat.setTransform(form)
+          ctx.fillRect(0, 0, 20, 20)
+          expect(pixel(1, 1)).toEqual(CLEAR)
+          expect(pixel(11, 11)).toEqual(BLACK)
+        }
+
+        // individual terms are accepted and omitting the matrix resets to the identity
+        pat.setTransform(1, 0, 0, 1, 10, 10)
+        ctx.clearRect(0, 0, WIDTH, HEIGHT)
+        ctx.fillRect(0, 0, 20, 20)
+        expect(pixel(11, 11)).toEqual(BLACK)
+        pat.setTransform()
+        ctx.clearRect(0, 0, WIDTH, HEIGHT)
+        ctx.fillRect(0, 0, 20, 20)
+        expect(pixel(1, 1)).toEqual(BLACK)
+
+        for (let bad of ["matrix", [1, 2, 3], {a:'big'}, null, [1, 0, 0, 1, NaN, 0]]){
+          expect(() => pat.setTransform(bad)).toThrow(TypeError)
+        }
+      })
+
+      test("with noise", () => {
+        let sample = (...args) => {
+          ctx.clearRect(0, 0, WIDTH, HEIGHT)
+          ctx.fillStyle = ctx.createNoise(...args)
+          ctx.fillRect(0, 0, 64, 64)
+          return Array.from(ctx.getImageData(0, 0, 64, 64).data)
+        }
+
+        let noise = sample(0.1, 2, 7)
+        expect(new Set(noise).size).toBeGreaterThan(16)
+        expect(sample(0.1, 2, 7)).toEqual(noise)
+        expect(sample(0.1, 2, 8)).not.toEqual(noise)
+        expect(sample([0.1, 0.1], 2, 7)).toEqual(noise)
+        expect(sample(0.1, 2, 7, {type:"turbulence"})).not.toEqual(noise)
+
+        // tiled noise repeats at the given interval
+        ctx.fillStyle = ctx.createNoise(0.1, 2, 7, {tile:32})
+        ctx.fillRect(0, 0, 64, 64)
+        expect(pixel(40, 20)).toEqual(pixel(8, 20))
+        expect(pixel(20, 40)).toEqual(pixel(20, 8))
+
+        expect(() => ctx.createNoise(0.1, 1, 0, {type:"plaid"})).toThrow("Unknown noise type")
+        expect(() => ctx.createNoise(-0.1)).toThrow("cannot be negative")
+        expect(() => ctx.createNoise(0.1, 0)).toThrow("at least one octave")
+      })
+
+      test("from Path2D", () => {
+        let square = new Path2D()
+        square.rect(0, 0, 5, 5)
+        ctx.fillStyle = ctx.patternFromPath(square, 10, {color:'green'})
+        ctx.fillRect(0, 0, 40, 40)
+        expect(pixel(2, 2)).toEqual(GREEN)
+        expect(pixel(7, 7)).toEqual(CLEAR)
+        expect(pixel(32, 22)).toEqual(GREEN)
+        expect(pixel(37, 22)).toEqual(CLEAR)
+
+        // a line width strokes the path rather than filling it
+        ctx.clearRect(0, 0, WIDTH, HEIGHT)
+        ctx.fillStyle = ctx.patternFromPath(square, [20, 10], {line:2})
+        ctx.fillRect(0, 0, 40, 40)
+        expect(pixel(5, 2)).toEqual(BLACK)
+        expect(pixel(2, 2)).toEqual(CLEAR)
+
+        expect(inspect(ctx.patternFromPath(square, 10))).toContain("Path 10×10")
+        expect(() => ctx.patternFromPath(square, 0)).toThrow("must be non-zero")
+        expect(() => ctx.patternFromPath(square, NaN)).toThrow("must be finite numbers")
+        expect(() => ctx.patternFromPath(square, [10, Infinity])).toThrow(TypeError)
+        expect(() => ctx.patternFromPath(square, 10, {color:'not-a-color'})).toThrow("Expected a CSS color")
+        expect(() => ctx.patternFromPath('M0 0 L5 5', 10)).toThrow("Expected a Path2D")
+      })
     })
 
     describe("CanvasGradient", () => {
@@ -327,6 +860,117 @@ describe("Context2D", ()=>{
         expect(pixel(20,0)).toEqual(BLACK)
       })
 
+      test("getColorStops()", () => {
+        let gradient = ctx.createRadialGradient(0, 0, 1, 0, 0, 10)
+        expect(gradient.getColorStops()).toEqual([])
+
+        gradient.addColorStop(1, 'rgba(0, 0, 255, 0.4)')
+        gradient.addColorStop(0, 'red')
+        gradient.addColorStop(0.25, '#0f08')
+        expect(gradient.getColorStops()).toEqual([
+          {offset:0, color:"rgba(255, 0, 0, 1)"},
+          {offset:0.25, color:"rgba(0, 255, 0, 0.533)"},
+          {offset:1, color:"rgba(0, 0, 255, 0.4)"},
+        ])
+      })
+
+      test("cloneWithGeometry()", () => {
+        let gradient = ctx.createLinearGradient(0, 0, 100, 0)
+        gradient.addColorStop(0, 'white')
+        gradient.addColorStop(1, 'black')
+
+        let radial = gradient.cloneWithGeometry("radial", 50, 50, 0, 50, 50, 40),
+            conic = gradient.cloneWithGeometry("conic", 0, 50, 50)
+        expect(radial).toBeInstanceOf(CanvasGradient)
+        expect(radial.getColorStops()).toEqual(gradient.getColorStops())
+        expect(conic.getColorStops()).toEqual(gradient.getColorStops())
+
+        // the copies have independent stops
+        radial.addColorStop(0.5, 'red')
+        expect(gradient.getColorStops().length).toBe(2)
+        expect(radial.getColorStops().length).toBe(3)
+
+        ctx.fillStyle = gradient.cloneWithGeometry("linear", 0, 0, 0, 100)
+        ctx.fillRect(0, 0, 100, 100)
+        expect(pixel(90, 0)).toEqual(WHITE)
+        expect(pixel(0, 99)[0]).toBeLessThan(5)
+
+        expect(() => gradient.cloneWithGeometry("linear", 0, 0)).toThrowError("Expected 4 arguments")
+        expect(() => gradient.cloneWithGeometry("radial", 0, 0, -1, 0, 0, 1)).toThrowError("radii cannot be negative")
+        expect(() => gradient.cloneWithGeometry("diagonal", 0, 0, 1, 1)).toThrowError("Unknown gradient style")
+      })
+
+      test("linear with interpolation space", () => {
+        // black-to-white midpoints: ~50% gray in sRGB, brighter when mixing light
+        // linearly, and darker in oklab (whose lightness is perceptual)
+        let mids = {srgb:128, "srgb-linear":188, oklab:99}
+        for (const [space, mid] of Object.entries(mids)){
+          let gradient = ctx.createLinearGradient(0,0,100,0, space);
+          gradient.addColorStop(0,'#000');
+          gradient.addColorStop(1,'#fff');
+          ctx.fillStyle = gradient;
+          ctx.fillRect(0,0,100,1);
+
+          let [r, g, b, a] = pixel(50, 0)
+          for (const c of [r, g, b]) expect(Math.abs(c - mid)).toBeLessThan(4)
+          expect(a).toEqual(255)
+        }
+
+        expect(() => ctx.createLinearGradient(0,0,100,0, "hsl") ).toThrowError("Unknown interpolation space")
+      })
+
+      test("radial & conic with interpolation space", () => {
+        // the radial midpoint should match the linear gradient's
+        for (const [space, mid] of Object.entries({srgb:128, oklab:99})){
+          let gradient = ctx.createRadialGradient(0,0,0, 0,0,100, space)
+          gradient.addColorStop(0,'#000');
+          gradient.addColorStop(1,'#fff');
+          ctx.fillStyle = gradient;
+          ctx.fillRect(0,0,100,1);
+          expect(Math.abs(pixel(50, 0)[0] - mid)).toBeLessThan(4)
+        }
+
+        // conic sweeps (and clones) should blend in their original gradient's space too
+        let sweeps = ["srgb", "oklab"].map(space => {
+          let gradient = ctx.createConicGradient(0, 50, 50, space)
+          gradient.addColorStop(0,'#000');
+          gradient.addColorStop(1,'#fff');
+          return gradient
+        })
+        sweeps.push(sweeps[1].cloneWithGeometry("conic", 0, 50, 50))
+
+        let [srgb, oklab, clone] = sweeps.map(gradient => {
+          ctx.fillStyle = gradient;
+          ctx.fillRect(0,0,100,100);
+          return pixel(10, 50)[0]
+        })
+        expect(srgb - oklab).toBeGreaterThan(10)
+        expect(clone).toEqual(oklab)
+
+        expect(() => ctx.createConicGradient(0, 0, 0, "hsl") ).toThrowError("Unknown interpolation space")
+      })
+
+      test("with translucent color stops", () => {
+        let stops = ['rgba(0, 0, 255, 0.5)', 'hsla(240, 100%, 50%, 0.5)', '#0000ff80', '#00f8']
+        for (const color of stops){
+          ctx.clearRect(0,0,WIDTH,HEIGHT)
+          let gradient = ctx.createLinearGradient(0,0,20,0);
+          gradient.addColorStop(0, color);
+          gradient.addColorStop(1, color);
+          ctx.fillStyle = gradient;
+          ctx.fillRect(0,0,20,20);
+
+          let [r, g, b, a] = pixel(10, 10)
+          expect([r, g, b]).toEqual([0, 0, 255])
+          expect(a).toBeGreaterThan(125)
+          expect(a).toBeLessThan(138)
+        }
+
+        let gradient = ctx.createLinearGradient(0,0,20,0);
+        expect(() => gradient.addColorStop(0, 'not-a-color') ).toThrow(TypeError)
+        expect(() => gradient.addColorStop(0, 'transparent') ).not.toThrow()
+      })
+
       test("radial", () => {
         let [x, y, inside, outside] = [100, 100, 45, 55],
             inner = [x, y, 25],
@@ -344,6 +988,45 @@ describe("Context2D", ()=>{
         expect(pixel(x, y+inside)).toEqual(BLACK)
         expect(pixel(x+outside, y)).toEqual([255,0,0,255])
         expect(pixel(x, y+outside)).toEqual([255,0,0,255])
+
+        expect(() => ctx.createRadialGradient(x, y, -1, x, y, 50) ).toThrowError("radii cannot be negative")
+        expect(() => ctx.createRadialGradient(x, y, 25, x, y, -1) ).toThrowError("radii cannot be negative")
+      })
+
+      test("conical", () => {
+        let args = [50, 100, 10, 150, 100, 60],
+            fill = gradient => {
+              gradient.addColorStop(0, 'white')
+              gradient.addColorStop(1, 'blue')
+              ctx.fillStyle = gradient
+              ctx.fillRect(0, 0, 200, 200)
+              return ctx.getImageData(0, 0, 200, 200).data
+            }
+
+        // identical to a radial gradient with the same circles
+        let conical = fill(ctx.createConicalGradient(...args)),
+            radial = fill(ctx.createRadialGradient(...args))
+        expect(conical).toEqual(radial)
+
+        // colors are clamped beyond the end circle
+        let [r, g, b, a] = pixel(150, 40)
+        expect([b, a]).toEqual([255, 255])
+        expect(Math.max(r, g)).toBeLessThan(5)
+
+        expect(() => ctx.createConicalGradient(0, 0, -1, 0, 0, 50) ).toThrowError("radii cannot be negative")
+      })
+
+      test("radial (with focal point)", () => {
+        // an off-center inner circle skews the ramp toward the focal point
+        let gradient = ctx.createRadialGradient(60, 100, 0, 100, 100, 50)
+        gradient.addColorStop(0,'#fff');
+        gradient.addColorStop(1,'#000');
+        ctx.fillStyle = gradient
+        ctx.fillRect(0,0, 200,200)
+
+        expect(pixel(60, 100)).toEqual(WHITE)
+        expect(pixel(45, 100)).toEqual(BLACK)
+        expect(pixel(75, 100)[0]).toBeGreaterThan(200)
       })
 
       test("conic", () => {
@@ -380,6 +1063,319 @@ describe("Context2D", ()=>{
       expect(pixel(10, 10)).toEqual([0, 161, 212, 245])
     })
 
+    test("save() & restore()", () => {
+      let changes = {
+        fillStyle: '#ff0000',
+        strokeStyle: '#00ff00',
+        font: 'italic bold 24px serif',
+        fontVariant: 'small-caps tabular-nums',
+        direction: 'rtl',
+        textAlign: 'center',
+        textBaseline: 'middle',
+        textTracking: 100,
+        textWrap: true,
+        globalAlpha: 0.5,
+        globalCompositeOperation: 'multiply',
+        imageSmoothingEnabled: false,
+        imageSmoothingQuality: 'high',
+        filter: 'blur(5px) invert(50%)',
+        shadowBlur: 10,
+        shadowColor: '#0000ff',
+        shadowOffsetX: 5,
+        shadowOffsetY: -5,
+        lineCap: 'round',
+        lineDashOffset: 3,
+        lineDashFit: 'move',
+        lineJoin: 'bevel',
+        lineWidth: 7,
+        miterLimit: 4,
+      }
+
+      // make sure every property actually gets modified
+      let original = _.mapValues(changes, (val, prop) => ctx[prop])
+      let lineDash = ctx.getLineDash(),
+          matrix = ctx.getTransform();
+      _.each(changes, (val, prop) => expect(original[prop]).not.toEqual(val))
+
+      ctx.save()
+      Object.assign(ctx, changes)
+      ctx.setLineDash([4, 2])
+      ctx.translate(10, 10)
+      _.each(changes, (val, prop) => expect([prop, ctx[prop]]).not.toEqual([prop, original[prop]]))
+      ctx.restore()
+
+      // then restored to their original values
+      _.each(original, (val, prop) => expect([prop, ctx[prop]]).toEqual([prop, val]))
+      expect(ctx.getLineDash()).toEqual(lineDash)
+      expect(ctx.getTransform()).toEqual(matrix)
+    })
+
+    test("transformPoint() & inverseTransformPoint()", () => {
+      let pt = ctx.transformPoint(10, 20)
+      expect(pt).toBeInstanceOf(DOMPoint)
+      expect(pt).toMatchObject({x:10, y:20})
+
+      ctx.translate(100, 50)
+      ctx.rotate(Math.PI / 2)
+      pt = ctx.transformPoint(10, 0)
+      expect(pt.x).toBeCloseTo(100)
+      expect(pt.y).toBeCloseTo(60)
+
+      pt = ctx.inverseTransformPoint(100, 60)
+      expect(pt.x).toBeCloseTo(10)
+      expect(pt.y).toBeCloseTo(0)
+
+      ctx.scale(0, 0)
+      expect(ctx.inverseTransformPoint(100, 60)).toBeNull()
+    })
+
+    test("filter (with relative lengths)", () => {
+      let blurred = () => {
+        ctx.clearRect(0,0,WIDTH,HEIGHT)
+        ctx.fillRect(100,100,100,100)
+        return [pixel(100, 150), pixel(95, 150), pixel(90, 150)]
+      }
+
+      ctx.filter = 'blur(4px)'
+      let px = blurred()
+      expect(px[2][3]).toBeGreaterThan(0)
+
+      // unitless values are treated as px
+      ctx.filter = 'blur(4)'
+      expect(ctx.filter).toBe('blur(4)')
+      expect(blurred()).toEqual(px)
+
+      // em units are relative to the current font size
+      ctx.font = '8px serif'
+      ctx.filter = 'blur(.5em)'
+      expect(ctx.filter).toBe('blur(.5em)')
+      expect(blurred()).toEqual(px)
+
+      // while rem units are relative to the rootFontSize
+      ctx.rootFontSize = 8
+      ctx.filter = 'blur(.5rem)'
+      expect(blurred()).toEqual(px)
+      expect(css.filter('drop-shadow(1rem 1em .5rem red)', 20, 10).filters['drop-shadow']).toEqual([10, 20, 5, 'red'])
+      ctx.filter = 'blur(.5em)'
+
+      // negative blur radii are invalid and leave the current filter untouched
+      ctx.filter = 'blur(-2px)'
+      expect(ctx.filter).toBe('blur(.5em)')
+      ctx.filter = 'drop-shadow(2px 2px -2px black)'
+      expect(ctx.filter).toBe('blur(.5em)')
+      expect(blurred()).toEqual(px)
+
+      // ...and are dropped from a chain without discarding the filters preceding them
+      ctx.filter = 'invert(100%) blur(-2px)'
+      expect(ctx.filter).toBe('invert(100%)')
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.fillStyle = 'white'
+      ctx.fillRect(0,0,20,20)
+      expect(pixel(10, 10)).toEqual(BLACK)
+      ctx.filter = 'none'
+
+      // shadow offsets can be negative or unitless zeroes
+      ctx.filter = 'drop-shadow(-100px 0 0 black)'
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.fillStyle = 'red'
+      ctx.fillRect(100,100,100,100)
+      expect(pixel(50, 150)).toEqual(BLACK)
+      expect(pixel(150, 150)).toEqual([255, 0, 0, 255])
+    })
+
+    test("filter (with color matrix)", () => {
+      let swapRedBlue = `matrix(0 0 1 0 0
+                                0 1 0 0 0
+                                1 0 0 0 0
+                                0 0 0 1 0)`
+      ctx.filter = swapRedBlue
+      expect(ctx.filter).toBe('matrix(0 0 1 0 0 0 1 0 0 0 1 0 0 0 0 0 0 0 1 0)')
+      ctx.fillStyle = 'red'
+      ctx.fillRect(0,0,20,20)
+      expect(pixel(10, 10)).toEqual([0, 0, 255, 255])
+
+      // the final column is an offset (in the 0–1 range)
+      ctx.filter = 'matrix(1 0 0 0 0, 0 1 0 0 1, 0 0 1 0 0, 0 0 0 1 0)'
+      ctx.fillRect(0,0,20,20)
+      expect(pixel(10, 10)).toEqual([255, 255, 0, 255])
+
+      // malformed matrices are ignored
+      ctx.filter = 'matrix(1 0 0)'
+      expect(ctx.filter).toBe('matrix(1 0 0 0 0 0 1 0 0 1 0 0 1 0 0 0 0 0 1 0)')
+    })
+
+    test("clearRect()", () => {
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      expect(pixel(0, 0)).toEqual(CLEAR)
+      expect(pixel(WIDTH-1, HEIGHT-1)).toEqual(CLEAR)
+
+      // clearing the full canvas only erases the clipped region
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.save()
+      ctx.rect(0, 0, WIDTH/2, HEIGHT)
+      ctx.clip()
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.restore()
+      expect(pixel(WIDTH/4, HEIGHT/2)).toEqual(CLEAR)
+      expect(pixel(WIDTH*3/4, HEIGHT/2)).toEqual(BLACK)
+    })
+
+    test("non-finite rect arguments", () => {
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      for (const bad of [NaN, Infinity, -Infinity, 'foo']){
+        expect(() => {
+          ctx.clearRect(bad, 0, WIDTH, HEIGHT)
+          ctx.clearRect(0, 0, WIDTH, bad)
+          ctx.fillRect(0, bad, 10, 10)
+          ctx.strokeRect(0, 0, bad, 10)
+        }).not.toThrow()
+      }
+      expect(pixel(0, 0)).toEqual(BLACK)
+      expect(() => ctx.fillRect(0, 0, 10)).toThrowError("Not enough arguments")
+    })
+
+    test("drawing with a singular transform", () => {
+      ctx.scale(0, 1)
+      expect(() => {
+        ctx.fillRect(0, 0, 100, 100)
+        ctx.strokeRect(0, 0, 100, 100)
+        ctx.beginPath()
+        ctx.rect(0, 0, 100, 100)
+        ctx.fill()
+        ctx.stroke()
+        ctx.clip()
+      }).not.toThrow()
+      expect(pixel(0, 50)).toEqual(CLEAR)
+
+      ctx.resetTransform()
+      ctx.beginPath()
+      ctx.rect(0, 0, 100, 100)
+      ctx.fill()
+      expect(pixel(50, 50)).toEqual(CLEAR) // still clipped to the zero-area region
+    })
+
+    test("drawCircle()", () => {
+      ctx.moveTo(0, 0)
+      ctx.lineTo(10, 0)
+      ctx.fillStyle = 'green'
+      ctx.drawCircle(50, 50, 20)
+      expect(pixel(50, 50)).toEqual(GREEN)
+      expect(pixel(50, 32)).toEqual(GREEN)
+      expect(pixel(50, 25)).toEqual(CLEAR)
+      expect(pixel(72, 50)).toEqual(CLEAR)
+
+      // the current path is left alone
+      let path = Path2D.transformed(ctx)
+      expect(path.edges.length).toBe(2)
+
+      // shadows are drawn as with any other shape
+      ctx.shadowColor = 'black'
+      ctx.shadowOffsetX = 100
+      ctx.drawCircle(50, 150, 20)
+      expect(pixel(150, 150)).toEqual(BLACK)
+
+      expect(() => ctx.drawCircle(50, 50, -1)).toThrowError("radius cannot be negative")
+      expect(() => ctx.drawCircle(50, 50, -1)).toThrowError(RangeError)
+      expect(() => ctx.drawCircle(NaN, 50, 10)).not.toThrow()
+      expect(() => ctx.drawCircle(50, 50)).toThrowError("Not enough arguments")
+    })
+
+    test("drawOval()", () => {
+      ctx.moveTo(0, 0)
+      ctx.lineTo(10, 0)
+      ctx.fillStyle = 'green'
+      ctx.drawOval(20, 40, 60, 20)
+      expect(pixel(50, 50)).toEqual(GREEN)
+      expect(pixel(22, 50)).toEqual(GREEN)
+      expect(pixel(50, 38)).toEqual(CLEAR)
+      expect(pixel(22, 42)).toEqual(CLEAR)
+
+      // the current path is left alone
+      expect(Path2D.transformed(ctx).edges.length).toBe(2)
+
+      // negative dimensions are measured leftward/upward from the origin
+      ctx.drawOval(180, 160, -60, -20)
+      expect(pixel(150, 150)).toEqual(GREEN)
+
+      // stroked ovals use the line settings and leave the interior empty
+      ctx.lineWidth = 4
+      ctx.strokeStyle = 'black'
+      ctx.drawOval(200, 0, 100, 50, 'stroke')
+      expect(pixel(250, 1)).toEqual(BLACK)
+      expect(pixel(250, 25)).toEqual(CLEAR)
+
+      expect(() => ctx.drawOval(0, 0, 10, 10, 'outline')).toThrowError('Unknown drawOval style "outline"')
+      expect(() => ctx.drawOval(NaN, 0, 10, 10)).not.toThrow()
+      expect(() => ctx.drawOval(0, 0, 10)).toThrowError("Not enough arguments")
+    })
+
+    test("drawLine()", () => {
+      ctx.moveTo(0, 0)
+      ctx.lineTo(10, 0)
+      ctx.lineWidth = 10
+      ctx.strokeStyle = 'green'
+      ctx.drawLine(20, 50, 120, 50)
+      expect(pixel(70, 50)).toEqual(GREEN)
+      expect(pixel(70, 46)).toEqual(GREEN)
+      expect(pixel(70, 40)).toEqual(CLEAR)
+      expect(pixel(15, 50)).toEqual(CLEAR)
+
+      // the current path is left alone
+      expect(Path2D.transformed(ctx).edges.length).toBe(2)
+
+      // line caps and shadows are applied
+      ctx.lineCap = 'square'
+      ctx.shadowColor = 'black'
+      ctx.shadowOffsetY = 20
+      ctx.drawLine(20, 100, 120, 100)
+      expect(pixel(17, 100)).toEqual(GREEN)
+      expect(pixel(70, 120)).toEqual(BLACK)
+
+      expect(() => ctx.drawLine(NaN, 0, 10, 10)).not.toThrow()
+      expect(() => ctx.drawLine(0, 0, 10)).toThrowError("Not enough arguments")
+    })
+
+    test("drawPoints()", () => {
+      ctx.lineWidth = 10
+      ctx.strokeStyle = 'green'
+
+      ctx.drawPoints("points", new Float32Array([20, 20, 60, 20]))
+      expect(pixel(20, 20)).toEqual(GREEN)
+      expect(pixel(60, 20)).toEqual(GREEN)
+      expect(pixel(40, 20)).toEqual(CLEAR)
+
+      // segments are drawn between pairs of points (and not between the pairs)
+      ctx.drawPoints("lines", [[20, 50], [60, 50], [100, 50], [140, 50]])
+      expect(pixel(40, 50)).toEqual(GREEN)
+      expect(pixel(80, 50)).toEqual(CLEAR)
+      expect(pixel(120, 50)).toEqual(GREEN)
+
+      // polygons connect every point but are left open
+      ctx.drawPoints("polygon", [20, 100, 100, 100, 100, 180])
+      expect(pixel(60, 100)).toEqual(GREEN)
+      expect(pixel(100, 140)).toEqual(GREEN)
+      expect(pixel(60, 140)).toEqual(CLEAR)
+
+      // textured strokes draw butt-capped points as squares too
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.strokeStyle = ctx.createTexture(4, {line:4, color:'green'})
+      ctx.drawPoints("points", [20, 20, 60, 20])
+      let inked = (x, y) => Array.from(ctx.getImageData(x - 4, y - 4, 8, 8).data).some(c => c > 0)
+      expect(inked(20, 20)).toBe(true)
+      expect(inked(60, 20)).toBe(true)
+      expect(inked(40, 20)).toBe(false)
+      expect(ctx.lineCap).toBe('butt')
+
+      ctx.lineCap = 'round'
+      ctx.drawPoints("points", [100, 20])
+      expect(inked(100, 20)).toBe(true)
+
+      expect(Path2D.transformed(ctx).edges.length).toBe(0)
+      expect(() => ctx.drawPoints("dots", [0, 0])).toThrowError('Unknown drawPoints mode "dots"')
+      expect(() => ctx.drawPoints("points")).toThrowError("Expected an array")
+    })
+
     test("clip()", () => {
       ctx.fillStyle = 'white'
       ctx.fillRect(0, 0, 2, 2)
@@ -467,6 +1463,272 @@ describe("Context2D", ()=>{
       expect(pixel(30, 30)).toEqual(BLACK)
     })
 
+    test("clip() with a Path2D", () => {
+      let square = new Path2D()
+      square.rect(0, 0, 20, 20)
+
+      // the path is interpreted in the current coordinate system, like the context's own path
+      ctx.save()
+      ctx.translate(50, 50)
+      ctx.scale(2, 2)
+      ctx.clip(square)
+      ctx.setTransform(1, 0, 0, 1, 0, 0)
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.restore()
+
+      expect(pixel(10, 10)).toEqual(CLEAR)
+      expect(pixel(55, 55)).toEqual(BLACK)
+      expect(pixel(85, 85)).toEqual(BLACK)
+      expect(pixel(95, 95)).toEqual(CLEAR)
+
+      // later transforms don't move a clip that's already been applied
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.save()
+      ctx.rotate(Math.PI / 4)
+      ctx.clip(square)
+      ctx.rotate(-Math.PI / 4)
+      ctx.translate(100, 100)
+      ctx.fillRect(-100, -100, WIDTH, HEIGHT)
+      ctx.restore()
+
+      expect(pixel(1, 10)).toEqual(BLACK)
+      expect(pixel(10, 1)).toEqual(CLEAR)
+    })
+
+    test("shadows with globalCompositeOperation", () => {
+      ctx.fillStyle = 'black'
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.shadowColor = 'red'
+      ctx.shadowOffsetX = ctx.shadowOffsetY = 20
+
+      // the shadow is composited using the same blend mode as the shape
+      ctx.globalCompositeOperation = 'destination-over'
+      ctx.fillStyle = 'white'
+      ctx.fillRect(10, 10, 20, 20)
+      expect(pixel(15, 15)).toEqual(BLACK)
+      expect(pixel(45, 45)).toEqual(BLACK)
+
+      // 'copy' replaces the whole canvas with the shape and its shadow
+      ctx.globalCompositeOperation = 'copy'
+      ctx.fillRect(10, 10, 20, 20)
+      expect(pixel(15, 15)).toEqual(WHITE)
+      expect(pixel(45, 45)).toEqual([255, 0, 0, 255])
+      expect(pixel(100, 100)).toEqual(CLEAR)
+    })
+
+    test("filter with globalAlpha", () => {
+      // the filter sees the source at full opacity and alpha is applied to its output
+      ctx.globalAlpha = 0.5
+      ctx.filter = 'matrix(1 0 0 0 0, 0 1 0 0 0, 0 0 1 0 0, 0 0 0 2 0)'
+      ctx.fillStyle = 'red'
+      ctx.fillRect(10, 10, 20, 20)
+      let [r, g, b, a] = pixel(20, 20)
+      expect(r).toBeGreaterThan(250)
+      expect(a).toBeCloseTo(128, -1)
+
+      // the shadow is faded by the same amount
+      ctx.filter = 'saturate(1)'
+      ctx.shadowColor = 'blue'
+      ctx.shadowOffsetX = ctx.shadowOffsetY = 50
+      ctx.fillRect(100, 100, 20, 20)
+      expect(pixel(160, 160)[3]).toBeCloseTo(128, -1)
+      expect(pixel(110, 110)[3]).toBeCloseTo(128, -1)
+    })
+
+    test("clipOut()", () => {
+      // punch a hole in an unclipped canvas
+      let hole = new Path2D()
+      hole.arc(50, 50, 20, 0, 2*Math.PI)
+      ctx.save()
+      ctx.clipOut(hole)
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.restore()
+      expect(pixel(50, 50)).toEqual(CLEAR)
+      expect(pixel(10, 10)).toEqual(BLACK)
+      expect(pixel(75, 50)).toEqual(BLACK)
+
+      // combine with a standard clip to create a ring
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.save()
+      ctx.beginPath()
+      ctx.arc(50, 50, 40, 0, 2*Math.PI)
+      ctx.clip()
+      ctx.clipOut(hole)
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.restore()
+      expect(pixel(50, 50)).toEqual(CLEAR)
+      expect(pixel(75, 50)).toEqual(BLACK)
+      expect(pixel(95, 50)).toEqual(CLEAR)
+
+      // the current path & fill rule can be used instead of a Path2D
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.save()
+      ctx.beginPath()
+      ctx.rect(10, 10, 80, 80)
+      ctx.rect(30, 30, 40, 40)
+      ctx.clipOut('evenodd')
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.restore()
+      expect(pixel(5, 5)).toEqual(BLACK)
+      expect(pixel(20, 20)).toEqual(CLEAR)
+      expect(pixel(50, 50)).toEqual(BLACK)
+
+      // Path2D arguments are affected by the current transform
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.save()
+      ctx.translate(100, 0)
+      ctx.clipOut(hole)
+      ctx.fillRect(-100, 0, WIDTH, HEIGHT)
+      ctx.restore()
+      expect(pixel(50, 50)).toEqual(BLACK)
+      expect(pixel(150, 50)).toEqual(CLEAR)
+    })
+
+    test("clipStroke()", () => {
+      let line = new Path2D()
+      line.moveTo(0, 50)
+      line.lineTo(200, 50)
+
+      // the clip follows the stroke's width and dashes
+      ctx.save()
+      ctx.lineWidth = 20
+      ctx.setLineDash([50, 50])
+      ctx.clipStroke(line)
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.restore()
+      expect(pixel(25, 50)).toEqual(BLACK)
+      expect(pixel(25, 42)).toEqual(BLACK)
+      expect(pixel(25, 35)).toEqual(CLEAR)
+      expect(pixel(75, 50)).toEqual(CLEAR)
+      expect(pixel(125, 50)).toEqual(BLACK)
+
+      // the current path is used if no Path2D is passed, and is scaled along with the line width
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.save()
+      ctx.scale(2, 2)
+      ctx.lineWidth = 10
+      ctx.beginPath()
+      ctx.moveTo(0, 100)
+      ctx.lineTo(100, 100)
+      ctx.clipStroke()
+      ctx.resetTransform()
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      ctx.restore()
+      expect(pixel(100, 200)).toEqual(BLACK)
+      expect(pixel(100, 192)).toEqual(BLACK)
+      expect(pixel(100, 185)).toEqual(CLEAR)
+      expect(pixel(210, 200)).toEqual(CLEAR)
+    })
+
+    test("applyBackdropFilter()", () => {
+      ctx.fillStyle = 'black'
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+
+      // only the region within the (transformed) rect is affected
+      ctx.translate(10, 10)
+      ctx.applyBackdropFilter('invert(100%)', 0, 0, 50, 50)
+      ctx.resetTransform()
+      expect(pixel(5, 5)).toEqual(BLACK)
+      expect(pixel(30, 30)).toEqual(WHITE)
+      expect(pixel(75, 75)).toEqual(BLACK)
+
+      // omitting the rect applies the filter to the whole canvas
+      ctx.applyBackdropFilter('invert(100%)')
+      expect(pixel(5, 5)).toEqual(WHITE)
+      expect(pixel(30, 30)).toEqual(BLACK)
+
+      // the filter doesn't persist in the context's state
+      expect(ctx.filter).toBe('none')
+      expect(() => ctx.applyBackdropFilter('not-a-filter(1)')).toThrow('Invalid filter')
+    })
+
+    test("stencil()", () => {
+      let square = new Path2D()
+      square.rect(10, 10, 20, 20)
+
+      let result = ctx.stencil(square, c => {
+        expect(c).toBe(ctx)
+        ctx.fillStyle = 'green'
+        ctx.translate(5, 5)
+        ctx.fillRect(0, 0, WIDTH, HEIGHT)
+        return 'done'
+      })
+      expect(result).toBe('done')
+      expect(pixel(20, 20)).toEqual(GREEN)
+      expect(pixel(5, 20)).toEqual(CLEAR)
+      expect(pixel(35, 35)).toEqual(CLEAR)
+
+      // the clip and other state changes are discarded afterward
+      expect(ctx.fillStyle).toBe('#000000')
+      expect(ctx.getTransform().isIdentity).toBe(true)
+      ctx.fillRect(40, 40, 10, 10)
+      expect(pixel(45, 45)).toEqual(BLACK)
+
+      // even if the callback throws
+      expect(() => ctx.stencil(square, () => { throw new Error("oops") })).toThrowError("oops")
+      ctx.fillRect(60, 60, 10, 10)
+      expect(pixel(65, 65)).toEqual(BLACK)
+
+      // a fill rule can be passed before the callback
+      let ring = new Path2D()
+      ring.rect(100, 100, 50, 50)
+      ring.rect(110, 110, 30, 30)
+      ctx.stencil(ring, 'evenodd', () => ctx.fillRect(0, 0, WIDTH, HEIGHT))
+      expect(pixel(105, 105)).toEqual(BLACK)
+      expect(pixel(125, 125)).toEqual(CLEAR)
+
+      expect(() => ctx.stencil(square)).toThrowError("Expected a callback function")
+      expect(() => ctx.stencil(null, () => {})).toThrowError("Expected a Path2D")
+    })
+
+    test("beginPath()", () => {
+      let nested = () => {
+        ctx.beginPath()
+        ctx.rect(10, 10, 100, 100)
+        ctx.rect(35, 35, 50, 50)
+      }
+
+      // clipping with the evenodd rule shouldn't affect the fill-rule of subsequent paths
+      nested()
+      ctx.clip('evenodd')
+      nested()
+      expect(ctx.isPointInPath(60, 60)).toBe(true)
+      expect(ctx.isPointInPath(60, 60, 'evenodd')).toBe(false)
+
+      ctx.fill()
+      expect(pixel(20, 20)).toEqual(BLACK)
+      expect(pixel(60, 60)).toEqual(CLEAR) // masked by the clip, not the fill
+
+      ctx.reset()
+      nested()
+      ctx.fill()
+      expect(pixel(60, 60)).toEqual(BLACK)
+    })
+
+    test("path()", () => {
+      ctx.beginPath()
+      ctx.rect(200, 200, 10, 10)
+
+      // builders can be interleaved without affecting each other or the current path
+      let outer = ctx.path().rect(10, 10, 100, 100),
+          inner = ctx.path().moveTo(35, 35)
+      inner.lineTo(85, 35).lineTo(85, 85).lineTo(35, 85).closePath()
+      outer.rect(35, 35, 50, 50)
+
+      let path = outer.fill('evenodd')
+      expect(path).toBeInstanceOf(Path2D)
+      expect(pixel(20, 20)).toEqual(BLACK)
+      expect(pixel(60, 60)).toEqual(CLEAR)
+
+      ctx.fillStyle = 'white'
+      inner.fill()
+      expect(pixel(60, 60)).toEqual(WHITE)
+
+      expect(ctx.isPointInPath(205, 205)).toBe(true)
+      expect(ctx.isPointInPath(20, 20)).toBe(false)
+      expect(ctx.currentPath.bounds).toMatchObject({left:200, top:200, right:210, bottom:210})
+    })
+
     test("fill()", () => {
       ctx.fillStyle = 'white'
       ctx.fillRect(0, 0, 2, 2)
@@ -544,6 +1806,26 @@ describe("Context2D", ()=>{
       }
     })
 
+    test("non-finite path arguments", () => {
+      ctx.moveTo(10, 10)
+      ctx.lineTo(20, 20)
+      let before = Path2D.transformed(ctx).edges
+
+      for (const bad of [NaN, Infinity, -Infinity]){
+        expect(() => {
+          ctx.moveTo(bad, 10)
+          ctx.lineTo(30, bad)
+          ctx.quadraticCurveTo(bad, 10, 30, 30)
+          ctx.bezierCurveTo(10, 10, 20, bad, 30, 30)
+          ctx.arcTo(10, bad, 30, 30, 5)
+          ctx.arcTo(10, bad, 30, 30, -5)
+          ctx.arcTo(10, 10, 30, 30, bad)
+        }).not.toThrow()
+      }
+
+      expect(Path2D.transformed(ctx).edges).toEqual(before)
+    })
+
     test('getImageData()', () => {
       ctx.fillStyle = 'rgba(255,0,0, 0.25)'
       ctx.fillRect(0,0,1,6)
@@ -572,6 +1854,71 @@ describe("Context2D", ()=>{
       }
     })
 
+    test('readPixels()', () => {
+      ctx.fillStyle = 'rgba(255,0,0, 0.5)'
+      ctx.fillRect(0,0,2,2)
+
+      let u8 = ctx.readPixels({x:0, y:0, width:2, height:2})
+      expect(u8).toBeInstanceOf(Uint8ClampedArray)
+      expect(u8.length).toBe(16)
+      expect(Array.from(u8.slice(0,4))).toEqual([255,0,0,128])
+      expect(Array.from(ctx.readPixels([0,0,1,1], {alphaType:"premul"}))).toEqual([128,0,0,128])
+
+      let [r, g, b, a] = ctx.readPixels([0,0,1,1], {colorType:"f32"})
+      expect(r).toBeCloseTo(1, 2)
+      expect(g).toBe(0)
+      expect(b).toBe(0)
+      expect(a).toBeCloseTo(0.5, 2)
+
+      let f16 = ctx.readPixels([0,0,2,2], {colorType:"f16"})
+      expect(f16).toBeInstanceOf(Uint16Array)
+      expect(f16.length).toBe(16)
+      expect(f16[0]).toBe(0x3c00) // 1.0 as a half-float
+
+      // regions outside the canvas are transparent
+      expect(Array.from(ctx.readPixels([-1,-1,1,1]))).toEqual(CLEAR)
+
+      expect(() => ctx.readPixels([0,0,1,1], {colorType:"u16"})).toThrowError('Unknown colorType "u16"')
+      expect(() => ctx.readPixels([0,0,1,1], {alphaType:"opaque"})).toThrowError('Unknown alphaType "opaque"')
+      expect(() => ctx.readPixels([0,0,0,1])).toThrowError("must be non-zero")
+    })
+
+    test('hitTest()', () => {
+      ctx.fillStyle = 'rgba(0,0,0, 0.1)'
+      ctx.fillRect(10, 10, 10, 10)
+      expect(ctx.hitTest(10, 10)).toBe(true)
+      expect(ctx.hitTest(19.9, 15)).toBe(true)
+      expect(ctx.hitTest(20, 15)).toBe(false)
+
+      // coordinates are in device pixels regardless of the transform
+      ctx.translate(100, 100)
+      expect(ctx.hitTest(15, 15)).toBe(true)
+
+      // erased and offscreen pixels aren't hits
+      ctx.resetTransform()
+      ctx.clearRect(10, 10, 5, 5)
+      expect(ctx.hitTest(12, 12)).toBe(false)
+      expect(ctx.hitTest(-1, 15)).toBe(false)
+      expect(ctx.hitTest(WIDTH, 0)).toBe(false)
+    })
+
+    test('colorAtPoint()', () => {
+      ctx.fillStyle = 'rgba(255, 0, 0, 0.5)'
+      ctx.fillRect(10, 10, 10, 10)
+      let {r, g, b, a} = ctx.colorAtPoint(15, 15)
+      expect(r).toBeCloseTo(1, 2)
+      expect(g).toBe(0)
+      expect(b).toBe(0)
+      expect(a).toBeCloseTo(0.5, 2)
+
+      // coordinates are in device pixels regardless of the transform
+      ctx.scale(2, 2)
+      expect(ctx.colorAtPoint(15, 15).a).toBeCloseTo(0.5, 2)
+
+      expect(ctx.colorAtPoint(5, 5)).toEqual({r:0, g:0, b:0, a:0})
+      expect(ctx.colorAtPoint(-1, WIDTH)).toEqual({r:0, g:0, b:0, a:0})
+    })
+
     test('putImageData()', () => {
       expect(() => ctx.putImageData({}, 0, 0)).toThrow()
       expect(() => ctx.putImageData(undefined, 0, 0)).toThrow()
@@ -589,6 +1936,22 @@ describe("Context2D", ()=>{
         4,5,6,255, 0,0,0,0,
         0,0,0,0,   0,0,0,0
       ])
+
+      // semi-transparent pixels survive the round trip unpremultiplied
+      let translucent = ctx.createImageData(2, 1)
+      translucent.data.set([255,0,255,128, 0,255,0,64], 0)
+      ctx.putImageData(translucent, 0, 0)
+      expect(Array.from(ctx.getImageData(0, 0, 2, 1).data)).toEqual([255,0,255,128, 0,255,0,64])
+
+      // dirty rects are clamped to the image data's bounds and ignore the clipping mask
+      ctx.reset()
+      ctx.rect(0, 0, 1, 1)
+      ctx.clip()
+      ctx.putImageData(srcImageData, 10, 10, 6, 0, -5, 1)
+      expect(pixel(10, 10)).toEqual(CLEAR)
+      expect(pixel(11, 10)).toEqual([5,6,7,255])
+      expect(pixel(11, 11)).toEqual(CLEAR)
+      expect(pixel(12, 10)).toEqual(CLEAR)
     })
 
     test("isPointInPath()", () => {
@@ -651,9 +2014,393 @@ describe("Context2D", ()=>{
       })
       expect(lft).toBeCloseTo(cnt)
       expect(cnt).toBeCloseTo(rgt)
+
+      // but the bounding box should be positioned relative to the alignment point
+      _.each({left:[0, 1], center:[0.5, 0.5], right:[1, 0]}, ([l, r], align) => {
+        ctx.textAlign = align
+        let {width, actualBoundingBoxLeft, actualBoundingBoxRight} = ctx.measureText(msg)
+        expect(actualBoundingBoxLeft).toBeCloseTo(width * l, 0)
+        expect(actualBoundingBoxRight).toBeCloseTo(width * r, 0)
+        expect(actualBoundingBoxLeft + actualBoundingBoxRight).toBeCloseTo(width)
+      })
+    })
+
+    test("textBaseline = 'top'", () => {
+      ctx.font = "100px Arial, DejaVu Sans"
+
+      // the em square spans the font size, divided between ascent & descent
+      let {emHeightAscent, emHeightDescent, hangingBaseline} = ctx.measureText("H")
+      expect(emHeightAscent + emHeightDescent).toBeCloseTo(100)
+      expect(emHeightAscent).toBeLessThan(ctx.fontBoundingBoxAscent + 0.01)
+
+      // 'top' aligns the font's cap-height (which is also the hanging baseline) with the y coordinate:
+      // 71.6px for Arial and 72.9px for DejaVu Sans
+      expect([71.6, 72.9].some(h => Math.abs(hangingBaseline - h) < 0.1)).toBe(true)
+      ctx.textBaseline = 'top'
+      let metrics = ctx.measureText("H")
+      expect(metrics.hangingBaseline).toBeCloseTo(0)
+      expect(metrics.alphabeticBaseline).toBeCloseTo(-hangingBaseline)
+      expect(metrics.emHeightAscent).toBeCloseTo(emHeightAscent - hangingBaseline)
+
+      // so the capital's top edge falls on the y coordinate
+      ctx.fillText("H", 10, 100)
+      let inked = y => ctx.getImageData(0, y, 100, 1).data.some(c => c > 0)
+      expect(inked(97)).toBe(false)
+      expect(inked(102)).toBe(true)
+      expect(inked(100 + hangingBaseline - 2)).toBe(true)
+      expect(inked(100 + hangingBaseline + 2)).toBe(false)
+    })
+
+    test("measureText() with measureTextCache", () => {
+      let cached = new Canvas(WIDTH, HEIGHT).getContext("2d", {measureTextCache:2}),
+          attrs = ['width', 'actualBoundingBoxLeft', 'actualBoundingBoxRight', 'actualBoundingBoxAscent', 'alphabeticBaseline'],
+          same = (a, b) => attrs.forEach(attr => expect(a[attr]).toBeCloseTo(b[attr]))
+      expect(cached.getContextAttributes().measureTextCache).toBe(2)
+      expect(new Canvas().getContext("2d", {measureTextCache:true}).getContextAttributes().measureTextCache).toBe(256)
+
+      for (let c of [ctx, cached]) c.font = "20px Arial, DejaVu Sans"
+      for (let msg of ["foo", "bar", "baz", "foo", "foo"]){
+        same(cached.measureText(msg), ctx.measureText(msg))
+      }
+
+      // changes to the typography-related state are reflected in the results
+      for (let [prop, val] of [['font', '40px Arial, DejaVu Sans'], ['textAlign', 'center'], ['textBaseline', 'top'], ['textTracking', 100]]){
+        ctx[prop] = cached[prop] = val
+        same(cached.measureText("foo"), ctx.measureText("foo"))
+      }
+
+      cached.save()
+      cached.font = "10px Arial, DejaVu Sans"
+      let small = cached.measureText("foo").width
+      cached.restore()
+      expect(cached.measureText("foo").width).toBeGreaterThan(small)
+    })
+
+    test("multi-line text", () => {
+      ctx.font = "20px Arial, DejaVu Sans"
+      let msg = "Lordran\ngypsum\nAnor Londo",
+          inked = (x, y, w, h) => ctx.getImageData(x, y, w, h).data.some(c => c > 0)
+
+      // newlines are treated as spaces by default
+      let metrics = ctx.measureText(msg)
+      expect(metrics.lines.length).toBe(1)
+      expect(metrics.width).toBeCloseTo(ctx.measureText(msg.replace(/\n/g, ' ')).width)
+
+      // but act as hard line-breaks when wrapping is enabled
+      ctx.textWrap = true
+      metrics = ctx.measureText(msg)
+      expect(metrics.lines.length).toBe(3)
+      expect(metrics.lines.map(({startIndex, endIndex}) => msg.slice(startIndex, endIndex).trim()))
+        .toEqual(["Lordran", "gypsum", "Anor Londo"])
+      let [first, second, third] = metrics.lines
+      expect(second.baseline).toBeGreaterThan(first.baseline)
+      expect(third.baseline - second.baseline).toBeCloseTo(second.baseline - first.baseline)
+      expect(metrics.actualBoundingBoxDescent).toBeGreaterThan(third.baseline)
+
+      // the overall bounding box spans the full block of lines
+      let blockHeight = metrics.actualBoundingBoxAscent + metrics.actualBoundingBoxDescent
+      expect(blockHeight).toBeCloseTo(third.y + third.height - first.y)
+      expect(ctx.measureText("Lordran", 10).lines.length).toBe(1)
+      expect(ctx.measureText("Lordran gypsum", 10).lines.length).toBe(2)
+
+      ctx.fillText(msg, 10, 30)
+      expect(inked(10, 10, 100, 25)).toBe(true)
+      expect(inked(10, 10 + third.baseline, 100, 25)).toBe(true)
+
+      // outlines use the same line spacing as fillText
+      let {bounds} = ctx.outlineText(msg)
+      expect(bounds.height).toBeGreaterThan(third.baseline)
+    })
+
+    test("setLineDash()", () => {
+      let alpha = (x, y) => pixel(x, y)[3]
+      ctx.lineWidth = 2
+      ctx.moveTo(0, 10)
+      ctx.lineTo(100, 10)
+
+      ctx.setLineDash([5, 3])
+      ctx.stroke()
+      expect(alpha(2, 10)).toBe(255)
+      expect(alpha(6, 10)).toBe(0)
+
+      // an empty list should restore solid lines
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.setLineDash([])
+      ctx.stroke()
+      expect(ctx.getLineDash()).toEqual([])
+      for (let x=0; x<16; x++) expect(alpha(x, 10)).toBe(255)
+    })
+
+    test("lineCap (with dashes)", () => {
+      let alpha = (x, y) => pixel(x, y)[3]
+      ctx.lineWidth = 4
+      ctx.setLineDash([4, 8])
+      ctx.moveTo(20, 10)
+      ctx.lineTo(100, 10)
+
+      // each dash should be capped, not just the ends of the line
+      let caps = {
+        butt:   [0, [0, 0]],
+        square: [255, [255, 255]],
+        round:  [255, [1, 128]],
+      }
+
+      for (const [cap, [edge, [cornerMin, cornerMax]]] of Object.entries(caps)){
+        ctx.clearRect(0,0,WIDTH,HEIGHT)
+        ctx.lineCap = cap
+        ctx.stroke()
+        expect(alpha(22, 10)).toBe(255)
+        expect(alpha(24, 10)).toBe(edge)
+        expect(alpha(25, 8)).toBeGreaterThanOrEqual(cornerMin)
+        expect(alpha(25, 8)).toBeLessThanOrEqual(cornerMax)
+        expect(alpha(29, 10)).toBe(0)
+      }
+    })
+
+    test("setLineDashAdvanced()", () => {
+      let alpha = (x, y) => pixel(x, y)[3],
+          line = () => {
+            ctx.clearRect(0, 0, WIDTH, HEIGHT)
+            ctx.beginPath()
+            ctx.moveTo(10, 10)
+            ctx.lineTo(200, 10)
+            ctx.stroke()
+          }
+      ctx.lineWidth = 10
+
+      // each dash gets its own caps (round ones extend beyond the dash by half the line width)
+      ctx.setLineDashAdvanced([{length:20, cap:'butt'}, 20, {length:20, cap:'round'}, 20])
+      expect(ctx.getLineDash()).toEqual([20, 20, 20, 20])
+      line()
+      expect(alpha(20, 10)).toBe(255)
+      expect(alpha(32, 10)).toBe(0)  // just past the butt-capped dash
+      expect(alpha(43, 10)).toBe(0)  // just before the round-capped dash
+      expect(alpha(47, 10)).toBe(255) // round cap
+      expect(alpha(52, 10)).toBe(255) // beginning of the round-capped dash
+      expect(alpha(73, 10)).toBe(255) // round cap
+      expect(alpha(92, 10)).toBe(255) // the pattern repeats
+
+      // zero-length dashes with round caps become dots
+      ctx.setLineDashAdvanced([{length:0, cap:'round'}, 20])
+      line()
+      expect(alpha(10, 10)).toBe(255)
+      expect(alpha(20, 10)).toBe(0)
+      expect(alpha(30, 10)).toBe(255)
+
+      // plain numbers use the lineCap, odd-length lists are repeated, and the offset is honored
+      ctx.lineCap = 'square'
+      ctx.setLineDashAdvanced([20, 20, {length:20, cap:'butt'}])
+      expect(ctx.getLineDash()).toEqual([20, 20, 20, 20, 20, 20])
+      ctx.lineDashOffset = 20
+      line()
+      expect(alpha(12, 10)).toBe(0)
+      expect(alpha(28, 10)).toBe(0)
+      expect(alpha(32, 10)).toBe(255) // butt-capped dash
+      expect(alpha(67, 10)).toBe(255) // square cap of the following dash
+
+      // the caps are also used by the shape-drawing shorthands
+      ctx.lineDashOffset = 0
+      ctx.setLineDashAdvanced([{length:20, cap:'butt'}, 20, {length:20, cap:'round'}, 20])
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawLine(10, 10, 200, 10)
+      expect(alpha(32, 10)).toBe(0)
+      expect(alpha(47, 10)).toBe(255)
+      expect(alpha(73, 10)).toBe(255)
+
+      // overly dense patterns fall back to the standard dash effect rather than hanging
+      ctx.setLineDashAdvanced([{length:0, cap:'round'}, 0.0001])
+      let start = Date.now()
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawLine(0, 10, 1000, 10)
+      expect(() => ctx.getImageData(0, 0, 1, 1)).not.toThrow()
+      expect(Date.now() - start).toBeLessThan(5000)
+
+      // the standard method clears the per-segment caps
+      ctx.setLineDash([20, 20])
+      ctx.lineCap = 'butt'
+      ctx.lineDashOffset = 0
+      line()
+      expect(alpha(32, 10)).toBe(0)
+      expect(alpha(48, 10)).toBe(0)
+
+      expect(() => ctx.setLineDashAdvanced([{length:10, cap:'pointy'}])).toThrow("Unknown cap style")
+      expect(() => ctx.setLineDashAdvanced(10)).toThrow("Expected an array")
+    })
+
+    test("lineDashOffset", () => {
+      let alpha = (x, y) => pixel(x, y)[3],
+          period = 8;
+      ctx.lineWidth = 2
+      ctx.setLineDash([4, 4])
+
+      // march the ants forward by half a pixel per frame
+      for (let frame=0; frame<60; frame++){
+        let offset = frame * 0.5,
+            coverage = 0;
+        ctx.clearRect(0,0,WIDTH,HEIGHT)
+        ctx.lineDashOffset = offset
+        ctx.beginPath()
+        ctx.moveTo(0, 10)
+        ctx.lineTo(100, 10)
+        ctx.stroke()
+
+        for (let x=0; x<period*2; x++){
+          coverage += alpha(x, 10)
+          if (Number.isInteger(offset)){
+            let on = (x + offset) % period < period/2
+            expect(alpha(x, 10)).toBe(on ? 255 : 0)
+          }
+        }
+
+        // the amount of ink per period should remain constant from frame to frame
+        expect(coverage).toBeGreaterThan(255 * period - 4)
+        expect(coverage).toBeLessThan(255 * period + 4)
+      }
+
+      // large offsets should be equivalent to their remainder within the period
+      let render = offset => {
+        ctx.clearRect(0,0,WIDTH,HEIGHT)
+        ctx.lineDashOffset = offset
+        ctx.stroke()
+        return _.range(period*2).map(x => alpha(x, 10))
+      }
+      expect(render(period * 1000003 + 2)).toEqual(render(2))
+      expect(render(-6)).toEqual(render(2))
+      expect(ctx.lineDashOffset).toBe(-6)
+    })
+
+    test("shadows", () => {
+      let alpha = (x, y) => pixel(x, y)[3]
+      ctx.shadowColor = 'black'
+      ctx.shadowOffsetX = 100
+
+      // a zero blur should produce a crisp shadow
+      ctx.fillRect(0,0,50,50)
+      expect(pixel(149, 20)).toEqual(BLACK)
+      expect(pixel(150, 20)).toEqual(CLEAR)
+
+      // shadowBlur is treated as a radius (the gaussian's std. deviation is half its value)
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.shadowBlur = 20
+      ctx.fillRect(0,100,100,200)
+      expect(alpha(170, 200)).toBeGreaterThan(250)
+      expect(alpha(200, 200)).toBeGreaterThan(100)
+      expect(alpha(200, 200)).toBeLessThan(156)
+      expect(alpha(230, 200)).toBeLessThan(5)
+
+      // rotation shouldn't affect the blur
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.translate(400, 100)
+      ctx.rotate(Math.PI/2)
+      ctx.fillRect(0,0,200,100)
+      expect(alpha(370, 200)).toBeLessThan(5)
+      expect(alpha(400, 200)).toBeGreaterThan(100)
+      expect(alpha(400, 200)).toBeLessThan(156)
+      expect(alpha(430, 200)).toBeGreaterThan(250)
+
+      // nor should mirroring (the offset is always in canvas coordinates)
+      for (const [sx, sy] of [[-1, 1], [1, -1], [-1, -1]]){
+        ctx.clearRect(0,0,WIDTH,HEIGHT)
+        ctx.resetTransform()
+        ctx.translate(sx < 0 ? 100 : 0, sy < 0 ? 400 : 0)
+        ctx.scale(sx, sy)
+        ctx.fillRect(0,100,100,200)
+        expect(alpha(170, 200)).toBeGreaterThan(250)
+        expect(alpha(200, 200)).toBeGreaterThan(100)
+        expect(alpha(200, 200)).toBeLessThan(156)
+        expect(alpha(230, 200)).toBeLessThan(5)
+      }
+
+      // the drop-shadow() filter uses the same convention
+      ctx.resetTransform()
+      ctx.shadowColor = 'transparent'
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.filter = 'drop-shadow(100px 0 20px black)'
+      ctx.fillRect(0,100,100,200)
+      expect(alpha(170, 200)).toBeGreaterThan(250)
+      expect(alpha(200, 200)).toBeGreaterThan(100)
+      expect(alpha(200, 200)).toBeLessThan(156)
+      expect(alpha(230, 200)).toBeLessThan(5)
+
+      // (including its indifference to rotation)
+      let falloff = () => [105, 115, 125].map(x => alpha(x, 200))
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.filter = 'drop-shadow(0 0 20px black)'
+      ctx.fillRect(0,100,100,200)
+      let upright = falloff()
+      expect(upright[0]).toBeGreaterThan(50)
+
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.translate(100, 100)
+      ctx.rotate(Math.PI/2)
+      ctx.fillRect(0,0,200,100)
+      falloff().forEach((a, i) => expect(Math.abs(a - upright[i])).toBeLessThan(4))
+    })
+
+    test("textTracking", () => {
+      let msg = "Lordran gypsum"
+      ctx.font = "20px Arial, DejaVu Sans"
+      let plain = ctx.measureText(msg).width
+
+      ctx.textTracking = 100
+      let loose = ctx.measureText(msg).width
+      expect(ctx.textTracking).toBe(100)
+      expect(loose).toBeGreaterThan(plain + msg.length)
+
+      // spacing is relative to the em size, so changing the font should rescale it
+      ctx.font = "40px Arial, DejaVu Sans"
+      expect(ctx.textTracking).toBe(100)
+      expect(ctx.measureText(msg).width).toBeCloseTo(loose * 2, -1)
     })
 
 
+    test("writingMode", () => {
+      let inked = (x, y, w, h) => ctx.getImageData(x, y, w, h).data.some(a => a)
+      expect(ctx.writingMode).toBe('horizontal-tb')
+      ctx.writingMode = 'sideways'
+      expect(ctx.writingMode).toBe('horizontal-tb')
+
+      let msg = "Lordran gypsum",
+          [x, y] = [WIDTH/2, 20]
+      ctx.font = "20px Arial, DejaVu Sans"
+      ctx.textBaseline = 'middle'
+      let width = ctx.measureText(msg).width
+
+      // text runs top-to-bottom from the anchor point
+      ctx.writingMode = 'vertical-rl'
+      expect(ctx.writingMode).toBe('vertical-rl')
+      ctx.fillText(msg, x, y)
+      expect(inked(x - 10, y, 20, width)).toBe(true)
+      expect(inked(x + 15, y - 10, width, 20)).toBe(false)
+      expect(ctx.measureText(msg).width).toBeCloseTo(width)
+
+      // metrics swap axes: the advance runs down the page and the line's extent runs across it
+      ctx.writingMode = 'horizontal-tb'
+      let across = ctx.measureText(msg)
+      ctx.writingMode = 'vertical-rl'
+      let down = ctx.measureText(msg)
+      expect(down.actualBoundingBoxAscent).toBeCloseTo(across.actualBoundingBoxLeft)
+      expect(down.actualBoundingBoxDescent).toBeCloseTo(across.actualBoundingBoxRight)
+      expect(down.actualBoundingBoxLeft).toBeCloseTo(across.actualBoundingBoxDescent)
+      expect(down.actualBoundingBoxRight).toBeCloseTo(across.actualBoundingBoxAscent)
+      expect(down.lines[0].width).toBeCloseTo(across.lines[0].height)
+      expect(down.lines[0].height).toBeCloseTo(across.lines[0].width)
+
+      // outlines are rotated as well
+      let {bounds} = ctx.outlineText(msg)
+      expect(bounds.height).toBeGreaterThan(bounds.width)
+
+      // wrapped lines are stacked in the direction specified by the mode
+      ctx.textWrap = true
+      for (const [mode, dx] of [['vertical-rl', -24], ['vertical-lr', 24]]){
+        ctx.clearRect(0, 0, WIDTH, HEIGHT)
+        ctx.writingMode = mode
+        ctx.fillText("Lordran\ngypsum", x, y)
+        expect(inked(x + dx - 8, y, 16, width/2)).toBe(true)
+        expect(inked(x - dx - 8, y, 16, width/2)).toBe(false)
+      }
+    })
+
     test("createProjection()", () => {
       let quad = [
         WIDTH*.33, HEIGHT/2,
@@ -744,6 +2491,213 @@ describe("Context2D", ()=>{
       expect(pixel(WIDTH*.25, HEIGHT/2)).toEqual(GREEN)
       expect(pixel(WIDTH*.75, HEIGHT/2)).toEqual(GREEN)
       expect(pixel(WIDTH/2, HEIGHT/2)).toEqual(CLEAR)
+
+      // negative dimensions are normalized and zero-sized rects are ignored
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.drawImage(srcCanvas, 3,3,-3,-3, 6,6,-6,-6)
+      expect(pixel(0, 0)).toEqual(GREEN)
+      expect(pixel(3, 3)).toEqual(CLEAR)
+      expect(pixel(5, 5)).toEqual(GREEN)
+
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      expect(() => ctx.drawImage(image, 0,0,0,10) ).not.toThrow()
+      expect(() => ctx.drawImage(image, 0,0,256,256, 0,0,10,0) ).not.toThrow()
+
+      // but zero-sized sources are an error
+      for (const [w, h] of [[0, 10], [10, 0]]){
+        let empty = new Canvas(w, h)
+        expect(() => ctx.drawImage(empty, 0, 0) ).toThrowError("width or height of zero")
+        expect(() => ctx.drawCanvas(empty, 0, 0) ).toThrowError("width or height of zero")
+      }
+      expect(pixel(0, 0)).toEqual(CLEAR)
+    })
+
+    test('drawImage() with imageSmoothingEnabled', async () => {
+      let image = await loadAsset('checkers.png'),
+          size = 64
+
+      ctx.imageSmoothingEnabled = false
+      ctx.drawImage(image, 0, 0, 4, 4, 0, 0, size, size)
+      expect(ctx.imageSmoothingEnabled).toBe(false)
+
+      ctx.imageSmoothingEnabled = true
+      ctx.drawImage(image, 0, 0, 4, 4, size, 0, size, size)
+      expect(ctx.imageSmoothingEnabled).toBe(true)
+
+      let pixelated = ctx.getImageData(0, 0, size, size).data,
+          smoothed = ctx.getImageData(size, 0, size, size).data
+      expect(pixelated).not.toEqual(smoothed)
+
+      // nearest-neighbor scaling should only produce the source's pure blacks & whites
+      expect(pixelated.every(c => c==0 || c==255)).toBe(true)
+      expect(smoothed.every(c => c==0 || c==255)).toBe(false)
+    })
+
+    test('drawImage() with ImageData', () => {
+      let imgData = new ImageData(4, 4)
+      for (let i=0; i<imgData.data.length; i+=4){
+        imgData.data.set(i < imgData.data.length/2 ? [0, 128, 0, 255] : [255, 255, 255, 128], i)
+      }
+
+      // unlike putImageData, drawing respects the transform and composites with the background
+      ctx.fillStyle = 'black'
+      ctx.fillRect(0, 0, 100, 100)
+      ctx.scale(10, 10)
+      ctx.drawImage(imgData, 1, 1)
+      expect(pixel(15, 15)).toEqual(GREEN)
+      expect(pixel(35, 15)).toEqual(GREEN)
+      expect(pixel(15, 45).every(c => Math.abs(c - 128) <= 1 || c == 255)).toBe(true)
+      expect(pixel(55, 55)).toEqual(BLACK)
+
+      // source & destination rects are supported as well
+      ctx.resetTransform()
+      ctx.drawImage(imgData, 0, 0, 4, 2, 200, 200, 40, 40)
+      expect(pixel(220, 235)).toEqual(GREEN)
+
+      // as are ImageData-like objects
+      let {width, height, data} = imgData
+      ctx.drawImage({width, height, data}, 300, 300)
+      expect(pixel(301, 301)).toEqual(GREEN)
+
+      expect(() => ctx.drawImage(new ImageData(0, 0), 0, 0)).toThrow("width or height of zero")
+    })
+
+    test('drawPath()', () => {
+      let line = new Path2D()
+      line.moveTo(20, 100)
+      line.lineTo(180, 100)
+
+      ctx.lineWidth = 2
+      ctx.strokeStyle = 'black'
+      ctx.drawPath(line, {lineWidth:20, lineCap:'square', strokeStyle:'green'})
+      expect(pixel(100, 108)).toEqual(GREEN)
+      expect(pixel(12, 100)).toEqual(GREEN)
+
+      // the context's own settings are left unchanged
+      expect(ctx.lineWidth).toBe(2)
+      expect(ctx.lineCap).toBe('butt')
+      expect(ctx.strokeStyle).toBe('#000000')
+      ctx.stroke(line)
+      expect(pixel(100, 100)).toEqual(BLACK)
+      expect(pixel(100, 108)).toEqual(GREEN)
+
+      // dashes can be overridden too
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawPath(line, {lineWidth:10, lineDash:[10, 10]})
+      expect(pixel(25, 100)).toEqual(BLACK)
+      expect(pixel(35, 100)).toEqual(CLEAR)
+      expect(ctx.getLineDash()).toEqual([])
+
+      // strokeWidth is accepted as an alias for lineWidth
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawPath(line, {strokeWidth:20, strokeStyle:'green'})
+      expect(pixel(100, 108)).toEqual(GREEN)
+      expect(ctx.lineWidth).toBe(2)
+
+      // and paths can be filled using either winding rule
+      let square = new Path2D()
+      square.rect(50, 50, 100, 100)
+      square.rect(75, 75, 50, 50)
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawPath(square, {style:'fill', fillRule:'evenodd', fillStyle:'green'})
+      expect(pixel(60, 60)).toEqual(GREEN)
+      expect(pixel(100, 100)).toEqual(CLEAR)
+      expect(ctx.fillStyle).toBe('#000000')
+
+      // shader-based styles set on the context survive a one-off override (as do its shadow settings)
+      let gradient = ctx.createLinearGradient(0, 0, WIDTH, 0)
+      ctx.fillStyle = gradient
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawPath(square, {style:'fill', fillStyle:'green', shadowColor:'black', shadowOffsetX:'10px'})
+      expect(pixel(60, 60)).toEqual(GREEN)
+      expect(pixel(155, 60)).toEqual(BLACK)
+      expect(ctx.fillStyle).toBe(gradient)
+      expect(ctx.shadowOffsetX).toBe(0)
+
+      expect(() => ctx.drawPath(line, {style:'smudge'})).toThrowError('Unknown drawPath style')
+      expect(() => ctx.drawPath(line, {font:'12px serif'})).toThrowError('Unknown drawPath option "font"')
+      expect(() => ctx.drawPath([], {})).toThrowError('Expected a Path2D')
+    })
+
+    test('drawYUVFrame()', () => {
+      let frame = (luma, u, v, colorSpace) => ({
+        width:4, height:2, colorSpace,
+        y:new Uint8Array(8).fill(luma),
+        u:new Uint8Array(2).fill(u),
+        v:new Uint8Array(2).fill(v),
+      })
+
+      ctx.drawYUVFrame(frame(235, 128, 128), 0, 0)
+      expect(pixel(0, 0)).toEqual(WHITE)
+      expect(pixel(3, 1)).toEqual(WHITE)
+      expect(pixel(4, 0)).toEqual(CLEAR)
+
+      ctx.drawYUVFrame(frame(16, 128, 128), 0, 0, 40, 20)
+      expect(pixel(39, 19)).toEqual(BLACK)
+
+      // pure red in both the (limited-range) bt709 and (full-range) jpeg color spaces
+      for (const red of [frame(63, 102, 240), frame(76, 85, 255, 'jpeg')]){
+        ctx.drawYUVFrame(red, 0, 0)
+        let [r, g, b] = pixel(1, 1)
+        expect(r).toBeGreaterThan(250)
+        expect(g).toBeLessThan(5)
+        expect(b).toBeLessThan(5)
+      }
+
+      // other byte-sized typed arrays are accepted, as are 16-bit samples
+      let clamped = {...frame(235, 128, 128), y:new Uint8ClampedArray(8).fill(235)}
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawYUVFrame(clamped, 0, 0)
+      expect(pixel(0, 0)).toEqual(WHITE)
+
+      let wide = {width:4, height:2, y:new Uint16Array(8).fill(235 * 257), u:new Uint16Array(2).fill(128 * 257), v:new Uint16Array(2).fill(128 * 257)}
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.drawYUVFrame(wide, 0, 0)
+      expect(pixel(0, 0)).toEqual(WHITE)
+
+      let mixed = {...frame(16, 128, 128), u:new Uint16Array(2)}
+      expect(() => ctx.drawYUVFrame(mixed, 0, 0) ).toThrowError("same bit depth")
+      expect(() => ctx.drawYUVFrame({...frame(16, 128, 128), width:3.5}, 0, 0) ).toThrowError("positive integers")
+
+      let tooSmall = {...frame(16, 128, 128), y:new Uint8Array(4)}
+      expect(() => ctx.drawYUVFrame(tooSmall, 0, 0) ).toThrowError("too small")
+      expect(() => ctx.drawYUVFrame(frame(16, 128, 128, 'rec2020'), 0, 0) ).toThrowError("Unknown YUV color space")
+    })
+
+    test('drawMesh()', async () => {
+      // two triangles sharing a diagonal edge
+      let square = [10,10, 110,10, 110,110, 10,110],
+          quad = [0, 1, 2, 0, 2, 3];
+
+      ctx.fillStyle = 'black'
+      ctx.drawMesh(square, null, null, quad)
+      expect(pixel(20, 100)).toEqual(BLACK)
+      expect(pixel(100, 20)).toEqual(BLACK)
+      expect(pixel(120, 60)).toEqual(CLEAR)
+
+      // vertex colors should be interpolated across the face
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.drawMesh(square, null, ['red', 'red', 'blue', 'blue'], quad)
+      let [r, g, b] = pixel(60, 12)
+      expect(r).toBeGreaterThan(b)
+      ;[r, g, b] = pixel(60, 108)
+      expect(b).toBeGreaterThan(r)
+
+      // texture coordinates sample from the fill pattern
+      let image = await loadAsset('checkers.png')
+      ctx.clearRect(0,0,WIDTH,HEIGHT)
+      ctx.imageSmoothingEnabled = false
+      ctx.fillStyle = ctx.createPattern(image, 'repeat')
+      ctx.drawMesh(new Float32Array(square), [0,0, 100,0, 100,100, 0,100], null, quad)
+      expect(pixel(10, 10)).toEqual(BLACK)
+      expect(pixel(11, 10)).toEqual(WHITE)
+
+      expect(() => ctx.drawMesh([0,0, 10,10]) ).toThrowError("complete triangles")
+      expect(() => ctx.drawMesh(square, null, null, [0, 1, 4]) ).toThrowError("existing vertices")
+      expect(() => ctx.drawMesh(square, null, null, [0, 1, 65538]) ).toThrowError(RangeError)
+      expect(() => ctx.drawMesh(square, null, null, [0, 1, -1]) ).toThrowError("between 0 and 65535")
+      expect(() => ctx.drawMesh(square, null, ['red'], quad) ).toThrowError("one color per vertex")
+      expect(() => ctx.drawMesh(square, null, null, quad, 'invalid') ).toThrowError("Unknown blend mode")
     })
 
     test('drawCanvas()', async () => {
@@ -780,6 +2734,237 @@ describe("Context2D", ()=>{
       expect( () => ctx.drawCanvas(image, 0, 0) ).not.toThrow()
     })
 
+    test('drawPicture()', () => {
+      let srcCanvas = new Canvas(10, 10),
+          srcCtx = srcCanvas.getContext("2d");
+      srcCtx.fillStyle = 'green'
+      srcCtx.fillRect(0, 0, 10, 10)
+
+      ctx.drawPicture(srcCanvas)
+      expect(pixel(5, 5)).toEqual(GREEN)
+      expect(pixel(15, 5)).toEqual(CLEAR)
+
+      // the same recording can be replayed with different transforms
+      ctx.drawPicture(srcCanvas, {translate:[100, 100], scale:3})
+      expect(pixel(125, 125)).toEqual(GREEN)
+      expect(pixel(135, 135)).toEqual(CLEAR)
+
+      ctx.drawPicture(srcCanvas, {translate:[200, 200], rotate:Math.PI, scale:[2, 1]})
+      expect(pixel(185, 195)).toEqual(GREEN)
+      expect(pixel(205, 205)).toEqual(CLEAR)
+
+      ctx.drawPicture(srcCanvas, {matrix:new DOMMatrix().translate(300, 0), translate:[0, 300]})
+      expect(pixel(305, 305)).toEqual(GREEN)
+
+      // the context's own transform is also in effect
+      ctx.save()
+      ctx.translate(400, 0)
+      ctx.drawPicture(srcCanvas, {translate:[0, 400]})
+      ctx.restore()
+      expect(pixel(405, 405)).toEqual(GREEN)
+
+      // alpha is combined with the globalAlpha
+      ctx.globalAlpha = 0.5
+      ctx.drawPicture(srcCanvas, {translate:[0, 50], alpha:0.5})
+      let [r, g, b, a] = pixel(5, 55)
+      expect(g).toBeGreaterThan(0)
+      expect(a).toBeCloseTo(64, -1)
+
+      expect(() => ctx.drawPicture(srcCtx)).toThrowError("Expected a Canvas")
+      expect(() => ctx.drawPicture(new Canvas(0, 10))).toThrowError("width or height of zero")
+    })
+
+    test('compositeWith()', () => {
+      let layer = new Canvas(WIDTH, HEIGHT),
+          layerCtx = layer.getContext("2d");
+      layerCtx.fillStyle = 'rgb(128, 128, 128)'
+      layerCtx.fillRect(0, 0, 20, 20)
+
+      // the blend mode and alpha apply to the layer as a whole
+      ctx.fillStyle = 'white'
+      ctx.fillRect(0, 0, 40, 40)
+      ctx.compositeWith(layerCtx, 'multiply')
+      expect(pixel(10, 10)).toEqual([128, 128, 128, 255])
+      expect(pixel(30, 30)).toEqual(WHITE)
+
+      ctx.compositeWith(layer, 'source-over', 0.5, 20, 20)
+      let [r, g, b, a] = pixel(30, 30)
+      expect(r).toBeCloseTo(191, -1)
+      expect(a).toBe(255)
+
+      // the context's transform & compositing state are ignored
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.translate(100, 100)
+      ctx.globalAlpha = 0.1
+      ctx.globalCompositeOperation = 'destination-over'
+      ctx.compositeWith(layer)
+      expect(pixel(10, 10)).toEqual([128, 128, 128, 255])
+      expect(pixel(110, 110)).toEqual(CLEAR)
+
+      expect(() => ctx.compositeWith(layer, 'plaid')).toThrowError("Unknown blend mode")
+      expect(() => ctx.compositeWith({})).toThrowError("Expected a CanvasRenderingContext2D or Canvas")
+      expect(() => ctx.compositeWith(new Canvas(0, 10))).toThrowError("width or height of zero")
+    })
+
+    test("drawNinePatch()", () => {
+      let src = new Canvas(30, 30),
+          sctx = src.getContext('2d')
+      sctx.fillStyle = 'black'
+      sctx.fillRect(0, 0, 30, 30)
+      sctx.fillStyle = 'white'
+      sctx.fillRect(10, 10, 10, 10)
+
+      // corners stay the same size while the center is stretched to fill the rest
+      ctx.imageSmoothingEnabled = false
+      ctx.drawNinePatch(src, {x:10, y:10, width:10, height:10}, [0, 0, 200, 100])
+      expect(pixel(5, 5)).toEqual(BLACK)
+      expect(pixel(100, 5)).toEqual(BLACK)
+      expect(pixel(5, 50)).toEqual(BLACK)
+      expect(pixel(15, 15)).toEqual(WHITE)
+      expect(pixel(100, 50)).toEqual(WHITE)
+      expect(pixel(185, 85)).toEqual(WHITE)
+      expect(pixel(195, 95)).toEqual(BLACK)
+      expect(pixel(205, 50)).toEqual(CLEAR)
+
+      expect(() => ctx.drawNinePatch({}, [0, 0, 1, 1], [0, 0, 1, 1])).toThrow("Expected an Image or Canvas")
+      expect(() => ctx.drawNinePatch(src, [0, 0, 1, 1])).toThrow("Expected center and destination")
+    })
+
+    test("tileImage()", () => {
+      let src = new Canvas(30, 30),
+          sctx = src.getContext('2d')
+      sctx.fillStyle = 'white'
+      sctx.fillRect(10, 10, 10, 10)
+
+      // the 10×10 white region repeats every 15px (with a 5px gap) across the destination
+      ctx.imageSmoothingEnabled = false
+      ctx.tileImage(src, [10, 10, 10, 10], {x:50, y:50, width:100, height:20}, {xSpacing:5})
+      expect(pixel(55, 55)).toEqual(WHITE)
+      expect(pixel(62, 55)).toEqual(CLEAR)
+      expect(pixel(70, 55)).toEqual(WHITE)
+      expect(pixel(145, 65)).toEqual(WHITE)
+      expect(pixel(155, 55)).toEqual(CLEAR)
+      expect(pixel(55, 45)).toEqual(CLEAR)
+
+      expect(() => ctx.tileImage({}, [0, 0, 1, 1], [0, 0, 1, 1])).toThrow("Expected an Image or Canvas")
+      expect(() => ctx.tileImage(src, [0, 0, 1, 1])).toThrow("Expected source and destination")
+      expect(() => ctx.tileImage(src, [0, 0, 1, 1], [0, 0, 1, 1], {ySpacing:-1})).toThrow("cannot be negative")
+    })
+
+    test("drawSprite()", () => {
+      let sheet = new Canvas(40, 20),
+          sctx = sheet.getContext('2d')
+      sctx.fillStyle = 'white'
+      sctx.fillRect(0, 0, 20, 20)
+      sctx.fillStyle = 'green'
+      sctx.fillRect(20, 0, 20, 20)
+
+      // the second frame is copied at its original size
+      ctx.drawSprite(sheet, [20, 0, 20, 20], 50, 50)
+      expect(pixel(50, 50)).toEqual(GREEN)
+      expect(pixel(69, 69)).toEqual(GREEN)
+      expect(pixel(70, 60)).toEqual(CLEAR)
+      expect(pixel(49, 60)).toEqual(CLEAR)
+
+      // the transform is ignored but alpha is not
+      ctx.scale(3, 3)
+      ctx.globalAlpha = 0.5
+      ctx.drawSprite(sheet, {x:0, y:0, width:20, height:20}, 100.7, 100.2)
+      expect(pixel(100, 100)[3]).toBeCloseTo(128, -1)
+      expect(pixel(119, 119)[3]).toBeCloseTo(128, -1)
+      expect(pixel(120, 110)).toEqual(CLEAR)
+
+      // shadows & composite modes match drawImage's (with the shadow offset in device space)
+      ctx.clearRect(0, 0, WIDTH, HEIGHT)
+      ctx.resetTransform()
+      ctx.scale(2, 2)
+      ctx.globalAlpha = 1
+      ctx.shadowColor = 'black'
+      ctx.shadowOffsetX = 30
+      ctx.drawSprite(sheet, [20, 0, 20, 20], 50, 50)
+      expect(pixel(60, 60)).toEqual(GREEN)
+      expect(pixel(85, 60)).toEqual(BLACK)
+      expect(pixel(100, 60)).toEqual(CLEAR)
+
+      ctx.shadowColor = 'transparent'
+      ctx.globalCompositeOperation = 'source-in'
+      ctx.drawSprite(sheet, [0, 0, 20, 20], 50, 50)
+      expect(pixel(60, 60)).toEqual(WHITE)
+      expect(pixel(85, 60)).toEqual(CLEAR)
+
+      expect(() => ctx.drawSprite({}, [0, 0, 1, 1], 0, 0)).toThrow("Expected an Image or Canvas")
+      expect(() => ctx.drawSprite(sheet)).toThrow("Expected a source rectangle")
+    })
+
+    test('dispose()', () => {
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      expect(pixel(0, 0)).toEqual(BLACK)
+
+      ctx.dispose()
+      expect(() => ctx.dispose()).not.toThrow()
+      expect(() => ctx.fillRect(0, 0, 10, 10)).toThrowError("disposed")
+      expect(() => ctx.getImageData(0, 0, 1, 1)).toThrowError("disposed")
+
+      // property getters & setters are guarded too
+      expect(() => ctx.fillStyle).toThrowError("disposed")
+      expect(() => ctx.lineWidth = 4).toThrowError("disposed")
+
+      // other contexts can no longer draw from the disposed canvas
+      let other = new Canvas(WIDTH, HEIGHT).getContext("2d")
+      expect(() => other.drawImage(canvas, 0, 0)).toThrowError("disposed canvas")
+      expect(() => other.drawCanvas(canvas, 0, 0)).toThrowError("disposed canvas")
+      expect(() => other.drawSprite(canvas, [0, 0, 10, 10], 0, 0)).toThrowError("disposed canvas")
+      expect(() => other.createPattern(canvas, 'repeat')).toThrowError("disposed canvas")
+      expect(() => other.compositeWith(canvas)).toThrowError("disposed canvas")
+
+      // but the canvas can still be exported (as a blank page)
+      expect(() => canvas.toBufferSync('png')).not.toThrow()
+    })
+
+    test('toDataURL()', () => {
+      ctx.fillStyle = 'green'
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+      let png = canvas.toDataURLSync("png"),
+          jpg = canvas.toDataURLSync("jpg"),
+          webp = canvas.toDataURLSync("webp")
+
+      expect(ctx.toDataURL()).toEqual(png)
+      expect(ctx.toDataURL("image/png")).toEqual(png)
+      expect(ctx.toDataURL("image/jpeg")).toEqual(jpg)
+      expect(ctx.toDataURL("image/jpeg", 0.5)).toEqual(canvas.toDataURLSync("jpg", {quality:0.5}))
+      expect(ctx.toDataURL("image/jpeg", 7)).toEqual(jpg)
+      expect(ctx.toDataURL("image/webp")).toEqual(webp)
+      expect(ctx.toDataURL("image/webp", 0.5)).toEqual(canvas.toDataURLSync("webp", {quality:0.5}))
+
+      // unsupported types fall back to png
+      expect(ctx.toDataURL("image/gif")).toEqual(png)
+      expect(ctx.toDataURL("text/plain")).toEqual(png)
+
+      // pages other than the current one can be encoded too
+      let next = canvas.newPage()
+      expect(next.toDataURL()).not.toEqual(png)
+      expect(ctx.toDataURL()).toEqual(png)
+    })
+
+    test('toBlob()', async () => {
+      ctx.fillStyle = 'green'
+      ctx.fillRect(0, 0, WIDTH, HEIGHT)
+
+      let toBlob = (...args) => new Promise(res => ctx.toBlob(res, ...args)),
+          png = await toBlob(),
+          jpg = await toBlob("image/jpeg", 0.5),
+          webp = await toBlob("image/webp")
+
+      expect(png.type).toEqual("image/png")
+      expect(Buffer.from(await png.arrayBuffer())).toEqual(canvas.toBufferSync("png"))
+      expect(jpg.type).toEqual("image/jpeg")
+      expect(Buffer.from(await jpg.arrayBuffer())).toEqual(canvas.toBufferSync("jpg", {quality:0.5}))
+      expect(webp.type).toEqual("image/webp")
+      expect(Buffer.from(await webp.arrayBuffer())).toEqual(canvas.toBufferSync("webp"))
+      expect((await toBlob("image/gif")).type).toEqual("image/png")
+      expect(() => ctx.toBlob("image/png")).toThrowError("Expected a callback function")
+    })
+
     test('reset()', async () => {
       ctx.fillStyle = 'green'
       ctx.scale(2, 2)
@@ -833,6 +3018,13 @@ describe("Context2D", ()=>{
         'italic small-caps bolder 16px cursive': { size: 16, style: 'italic', variant: 'small-caps', weight: 800, family: ['cursive'] },
         '20px "new century schoolbook", serif': { size: 20, family: ['new century schoolbook','serif'] },
         '20px "Arial bold 300"': { size: 20, family: ['Arial bold 300'], variant: 'normal' }, // synthetic case with weight keyword inside family
+        '20px/1.2 Arial': { size: 20, lineHeight: 24, family: ['Arial'] },
+        '20px/normal Arial': { size: 20, lineHeight: 24, family: ['Arial'] },
+        '20px/2 Arial': { size: 20, lineHeight: 40, family: ['Arial'] },
+        '20px/1.5em Arial': { size: 20, lineHeight: 30, family: ['Arial'] },
+        '20px/150% Arial': { size: 20, lineHeight: 30, family: ['Arial'] },
+        '20px/32px Arial': { size: 20, lineHeight: 32, family: ['Arial'] },
+        'bold 20px/18pt Arial': { size: 20, lineHeight: 24, weight: 700, family: ['Arial'] },
       }
 
       _.each(cases, (spec, font) => {
@@ -952,6 +3144,45 @@ describe("Context2D", ()=>{
       ctx.fillStyle = 'hsl(1.24e2, 760e-1%, 4.7e1%)';
       expect(ctx.fillStyle).toBe('#1dd329');
 
+      // css color level 4 functions
+
+      ctx.fillStyle = 'oklch(70% 0.1 0)';
+      expect(ctx.fillStyle).toBe('#d2849c');
+
+      ctx.fillStyle = 'oklch(0.628 0.2577 29.23deg)';
+      expect(ctx.fillStyle).toBe('#ff0000');
+
+      ctx.fillStyle = 'oklch(70% 0.1 none / 50%)';
+      expect(ctx.fillStyle).toBe('rgba(210, 132, 156, 0.502)');
+
+      ctx.fillStyle = 'oklab(1 0 0)';
+      expect(ctx.fillStyle).toBe('#ffffff');
+
+      ctx.fillStyle = 'OKLAB(0% none none)';
+      expect(ctx.fillStyle).toBe('#000000');
+
+      ctx.fillStyle = 'color(srgb 1 0.5 0)';
+      expect(ctx.fillStyle).toBe('#ff8000');
+
+      ctx.fillStyle = 'color(srgb-linear 50% 50% 50%)';
+      expect(ctx.fillStyle).toBe('#bcbcbc');
+
+      ctx.fillStyle = 'color(display-p3 0 1 0)';
+      expect(ctx.fillStyle).toBe('#00ff00');
+
+      ctx.fillStyle = 'hsl(none 100% 50%)';
+      expect(ctx.fillStyle).toBe('#ff0000');
+
+      ctx.fillStyle = 'hsl(0.5turn none 50% / 0.25)';
+      expect(ctx.fillStyle).toBe('rgba(128, 128, 128, 0.251)');
+
+      ctx.shadowColor = 'oklch(70% 0.1 0)';
+      expect(ctx.shadowColor).toBe('#d2849c');
+
+      ctx.fillStyle = '#123456';
+      ctx.fillStyle = 'color(rec2020 1 0 0)';
+      expect(ctx.fillStyle).toBe('#123456');
+
       // case-insensitive css names
 
       ctx.fillStyle = "sILveR";
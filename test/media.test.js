@@ -3,7 +3,9 @@
 const _ = require('lodash'),
       fs = require('fs'),
       glob = require('glob').sync,
+      tmp = require('tmp'),
       {Image, FontLibrary, loadImage} = require('../lib'),
+      FEATURES = require('../lib/v6').Canvas_features(),
       simple = require('simple-get')
 
 jest.mock('simple-get', () => {
@@ -206,5 +208,28 @@ describe("FontLibrary", ()=>{
     expect(FontLibrary.has(name)).toBe(false)
     expect(FontLibrary.has(alias)).toBe(false)
   })
+
+  test("can identify WOFF2 fonts", ()=>{
+    // files with the WOFF2 signature are decompressed (or rejected with an explanation if
+    // the library was built without the `woff2` feature) rather than being passed to skia
+    let woff2 = `${tmp.dirSync().name}/truncated.woff2`
+    fs.writeFileSync(woff2, Buffer.concat([Buffer.from('wOF2'), Buffer.alloc(44)]))
+    expect(() => FontLibrary.use(woff2)).toThrowError(/WOFF2/)
+  })
+
+  test("can register WOFF2 fonts", ()=>{
+    let woff2 = findFont("SourceCodePro-Regular.woff2"),
+        name = "Source Code Pro";
+
+    if (FEATURES.includes('woff2')){
+      expect(() => FontLibrary.use(woff2)).not.toThrow()
+      expect(FontLibrary.has(name)).toBe(true)
+      expect(FontLibrary.family(name).weights).toContain(400)
+      FontLibrary.reset()
+    }else{
+      expect(() => FontLibrary.use(woff2)).toThrowError('compiled with the `woff2` feature')
+      expect(FontLibrary.has(name)).toBe(false)
+    }
+  })
 })
 
@@ -42,6 +42,23 @@ describe("Path2D", ()=>{
       expect(p1.bounds).toMatchObject(p2.bounds)
     })
 
+    test('a context’s current path', () => {
+      ctx.translate(100, 50)
+      ctx.scale(2, 2)
+      ctx.rect(10, 10, 20, 20)
+      ctx.resetTransform()
+      ctx.lineTo(0, 0)
+
+      let p1 = Path2D.transformed(ctx)
+      expect(p1.bounds).toMatchObject({left:0, top:0, right:160, bottom:110})
+
+      // the snapshot is independent of subsequent changes to the context
+      ctx.beginPath()
+      expect(p1.edges.length).toBeGreaterThan(0)
+      expect(Path2D.transformed(ctx).edges).toEqual([])
+      expect(() => Path2D.transformed(p1) ).toThrowError("Expected a CanvasRenderingContext2D")
+    })
+
     test('a stream of edges', () => {
       let p = new Path2D()
 
@@ -168,6 +185,16 @@ describe("Path2D", ()=>{
       expect(pixel(150, 33)).toEqual(BLACK)
       expect(() => p.arcTo(0,0, 20,20) ).toThrowError("Not enough arguments")
       expect(() => p.arcTo(150, 5, null, 'foo', NaN) ).not.toThrow()
+      expect(() => p.arcTo(150, 5, 200, 100, -25) ).toThrowError("radius cannot be negative")
+      expect(() => ctx.arcTo(150, 5, 200, 100, -25) ).toThrowError("radius cannot be negative")
+      expect(() => p.arcTo(150, 5, 200, 100, -25) ).toThrowError(RangeError)
+      expect(() => ctx.arcTo(150, 5, 200, 100, -25) ).toThrowError(RangeError)
+
+      // collinear points degrade to a straight line to the first control point
+      let line = new Path2D()
+      line.moveTo(10, 10)
+      line.arcTo(50, 10, 90, 10, 20)
+      expect(line.edges).toEqual([["moveTo", 10, 10], ["lineTo", 50, 10]])
     })
 
     test("rect", () => {
@@ -221,6 +248,37 @@ describe("Path2D", ()=>{
       expect(pixel(150, 150)).toEqual(WHITE)
       expect(() => p.arc(150, 150, 75, Math.PI/8) ).toThrowError("Not enough arguments")
       expect(() => p.arc(150, 150, 75, Math.PI/8, Math.PI*1.5) ).not.toThrow()
+      expect(() => p.arc(150, 150, -75, Math.PI/8, Math.PI*1.5) ).toThrowError("radius cannot be negative")
+      expect(() => p.arc(150, 150, -75, Math.PI/8, Math.PI*1.5) ).toThrowError(RangeError)
+      expect(() => ctx.arc(150, 150, -75, Math.PI/8, Math.PI*1.5) ).toThrowError(RangeError)
+    })
+
+    test("arc (full circles)", () => {
+      // sweeps of 2π or more in the drawing direction produce a complete circle
+      let circles = [
+        [-Math.PI/2, 1.5*Math.PI, false],
+        [Math.PI, -Math.PI, true],
+        [0, 3*TAU, false],
+        [Math.PI/4, Math.PI/4 - TAU, true],
+      ]
+
+      for (const [start, end, ccw] of circles){
+        scrub()
+        p = new Path2D()
+        p.arc(150, 150, 75, start, end, ccw)
+        ctx.fill(p)
+        expect(pixel(150, 80)).toEqual(BLACK)
+        expect(pixel(150, 220)).toEqual(BLACK)
+        expect(pixel(80, 150)).toEqual(BLACK)
+        expect(pixel(220, 150)).toEqual(BLACK)
+      }
+
+      // while identical start & end angles add no area
+      scrub()
+      p = new Path2D()
+      p.arc(150, 150, 75, Math.PI, Math.PI)
+      ctx.fill(p)
+      expect(pixel(150, 150)).toEqual(CLEAR)
     })
 
     test("ellipse", () => {
@@ -243,6 +301,9 @@ describe("Path2D", ()=>{
       expect(pixel(127, 175)).toEqual(CLEAR)
       expect(pixel(130, 60)).toEqual(CLEAR)
       expect(pixel(163, 100)).toEqual(BLACK)
+
+      expect(() => p.ellipse(100, 100, -100, 50, 0, 0, Math.PI) ).toThrowError(RangeError)
+      expect(() => ctx.ellipse(100, 100, 100, -50, 0, 0, Math.PI) ).toThrowError(RangeError)
     })
   })
 
@@ -398,6 +459,23 @@ describe("Path2D", ()=>{
 
       ctx.fill(c.simplify())
       expect(center()).toEqual(BLACK)
+
+      // self-intersecting paths are reduced to their outline
+      let star = new Path2D()
+      star.moveTo(50, 0)
+      star.lineTo(79, 90)
+      star.lineTo(2, 35)
+      star.lineTo(98, 35)
+      star.lineTo(21, 90)
+      star.closePath()
+      let outline = star.simplify()
+      expect(outline.contains(50, 50)).toBe(true)
+      expect(outline.edges.filter(([verb]) => verb=='lineTo').length).toBeGreaterThan(5)
+      expect(outline.simplify('evenodd').contains(50, 50)).toBe(true)
+
+      // the original path is left unmodified
+      expect(star.simplify('evenodd').contains(50, 50)).toBe(false)
+      expect(star.contains(50, 50)).toBe(true)
     })
 
     test("unwind", () => {
@@ -414,6 +492,38 @@ describe("Path2D", ()=>{
       expect(pixel(215, 55)).toEqual(CLEAR)
     })
 
+    test("reverse", () => {
+      let tri = new Path2D()
+      tri.moveTo(0, 0)
+      tri.lineTo(10, 0)
+      tri.lineTo(10, 10)
+      tri.closePath()
+      expect(tri.reverse().edges).toEqual([
+        ["moveTo", 10, 10], ["lineTo", 10, 0], ["lineTo", 0, 0], ["closePath"]
+      ])
+      expect(tri.edges[0]).toEqual(["moveTo", 0, 0])
+
+      // reversing an inner contour punches a hole even with the nonzero rule
+      let frame = new Path2D(),
+          hole = new Path2D()
+      frame.rect(0, 0, 30, 30)
+      hole.rect(10, 10, 10, 10)
+      frame.addPath(hole)
+      ctx.fill(frame.offset(50, 40))
+      expect(pixel(65, 55)).toEqual(BLACK)
+
+      frame = new Path2D()
+      frame.rect(0, 0, 30, 30)
+      frame.addPath(hole.reverse())
+      ctx.fill(frame.offset(100, 40))
+      expect(pixel(115, 55)).toEqual(CLEAR)
+      expect(pixel(105, 45)).toEqual(BLACK)
+
+      // the fill rule is preserved
+      let evenodd = frame.simplify('evenodd')
+      expect(evenodd.reverse().contains(15, 15)).toBe(evenodd.contains(15, 15))
+    })
+
     test("interpolate", () => {
       let start = new Path2D()
       start.moveTo(100, 100)
@@ -462,6 +572,38 @@ describe("Path2D", ()=>{
       expect(pixel(470, 200)).toEqual(BLACK)
       scrub()
 
+      // mismatched paths report where their commands diverge
+      let other = new Path2D()
+      other.moveTo(300, 100)
+      other.lineTo(400, 200)
+      expect(() => start.interpolate(other, .5)).toThrow("command 1 is bezierCurveTo in one path and lineTo in the other")
+      other.lineTo(300, 300)
+      let line = new Path2D()
+      line.moveTo(0, 0)
+      line.lineTo(10, 10)
+      expect(() => line.interpolate(other, .5)).toThrow("command 2 is missing in one path and lineTo in the other")
+
+    })
+
+    test("equals", () => {
+      expect(a.equals(a)).toBe(true)
+      expect(a.equals(new Path2D(a))).toBe(true)
+      expect(a.equals(new Path2D(a.d))).toBe(true)
+      expect(a.equals(b)).toBe(false)
+      expect(a.equals(a.offset(0, 0.001))).toBe(false)
+      expect(a.equals(a.offset(0, 0.001), 0.01)).toBe(true)
+      expect(a.equals("M 10,50 h 100 v 20 h -100 Z")).toBe(false)
+
+      // same region, different commands
+      let c = new Path2D()
+      c.rect(10, 50, 100, 20)
+      expect(c.equals(new Path2D("M 10,50 v 20 h 100 v -20 Z"))).toBe(false)
+
+      let conic = new Path2D("M 10,10"),
+          other = new Path2D("M 10,10")
+      conic.conicCurveTo(50, 10, 50, 50, 0.5)
+      other.conicCurveTo(50, 10, 50, 50, 2)
+      expect(conic.equals(other)).toBe(false)
     })
 
   })
@@ -486,6 +628,34 @@ describe("Path2D", ()=>{
       expect(notAllBlack).toContain(true)
     })
 
+    test("toStrokePath", () => {
+      let line = new Path2D()
+      line.moveTo(100, 100)
+      line.lineTo(200, 100)
+
+      ctx.lineWidth = 20
+      ctx.lineCap = 'square'
+      let outline = line.toStrokePath(ctx)
+      expect(outline.bounds).toMatchObject({left:90, top:90, right:210, bottom:110})
+      expect(outline.contains(150, 105)).toBe(true)
+      expect(line.contains(150, 105)).toBe(false)
+
+      // filling the outline should match stroking the original
+      ctx.fill(outline)
+      expect(pixel(95, 100)).toEqual(BLACK)
+      expect(pixel(150, 92)).toEqual(BLACK)
+      expect(pixel(150, 115)).toEqual(CLEAR)
+
+      // dashes are included in the outline
+      ctx.setLineDash([20, 20])
+      ctx.lineCap = 'butt'
+      let dashed = line.toStrokePath(ctx)
+      expect(dashed.contains(110, 100)).toBe(true)
+      expect(dashed.contains(130, 100)).toBe(false)
+
+      expect(() => line.toStrokePath(line) ).toThrowError("Expected a CanvasRenderingContext2D")
+    })
+
     test("round", () => {
       // hit by both
       let alpha = () => pixel(50, 220),